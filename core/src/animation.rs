@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::generators::{CarveEvent, Steps};
+use crate::gif::GifEncoder;
+use crate::render::{save_png, RenderOptions, Renderer};
+use crate::solvers::Solver;
+use crate::util::Color;
+use crate::{CellHandle, Grid, Renderable};
+
+/// Background of the cell the generator is at
+const CURRENT: Color = Color::new(255, 96, 96, 255);
+
+/// Background of the row the generator is scanning
+const SCANNING: Color = Color::new(255, 240, 160, 255);
+
+/// Animated export of a maze being carved and then solved
+/// Each carve step is shown with the cell the generator is at highlighted,
+/// then the solver's coloring floods out from the root
+/// one distance at a time, ending on the finished maze
+#[derive(Debug, Copy, Clone)]
+pub struct Animation {
+    options: RenderOptions,
+    frames_per_step: f64,
+    delay: u32,
+    loops: u16,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            options: RenderOptions::default(),
+            frames_per_step: 1.0,
+            delay: 50,
+            loops: 0,
+        }
+    }
+}
+
+impl Animation {
+    /// Renders the frames with the given options
+    pub fn with_render_options(mut self, options: RenderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Shows each step for the given number of frames
+    /// Fractions skip steps, so 0.1 shows every tenth step
+    pub fn with_frames_per_step(mut self, frames_per_step: f64) -> Self {
+        self.frames_per_step = frames_per_step.max(0.0);
+        self
+    }
+
+    /// Shows each frame for the given number of milliseconds
+    /// GIFs only support hundredths of a second
+    pub fn with_delay(mut self, delay: u32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Plays a GIF the given number of times (0 loops forever)
+    pub fn with_loops(mut self, loops: u16) -> Self {
+        self.loops = loops;
+        self
+    }
+
+    /// Saves the animation as a GIF at the given path
+    pub fn save_gif(
+        &self,
+        path: impl AsRef<Path>,
+        steps: Steps,
+        solver: &dyn Solver,
+        root: (usize, usize),
+    ) -> io::Result<()> {
+        let delay = ((self.delay + 5) / 10).max(1) as usize;
+
+        let mut encoder = None;
+        self.frames(steps, solver, root, |size, pixels, frames| {
+            if encoder.is_none() {
+                let writer = io::BufWriter::new(fs::File::create(path.as_ref())?);
+                encoder = Some(GifEncoder::new(writer, size, self.loops)?);
+            }
+
+            encoder.as_mut().unwrap().add_frame(pixels, delay * frames)
+        })?;
+
+        if let Some(encoder) = encoder {
+            encoder.finish()?;
+        }
+
+        Ok(())
+    }
+
+    /// Saves the animation as numbered PNG frames next to the given path
+    /// (maze.png is saved as maze-0000.png, maze-0001.png, ...)
+    /// Steps shown for more than one frame are saved more than once
+    /// Returns the number of frames saved
+    pub fn save_png_frames(
+        &self,
+        path: impl AsRef<Path>,
+        steps: Steps,
+        solver: &dyn Solver,
+        root: (usize, usize),
+    ) -> io::Result<usize> {
+        let path = path.as_ref();
+
+        let mut count = 0;
+        self.frames(steps, solver, root, |size, pixels, frames| {
+            for _ in 0..frames {
+                save_png(frame_path(path, count), size, pixels)?;
+                count += 1;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(count)
+    }
+
+    /// Renders each frame and passes it on with the number of frames to show it for
+    fn frames(
+        &self,
+        mut steps: Steps,
+        solver: &dyn Solver,
+        root: (usize, usize),
+        mut frame: impl FnMut((usize, usize), &[u8], usize) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut step = 0;
+        let mut frames_due = || {
+            let frames = ((step + 1) as f64 * self.frames_per_step).floor()
+                - (step as f64 * self.frames_per_step).floor();
+            step += 1;
+            frames as usize
+        };
+
+        // the empty grid
+        let (size, pixels) = steps.grid().render(&self.options, true);
+        frame(size, &pixels, 1)?;
+
+        // carving the maze
+        while let Some(event) = steps.next() {
+            let frames = frames_due();
+            if frames == 0 {
+                continue;
+            }
+
+            let grid = steps.grid();
+            let mut backgrounds = HashMap::new();
+            if let CarveEvent::Scan(row) = event {
+                for cell in grid.iter().filter(|cell| cell.row() == row) {
+                    backgrounds.insert((cell.row(), cell.col()), SCANNING);
+                }
+            }
+            if let Some(cell) = event.current() {
+                backgrounds.insert((cell.row, cell.col), CURRENT);
+            }
+
+            let highlight = Highlight {
+                grid,
+                backgrounds: &backgrounds,
+            };
+            let (size, pixels) = highlight.render(&self.options, true);
+            frame(size, &pixels, frames)?;
+        }
+
+        // solving the maze
+        let grid = solver.grid();
+        let root = CellHandle::new(root.0, root.1);
+        if grid.get_cell(root).is_some() {
+            let distances = crate::distances(grid, root);
+            let (_, max_distance) = distances.max_distance();
+
+            let mut rings = vec![Vec::new(); max_distance + 1];
+            for cell in grid.iter() {
+                if let Some(distance) = distances.get_distance(&cell.handle()) {
+                    rings[distance].push((cell.row(), cell.col()));
+                }
+            }
+
+            let mut backgrounds = HashMap::new();
+            for ring in rings {
+                for (row, col) in ring {
+                    backgrounds.insert((row, col), solver.cell_background(row, col));
+                }
+
+                let frames = frames_due();
+                if frames == 0 {
+                    continue;
+                }
+
+                let highlight = Highlight {
+                    grid,
+                    backgrounds: &backgrounds,
+                };
+                let (size, pixels) = highlight.render(&self.options, true);
+                frame(size, &pixels, frames)?;
+            }
+        }
+
+        // and holding on the finished maze for a second
+        let hold = (1000 / self.delay.max(1)).max(1) as usize;
+        let (size, pixels) = solver.render(&self.options, true);
+        frame(size, &pixels, hold)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the path of a numbered frame
+fn frame_path(path: &Path, index: usize) -> PathBuf {
+    let mut filename = path.file_stem().unwrap_or_default().to_os_string();
+    filename.push(format!("-{:04}", index));
+
+    let mut frame_path = path.to_owned();
+    frame_path.set_file_name(&filename);
+    frame_path.set_extension(path.extension().unwrap_or_else(|| OsStr::new("png")));
+    frame_path
+}
+
+/// Colors the given cells of a grid, leaving the rest white
+struct Highlight<'a> {
+    grid: &'a Grid,
+    backgrounds: &'a HashMap<(usize, usize), Color>,
+}
+
+impl Solver for Highlight<'_> {
+    fn name(&self) -> &str {
+        "Highlight"
+    }
+
+    fn grid(&self) -> &Grid {
+        self.grid
+    }
+
+    fn cell_background(&self, row: usize, col: usize) -> Color {
+        self.backgrounds
+            .get(&(row, col))
+            .copied()
+            .unwrap_or(Color::WHITE)
+    }
+
+    fn solve(&self, _goal_row: usize, _goal_col: usize) {}
+}
+
+impl Renderable for Highlight<'_> {
+    fn render_ascii(&self) -> String {
+        self.grid.render_ascii_solver(Some(self))
+    }
+
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        self.grid.image_size(options)
+    }
+
+    fn draw(&self, renderer: &mut dyn Renderer, options: &RenderOptions, color: bool) {
+        self.grid.draw_solver(renderer, options, Some(self), color)
+    }
+}
@@ -1,6 +1,8 @@
 use std::collections::hash_set::Iter;
 use std::collections::HashSet;
 
+use rand::RngCore;
+
 use crate::util::sample;
 use crate::Grid;
 
@@ -8,11 +10,27 @@ use crate::Grid;
 pub struct CellHandle {
     pub row: usize,
     pub col: usize,
+
+    // weave grids can have a cell tunneling
+    // under the cell at the same row / col
+    pub under: bool,
 }
 
 impl CellHandle {
     pub fn new(row: usize, col: usize) -> Self {
-        Self { row, col }
+        Self {
+            row,
+            col,
+            under: false,
+        }
+    }
+
+    pub fn new_under(row: usize, col: usize) -> Self {
+        Self {
+            row,
+            col,
+            under: true,
+        }
     }
 
     // not sure why but into() doesn't work even tho From is impl'd
@@ -21,16 +39,13 @@ impl CellHandle {
     }
 
     pub fn get_cell<'a>(&self, grid: &'a Grid) -> Option<&'a Cell> {
-        grid.get(self.row, self.col)
+        grid.get_cell(*self)
     }
 }
 
 impl From<(usize, usize)> for CellHandle {
     fn from(handle: (usize, usize)) -> Self {
-        Self {
-            row: handle.0,
-            col: handle.1,
-        }
+        Self::new(handle.0, handle.1)
     }
 }
 
@@ -38,6 +53,9 @@ impl From<(usize, usize)> for CellHandle {
 pub enum Cell {
     Orthogonal(OrthogonalCell),
     Polar(PolarCell),
+    Hex(HexCell),
+    Triangle(TriangleCell),
+    Layered(LayeredCell),
 }
 
 impl Cell {
@@ -45,14 +63,37 @@ impl Cell {
         Self::Orthogonal(OrthogonalCell::new(row, col))
     }
 
+    pub fn new_under(row: usize, col: usize) -> Self {
+        Self::Orthogonal(OrthogonalCell::new_under(row, col))
+    }
+
     pub fn new_polar(row: usize, col: usize) -> Self {
         Self::Polar(PolarCell::new(row, col))
     }
 
+    pub fn new_hex(row: usize, col: usize) -> Self {
+        Self::Hex(HexCell::new(row, col))
+    }
+
+    pub fn new_triangle(row: usize, col: usize) -> Self {
+        Self::Triangle(TriangleCell::new(row, col))
+    }
+
+    pub fn new_layered(row: usize, col: usize, level: usize) -> Self {
+        Self::Layered(LayeredCell::new(row, col, level))
+    }
+
     pub fn handle(&self) -> CellHandle {
         match self {
-            Self::Orthogonal(cell) => CellHandle::new(cell.row, cell.col),
+            Self::Orthogonal(cell) => CellHandle {
+                row: cell.row,
+                col: cell.col,
+                under: cell.under,
+            },
             Self::Polar(cell) => CellHandle::new(cell.row, cell.col),
+            Self::Hex(cell) => CellHandle::new(cell.row, cell.col),
+            Self::Triangle(cell) => CellHandle::new(cell.row, cell.col),
+            Self::Layered(cell) => CellHandle::new(cell.row, cell.col),
         }
     }
 
@@ -60,6 +101,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.row,
             Self::Polar(cell) => cell.row,
+            Self::Hex(cell) => cell.row,
+            Self::Triangle(cell) => cell.row,
+            Self::Layered(cell) => cell.row,
         }
     }
 
@@ -67,6 +111,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.col,
             Self::Polar(cell) => cell.col,
+            Self::Hex(cell) => cell.col,
+            Self::Triangle(cell) => cell.col,
+            Self::Layered(cell) => cell.col,
         }
     }
 
@@ -74,6 +121,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.has_neighbors(),
             Self::Polar(cell) => cell.has_neighbors(),
+            Self::Hex(cell) => cell.has_neighbors(),
+            Self::Triangle(cell) => cell.has_neighbors(),
+            Self::Layered(cell) => cell.has_neighbors(),
         }
     }
 
@@ -87,12 +137,15 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.neighbors(),
             Self::Polar(cell) => cell.neighbors(),
+            Self::Hex(cell) => cell.neighbors(),
+            Self::Triangle(cell) => cell.neighbors(),
+            Self::Layered(cell) => cell.neighbors(),
         }
     }
 
-    pub fn get_random_neighbor(&self) -> CellHandle {
+    pub fn get_random_neighbor(&self, rng: &mut dyn RngCore) -> CellHandle {
         let neighbors = self.neighbors();
-        *sample(&neighbors)
+        *sample(&neighbors, rng)
     }
 
     // sets this cell as orphaned
@@ -102,6 +155,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.orphan(),
             Self::Polar(cell) => cell.orphan(),
+            Self::Hex(cell) => cell.orphan(),
+            Self::Triangle(cell) => cell.orphan(),
+            Self::Layered(cell) => cell.orphan(),
         }
     }
 
@@ -112,6 +168,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.orphaned(grid),
             Self::Polar(cell) => cell.orphaned(grid),
+            Self::Hex(cell) => cell.orphaned(grid),
+            Self::Triangle(cell) => cell.orphaned(grid),
+            Self::Layered(cell) => cell.orphaned(grid),
         }
     }
 
@@ -121,6 +180,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.links.contains(&other),
             Self::Polar(cell) => cell.links.contains(&other),
+            Self::Hex(cell) => cell.links.contains(&other),
+            Self::Triangle(cell) => cell.links.contains(&other),
+            Self::Layered(cell) => cell.links.contains(&other),
         }
     }
 
@@ -132,6 +194,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.links.insert(other),
             Self::Polar(cell) => cell.links.insert(other),
+            Self::Hex(cell) => cell.links.insert(other),
+            Self::Triangle(cell) => cell.links.insert(other),
+            Self::Layered(cell) => cell.links.insert(other),
         };
     }
 
@@ -143,6 +208,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.links.remove(&other),
             Self::Polar(cell) => cell.links.remove(&other),
+            Self::Hex(cell) => cell.links.remove(&other),
+            Self::Triangle(cell) => cell.links.remove(&other),
+            Self::Layered(cell) => cell.links.remove(&other),
         };
     }
 
@@ -152,6 +220,9 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => !cell.links.is_empty(),
             Self::Polar(cell) => !cell.links.is_empty(),
+            Self::Hex(cell) => !cell.links.is_empty(),
+            Self::Triangle(cell) => !cell.links.is_empty(),
+            Self::Layered(cell) => !cell.links.is_empty(),
         }
     }
 
@@ -161,15 +232,22 @@ impl Cell {
         match self {
             Self::Orthogonal(cell) => cell.links.iter(),
             Self::Polar(cell) => cell.links.iter(),
+            Self::Hex(cell) => cell.links.iter(),
+            Self::Triangle(cell) => cell.links.iter(),
+            Self::Layered(cell) => cell.links.iter(),
         }
     }
 }
 
 #[derive(Debug, Clone)]
-struct OrthogonalCell {
+pub struct OrthogonalCell {
     pub row: usize,
     pub col: usize,
 
+    // under cells tunnel beneath the cell at the same row / col
+    // (see WeaveGrid)
+    pub under: bool,
+
     // track whether we have a neighbor or not
     // (this helps identify edge cells)
     pub north: Option<CellHandle>,
@@ -186,6 +264,7 @@ impl OrthogonalCell {
         Self {
             row,
             col,
+            under: false,
             north: None,
             south: None,
             east: None,
@@ -194,6 +273,31 @@ impl OrthogonalCell {
         }
     }
 
+    fn new_under(row: usize, col: usize) -> Self {
+        Self {
+            under: true,
+            ..Self::new(row, col)
+        }
+    }
+
+    /// Returns true if this cell is a straight east / west passage
+    pub fn is_horizontal_passage(&self) -> bool {
+        let linked = |neighbor: Option<CellHandle>| {
+            neighbor.is_some_and(|neighbor| self.links.contains(&neighbor))
+        };
+
+        linked(self.east) && linked(self.west) && !linked(self.north) && !linked(self.south)
+    }
+
+    /// Returns true if this cell is a straight north / south passage
+    pub fn is_vertical_passage(&self) -> bool {
+        let linked = |neighbor: Option<CellHandle>| {
+            neighbor.is_some_and(|neighbor| self.links.contains(&neighbor))
+        };
+
+        linked(self.north) && linked(self.south) && !linked(self.east) && !linked(self.west)
+    }
+
     fn has_neighbors(&self) -> bool {
         self.north.is_some() || self.south.is_some() || self.east.is_some() || self.west.is_some()
     }
@@ -229,37 +333,29 @@ impl OrthogonalCell {
     }
 
     fn orphaned(&self, grid: &mut Grid) {
-        if let Some(north) = self.north {
-            if let Some(cell) = grid.get_mut(north.row, north.col) {
-                match cell {
-                    Cell::Orthogonal(cell) => cell.south = None,
-                    _ => panic!("Invalid cell type"),
-                }
-            }
-        }
-
-        if let Some(south) = self.south {
-            if let Some(cell) = grid.get_mut(south.row, south.col) {
-                match cell {
-                    Cell::Orthogonal(cell) => cell.north = None,
-                    _ => panic!("Invalid cell type"),
-                }
-            }
-        }
-
-        if let Some(east) = self.east {
-            if let Some(cell) = grid.get_mut(east.row, east.col) {
-                match cell {
-                    Cell::Orthogonal(cell) => cell.west = None,
-                    _ => panic!("Invalid cell type"),
-                }
-            }
-        }
+        let handle = CellHandle {
+            row: self.row,
+            col: self.col,
+            under: self.under,
+        };
 
-        if let Some(west) = self.west {
-            if let Some(cell) = grid.get_mut(west.row, west.col) {
+        // neighbors across folded edges (cube grids)
+        // don't always point back from the opposite direction
+        for neighbor in self.neighbors() {
+            if let Some(cell) = grid.get_cell_mut(neighbor) {
                 match cell {
-                    Cell::Orthogonal(cell) => cell.east = None,
+                    Cell::Orthogonal(cell) => {
+                        for direction in [
+                            &mut cell.north,
+                            &mut cell.south,
+                            &mut cell.east,
+                            &mut cell.west,
+                        ] {
+                            if *direction == Some(handle) {
+                                *direction = None;
+                            }
+                        }
+                    }
                     _ => panic!("Invalid cell type"),
                 }
             }
@@ -329,8 +425,10 @@ impl PolarCell {
     }
 
     fn orphaned(&self, grid: &mut Grid) {
+        let handle = CellHandle::new(self.row, self.col);
+
         if let Some(cw) = self.cw {
-            if let Some(cell) = grid.get_mut(cw.row, cw.col) {
+            if let Some(cell) = grid.get_cell_mut(cw) {
                 match cell {
                     Cell::Polar(cell) => cell.ccw = None,
                     _ => panic!("Invalid cell type"),
@@ -339,7 +437,7 @@ impl PolarCell {
         }
 
         if let Some(ccw) = self.ccw {
-            if let Some(cell) = grid.get_mut(ccw.row, ccw.col) {
+            if let Some(cell) = grid.get_cell_mut(ccw) {
                 match cell {
                     Cell::Polar(cell) => cell.cw = None,
                     _ => panic!("Invalid cell type"),
@@ -348,17 +446,21 @@ impl PolarCell {
         }
 
         if let Some(inward) = self.inward {
-            if let Some(cell) = grid.get_mut(inward.row, inward.col) {
+            if let Some(cell) = grid.get_cell_mut(inward) {
                 match cell {
-                    Cell::Polar(cell) => cell.outward.clear(),
+                    Cell::Polar(cell) => cell.outward.retain(|&outward| outward != handle),
                     _ => panic!("Invalid cell type"),
                 }
             }
         }
 
-        for outward in self.outward {
-            if let Some(cell) = grid.get_mut(outward.row, outward.col) {
+        for outward in &self.outward {
+            if let Some(cell) = grid.get_cell_mut(*outward) {
                 match cell {
+                    // sphere equator cells are outward neighbors of each other
+                    Cell::Polar(cell) if cell.inward != Some(handle) => {
+                        cell.outward.retain(|&outward| outward != handle)
+                    }
                     Cell::Polar(cell) => cell.inward = None,
                     _ => panic!("Invalid cell type"),
                 }
@@ -366,3 +468,414 @@ impl PolarCell {
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct HexCell {
+    pub row: usize,
+    pub col: usize,
+
+    // track whether we have a neighbor or not
+    // (this helps identify edge cells)
+    pub north: Option<CellHandle>,
+    pub south: Option<CellHandle>,
+    pub northeast: Option<CellHandle>,
+    pub northwest: Option<CellHandle>,
+    pub southeast: Option<CellHandle>,
+    pub southwest: Option<CellHandle>,
+
+    // linked cells have no wall between them
+    links: HashSet<CellHandle>,
+}
+
+impl HexCell {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self {
+            row,
+            col,
+            north: None,
+            south: None,
+            northeast: None,
+            northwest: None,
+            southeast: None,
+            southwest: None,
+            links: HashSet::default(),
+        }
+    }
+
+    /// The eastern neighbor in the same row
+    /// Columns zig-zag so this alternates between the southeast and northeast neighbors
+    pub fn east(&self) -> Option<CellHandle> {
+        if self.col.is_multiple_of(2) {
+            self.southeast
+        } else {
+            self.northeast
+        }
+    }
+
+    /// The western neighbor in the same row
+    /// Columns zig-zag so this alternates between the southwest and northwest neighbors
+    pub fn west(&self) -> Option<CellHandle> {
+        if self.col.is_multiple_of(2) {
+            self.southwest
+        } else {
+            self.northwest
+        }
+    }
+
+    fn has_neighbors(&self) -> bool {
+        self.north.is_some()
+            || self.south.is_some()
+            || self.northeast.is_some()
+            || self.northwest.is_some()
+            || self.southeast.is_some()
+            || self.southwest.is_some()
+    }
+
+    // TODO: this could be better if we didn't allocate a vec each time
+    fn neighbors(&self) -> Vec<CellHandle> {
+        let mut neighbors = Vec::with_capacity(6);
+
+        if let Some(neighbor) = self.north {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.south {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.northeast {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.northwest {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.southeast {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.southwest {
+            neighbors.push(neighbor);
+        }
+
+        neighbors
+    }
+
+    fn orphan(&mut self) {
+        self.north = None;
+        self.south = None;
+        self.northeast = None;
+        self.northwest = None;
+        self.southeast = None;
+        self.southwest = None;
+    }
+
+    fn orphaned(&self, grid: &mut Grid) {
+        if let Some(north) = self.north {
+            if let Some(cell) = grid.get_cell_mut(north) {
+                match cell {
+                    Cell::Hex(cell) => cell.south = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(south) = self.south {
+            if let Some(cell) = grid.get_cell_mut(south) {
+                match cell {
+                    Cell::Hex(cell) => cell.north = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(northeast) = self.northeast {
+            if let Some(cell) = grid.get_cell_mut(northeast) {
+                match cell {
+                    Cell::Hex(cell) => cell.southwest = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(northwest) = self.northwest {
+            if let Some(cell) = grid.get_cell_mut(northwest) {
+                match cell {
+                    Cell::Hex(cell) => cell.southeast = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(southeast) = self.southeast {
+            if let Some(cell) = grid.get_cell_mut(southeast) {
+                match cell {
+                    Cell::Hex(cell) => cell.northwest = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(southwest) = self.southwest {
+            if let Some(cell) = grid.get_cell_mut(southwest) {
+                match cell {
+                    Cell::Hex(cell) => cell.northeast = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TriangleCell {
+    pub row: usize,
+    pub col: usize,
+
+    // track whether we have a neighbor or not
+    // (this helps identify edge cells)
+    // upright cells only have a south neighbor
+    // and inverted cells only have a north neighbor
+    pub north: Option<CellHandle>,
+    pub south: Option<CellHandle>,
+    pub east: Option<CellHandle>,
+    pub west: Option<CellHandle>,
+
+    // linked cells have no wall between them
+    links: HashSet<CellHandle>,
+}
+
+impl TriangleCell {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self {
+            row,
+            col,
+            north: None,
+            south: None,
+            east: None,
+            west: None,
+            links: HashSet::default(),
+        }
+    }
+
+    /// Returns true if the cell points up, false if it points down
+    pub fn upright(&self) -> bool {
+        (self.row + self.col).is_multiple_of(2)
+    }
+
+    fn has_neighbors(&self) -> bool {
+        self.north.is_some() || self.south.is_some() || self.east.is_some() || self.west.is_some()
+    }
+
+    // TODO: this could be better if we didn't allocate a vec each time
+    fn neighbors(&self) -> Vec<CellHandle> {
+        let mut neighbors = Vec::with_capacity(3);
+
+        if let Some(neighbor) = self.north {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.south {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.east {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.west {
+            neighbors.push(neighbor);
+        }
+
+        neighbors
+    }
+
+    fn orphan(&mut self) {
+        self.north = None;
+        self.south = None;
+        self.east = None;
+        self.west = None;
+    }
+
+    fn orphaned(&self, grid: &mut Grid) {
+        if let Some(north) = self.north {
+            if let Some(cell) = grid.get_cell_mut(north) {
+                match cell {
+                    Cell::Triangle(cell) => cell.south = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(south) = self.south {
+            if let Some(cell) = grid.get_cell_mut(south) {
+                match cell {
+                    Cell::Triangle(cell) => cell.north = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(east) = self.east {
+            if let Some(cell) = grid.get_cell_mut(east) {
+                match cell {
+                    Cell::Triangle(cell) => cell.west = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(west) = self.west {
+            if let Some(cell) = grid.get_cell_mut(west) {
+                match cell {
+                    Cell::Triangle(cell) => cell.east = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LayeredCell {
+    // rows are stacked by level
+    // so this is the row across all levels
+    pub row: usize,
+    pub col: usize,
+    pub level: usize,
+
+    // track whether we have a neighbor or not
+    // (this helps identify edge cells)
+    pub north: Option<CellHandle>,
+    pub south: Option<CellHandle>,
+    pub east: Option<CellHandle>,
+    pub west: Option<CellHandle>,
+    pub up: Option<CellHandle>,
+    pub down: Option<CellHandle>,
+
+    // linked cells have no wall between them
+    links: HashSet<CellHandle>,
+}
+
+impl LayeredCell {
+    pub fn new(row: usize, col: usize, level: usize) -> Self {
+        Self {
+            row,
+            col,
+            level,
+            north: None,
+            south: None,
+            east: None,
+            west: None,
+            up: None,
+            down: None,
+            links: HashSet::default(),
+        }
+    }
+
+    fn has_neighbors(&self) -> bool {
+        self.north.is_some()
+            || self.south.is_some()
+            || self.east.is_some()
+            || self.west.is_some()
+            || self.up.is_some()
+            || self.down.is_some()
+    }
+
+    // TODO: this could be better if we didn't allocate a vec each time
+    fn neighbors(&self) -> Vec<CellHandle> {
+        let mut neighbors = Vec::with_capacity(6);
+
+        if let Some(neighbor) = self.north {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.south {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.east {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.west {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.up {
+            neighbors.push(neighbor);
+        }
+
+        if let Some(neighbor) = self.down {
+            neighbors.push(neighbor);
+        }
+
+        neighbors
+    }
+
+    fn orphan(&mut self) {
+        self.north = None;
+        self.south = None;
+        self.east = None;
+        self.west = None;
+        self.up = None;
+        self.down = None;
+    }
+
+    fn orphaned(&self, grid: &mut Grid) {
+        if let Some(north) = self.north {
+            if let Some(cell) = grid.get_cell_mut(north) {
+                match cell {
+                    Cell::Layered(cell) => cell.south = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(south) = self.south {
+            if let Some(cell) = grid.get_cell_mut(south) {
+                match cell {
+                    Cell::Layered(cell) => cell.north = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(east) = self.east {
+            if let Some(cell) = grid.get_cell_mut(east) {
+                match cell {
+                    Cell::Layered(cell) => cell.west = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(west) = self.west {
+            if let Some(cell) = grid.get_cell_mut(west) {
+                match cell {
+                    Cell::Layered(cell) => cell.east = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(up) = self.up {
+            if let Some(cell) = grid.get_cell_mut(up) {
+                match cell {
+                    Cell::Layered(cell) => cell.down = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+
+        if let Some(down) = self.down {
+            if let Some(cell) = grid.get_cell_mut(down) {
+                match cell {
+                    Cell::Layered(cell) => cell.up = None,
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+    }
+}
@@ -43,6 +43,13 @@ impl Distances {
         (max_cell, max_distance)
     }
 
+    /// Returns the cells in order of their distance from the root
+    pub fn cells_by_distance(&self) -> Vec<CellHandle> {
+        let mut cells = self.cells.iter().collect::<Vec<_>>();
+        cells.sort_by_key(|(_, distance)| **distance);
+        cells.into_iter().map(|(cell, _)| *cell).collect()
+    }
+
     /*pub fn cells(&self) -> Keys<'_, CellHandle, usize> {
         self.cells.keys()
     }*/
@@ -50,7 +57,7 @@ impl Distances {
 
 /// Computes the distance from the root cell to every other cell
 pub fn distances(grid: &Grid, root: CellHandle) -> Distances {
-    assert!(grid.get(root.row, root.col).is_some());
+    assert!(grid.get_cell(root).is_some());
 
     let mut distances = Distances::new(root);
     let mut frontier = vec![root];
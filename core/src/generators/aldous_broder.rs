@@ -1,6 +1,8 @@
+use rand::RngCore;
+
 use crate::Grid;
 
-use super::Generator;
+use super::{link, start_cells, CarveEvent, Generator, Progress, StopCondition};
 
 /// Aldous-Broder maze generator
 /// Selects random cells, including cells already visited, to visit until all of them are visited
@@ -16,17 +18,25 @@ impl Generator for AldousBroder {
         "Aldous-Broder"
     }
 
-    fn run(&self, grid: &mut Grid) {
-        let mut cell_handle = grid.get_random().handle();
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let mut cell_handle = start_cells(grid, rng)[0];
+        events(CarveEvent::Visit(cell_handle));
 
-        let mut unvisited = grid.enabled_count() - 1;
-        while unvisited > 0 {
-            let cell = cell_handle.get_cell(grid).unwrap();
-            let neighbor_handle = cell.get_random_neighbor();
+        let mut progress = Progress::new(grid, stop);
+        while !progress.is_done() {
+            let neighbor_handle = grid.get_random_neighbor(cell_handle, rng);
             let neighbor = neighbor_handle.get_cell(grid).unwrap();
             if !neighbor.has_links() {
-                grid.link_cells(cell_handle, neighbor_handle);
-                unvisited -= 1;
+                link(grid, cell_handle, neighbor_handle, events);
+                progress.visit();
+            } else {
+                events(CarveEvent::Visit(neighbor_handle));
             }
 
             cell_handle = neighbor_handle;
@@ -1,37 +1,133 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::RngCore;
+
 use crate::util::sample;
-use crate::{Cell, CellHandle, Grid};
+use crate::{Cell, CellHandle, Grid, GridType};
+
+use super::{has_links, link_multi, CarveEvent, Generator, StopCondition};
+
+/// The diagonal a binary tree maze leans toward
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Diagonal {
+    #[default]
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl Diagonal {
+    fn is_north(&self) -> bool {
+        matches!(self, Self::NorthEast | Self::NorthWest)
+    }
+
+    fn is_east(&self) -> bool {
+        matches!(self, Self::NorthEast | Self::SouthEast)
+    }
+}
+
+impl fmt::Display for Diagonal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NorthEast => write!(f, "northeast"),
+            Self::NorthWest => write!(f, "northwest"),
+            Self::SouthEast => write!(f, "southeast"),
+            Self::SouthWest => write!(f, "southwest"),
+        }
+    }
+}
 
-use super::Generator;
+impl FromStr for Diagonal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "northeast" | "ne" => Ok(Self::NorthEast),
+            "northwest" | "nw" => Ok(Self::NorthWest),
+            "southeast" | "se" => Ok(Self::SouthEast),
+            "southwest" | "sw" => Ok(Self::SouthWest),
+            _ => Err(format!("Invalid diagonal: {}", s)),
+        }
+    }
+}
 
 /// BinaryTree maze generator
-/// Selects random N/E neighbors to link
+/// Selects random neighbors along a diagonal (N/E by default) to link
+/// (inward / clockwise neighbors on polar grids, south being outward)
+/// (N / same row E neighbors on hex grids)
 /// Perfect - Yes
 /// Uniform - No
-/// Bias - North / East edge paths are always unbroken
+/// Bias - Edge paths along the diagonal are always unbroken
 /// Runtime - Fast
+/// Only orthogonal, polar, hex and weave grids are supported
+/// and grids that are already linked are left as they are
 #[derive(Debug, Default)]
-pub struct BinaryTree;
+pub struct BinaryTree {
+    diagonal: Diagonal,
+}
 
 impl BinaryTree {
-    /// Pick a random N/E neighbor to link
-    pub(crate) fn choose_neighbor(cell: &Cell) -> Option<CellHandle> {
+    /// Leans the maze toward the given diagonal
+    pub fn with_diagonal(mut self, diagonal: Diagonal) -> Self {
+        self.diagonal = diagonal;
+        self
+    }
+
+    /// Pick a random neighbor along the diagonal to link
+    pub(crate) fn choose_neighbor(&self, cell: &Cell, rng: &mut dyn RngCore) -> Option<CellHandle> {
         // TODO: do this without allocating
 
         let mut neighbors = Vec::with_capacity(2);
 
-        if let Some(north) = cell.north {
-            neighbors.push(north);
-        }
+        let (north, east) = (self.diagonal.is_north(), self.diagonal.is_east());
+        match cell {
+            Cell::Orthogonal(cell) => {
+                if let Some(vertical) = if north { cell.north } else { cell.south } {
+                    neighbors.push(vertical);
+                }
+
+                if let Some(horizontal) = if east { cell.east } else { cell.west } {
+                    neighbors.push(horizontal);
+                }
+            }
+            Cell::Polar(cell) => {
+                if north {
+                    if let Some(inward) = cell.inward {
+                        neighbors.push(inward);
+                    }
+                } else if !cell.outward.is_empty() {
+                    neighbors.push(*sample(&cell.outward, rng));
+                }
+
+                // the first / last cell in the ring can't wrap around
+                // (the same as the boundary of an orthogonal grid)
+                if east {
+                    if let Some(cw) = cell.cw.filter(|cw| cw.col > cell.col) {
+                        neighbors.push(cw);
+                    }
+                } else if let Some(ccw) = cell.ccw.filter(|ccw| ccw.col < cell.col) {
+                    neighbors.push(ccw);
+                }
+            }
+            Cell::Hex(cell) => {
+                if let Some(vertical) = if north { cell.north } else { cell.south } {
+                    neighbors.push(vertical);
+                }
 
-        if let Some(east) = cell.east {
-            neighbors.push(east);
+                if let Some(horizontal) = if east { cell.east() } else { cell.west() } {
+                    neighbors.push(horizontal);
+                }
+            }
+            Cell::Triangle(_) | Cell::Layered(_) => (),
         }
 
         if neighbors.is_empty() {
             return None;
         }
 
-        let neighbor = *sample(&neighbors);
+        let neighbor = *sample(&neighbors, rng);
         Some(neighbor)
     }
 }
@@ -41,20 +137,38 @@ impl Generator for BinaryTree {
         "Binary Tree"
     }
 
-    fn run(&self, grid: &mut Grid) {
-        if grid.has_orphans() {
+    fn supports(&self, grid: &Grid) -> bool {
+        // this only produces a perfect maze when every cell but one
+        // has a neighbor along the diagonal
+        // and nothing is linked yet
+        !grid.has_orphans()
+            && !has_links(grid)
+            && matches!(
+                grid.grid_type(),
+                GridType::Orthogonal | GridType::Polar | GridType::Hex | GridType::Weave
+            )
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        if !self.supports(grid) {
             return;
         }
 
         let links = grid
             .iter()
             .filter_map(|cell| {
-                if let Some(neighbor) = Self::choose_neighbor(cell) {
+                if let Some(neighbor) = self.choose_neighbor(cell, rng) {
                     return Some((cell.handle(), neighbor));
                 }
                 None
             })
             .collect::<Vec<(CellHandle, CellHandle)>>();
-        grid.link_cells_multi(links);
+        link_multi(grid, links, events);
     }
 }
@@ -1,38 +1,66 @@
+use rand::{Rng, RngCore};
 use rayon::prelude::*;
 
-use crate::{CellHandle, Grid};
+use crate::util::stream_rng;
+use crate::{Cell, CellHandle, Grid};
 
-use super::{BinaryTree, Generator};
+use super::{link_multi, BinaryTree, CarveEvent, Diagonal, Generator, StopCondition};
 
 /// BinaryTree maze generator (parallelized)
-/// Selects random N/E neighbors to link
+/// Selects random neighbors along a diagonal (N/E by default) to link
 /// Perfect - Yes
 /// Uniform - No
-/// Bias - North / East edge paths are always unbroken
+/// Bias - Edge paths along the diagonal are always unbroken
 /// Runtime - Fast
+/// Only orthogonal, polar, hex and weave grids are supported
+/// and grids that are already linked are left as they are
 #[derive(Debug, Default)]
-pub struct BinaryTreeParallel;
+pub struct BinaryTreeParallel {
+    binary_tree: BinaryTree,
+}
+
+impl BinaryTreeParallel {
+    /// Leans the maze toward the given diagonal
+    pub fn with_diagonal(mut self, diagonal: Diagonal) -> Self {
+        self.binary_tree = self.binary_tree.with_diagonal(diagonal);
+        self
+    }
+}
 
 impl Generator for BinaryTreeParallel {
     fn name(&self) -> &str {
         "Binary Tree (Parallel)"
     }
 
-    fn run(&self, grid: &mut Grid) {
-        if grid.has_orphans() {
+    fn supports(&self, grid: &Grid) -> bool {
+        self.binary_tree.supports(grid)
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        if !self.supports(grid) {
             return;
         }
 
+        let seed = rng.gen();
         let links = grid
             .iter()
-            .par_bridge()
-            .filter_map(|cell| {
-                if let Some(neighbor) = BinaryTree::choose_neighbor(cell) {
+            .collect::<Vec<&Cell>>()
+            .par_iter()
+            .enumerate()
+            .filter_map(|(index, cell)| {
+                let mut rng = stream_rng(seed, index as u64);
+                if let Some(neighbor) = self.binary_tree.choose_neighbor(cell, &mut rng) {
                     return Some((cell.handle(), neighbor));
                 }
                 None
             })
             .collect::<Vec<(CellHandle, CellHandle)>>();
-        grid.link_cells_multi(links);
+        link_multi(grid, links, events);
     }
 }
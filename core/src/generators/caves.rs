@@ -0,0 +1,371 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, RngCore};
+
+use crate::{CellHandle, Grid, GridType};
+
+use super::{has_links, link_multi, CarveEvent, Generator, StopCondition};
+
+/// Cellular automaton birth / survival rules (B678/S345678)
+/// Live cells are cave walls and each digit is a count of the 8 surrounding walls
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CaveRules {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+impl CaveRules {
+    /// Creates rules from the wall counts that create and keep walls
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let mut rules = Self {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+
+        for &count in birth.iter().filter(|count| **count <= 8) {
+            rules.birth[count] = true;
+        }
+
+        for &count in survival.iter().filter(|count| **count <= 8) {
+            rules.survival[count] = true;
+        }
+
+        rules
+    }
+
+    /// Returns true if the cell is a wall in the next generation
+    fn is_wall(&self, wall: bool, walls: usize) -> bool {
+        if wall {
+            self.survival[walls]
+        } else {
+            self.birth[walls]
+        }
+    }
+}
+
+impl Default for CaveRules {
+    fn default() -> Self {
+        Self::new(&[6, 7, 8], &[3, 4, 5, 6, 7, 8])
+    }
+}
+
+impl fmt::Display for CaveRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| {
+            (0..=8)
+                .filter(|count| counts[*count])
+                .map(|count| count.to_string())
+                .collect::<String>()
+        };
+
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+impl FromStr for CaveRules {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = |rule: &str, prefix: char| {
+            rule.strip_prefix(prefix)
+                .or_else(|| rule.strip_prefix(prefix.to_ascii_lowercase()))?
+                .chars()
+                .map(|count| count.to_digit(10).filter(|count| *count <= 8))
+                .map(|count| count.map(|count| count as usize))
+                .collect::<Option<Vec<usize>>>()
+        };
+
+        let (birth, survival) = s
+            .split_once('/')
+            .and_then(|(birth, survival)| Some((digits(birth, 'B')?, digits(survival, 'S')?)))
+            .ok_or_else(|| format!("Invalid cave rules: {}", s))?;
+
+        Ok(Self::new(&birth, &survival))
+    }
+}
+
+/// Cellular automaton cave generator
+/// Randomly fills the grid with walls and runs the automaton rules
+/// to grow open caverns, then tunnels between them so they're all reachable
+/// Cave floors are linked to each other and walls are orphaned
+/// Perfect - No
+/// Uniform - No
+/// Bias - Large open areas
+/// Runtime - Fast
+/// Only orthogonal grids that aren't already linked are supported
+#[derive(Debug)]
+pub struct Caves {
+    rules: CaveRules,
+    wall_chance: f64,
+    steps: usize,
+}
+
+impl Default for Caves {
+    fn default() -> Self {
+        Self {
+            rules: CaveRules::default(),
+            wall_chance: 0.45,
+            steps: 4,
+        }
+    }
+}
+
+impl Caves {
+    /// Runs the automaton with the given rules
+    pub fn with_rules(mut self, rules: CaveRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Starts each cell as a wall with the given chance
+    pub fn with_wall_chance(mut self, wall_chance: f64) -> Self {
+        self.wall_chance = wall_chance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Runs the automaton for the given number of generations
+    pub fn with_steps(mut self, steps: usize) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Runs one generation of the automaton
+    /// Masked cells and cells past the edges of the grid count as walls
+    fn step(&self, walls: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        let is_wall = |row: isize, col: isize| {
+            row < 0
+                || col < 0
+                || walls
+                    .get(row as usize)
+                    .and_then(|cells| cells.get(col as usize))
+                    .copied()
+                    .unwrap_or(true)
+        };
+
+        walls
+            .iter()
+            .enumerate()
+            .map(|(row, cells)| {
+                (0..cells.len())
+                    .map(|col| {
+                        let (row, col) = (row as isize, col as isize);
+                        let count = (-1..=1)
+                            .flat_map(|y| (-1..=1).map(move |x| (y, x)))
+                            .filter(|&(y, x)| (y, x) != (0, 0) && is_wall(row + y, col + x))
+                            .count();
+
+                        self.rules.is_wall(is_wall(row, col), count)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the floor cells in the grid
+    fn floors(grid: &Grid, walls: &[Vec<bool>]) -> Vec<(usize, usize)> {
+        grid.iter()
+            .map(|cell| cell.handle().unpack())
+            .filter(|(row, col)| !walls[*row][*col])
+            .collect()
+    }
+
+    /// Returns the floor cells reachable from the given floor cell
+    fn flood(walls: &[Vec<bool>], start: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut seen = vec![vec![false; walls[0].len()]; walls.len()];
+        seen[start.0][start.1] = true;
+
+        let mut region = Vec::new();
+        let mut frontier = VecDeque::from([start]);
+        while let Some(cell) = frontier.pop_front() {
+            region.push(cell);
+
+            for neighbor in Self::adjacent(walls, cell) {
+                if !walls[neighbor.0][neighbor.1] && !seen[neighbor.0][neighbor.1] {
+                    seen[neighbor.0][neighbor.1] = true;
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        region
+    }
+
+    /// Returns the cells north, south, east and west of the given cell
+    fn adjacent(
+        walls: &[Vec<bool>],
+        (row, col): (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (rows, cols) = (walls.len(), walls[0].len());
+        [
+            row.checked_sub(1).map(|row| (row, col)),
+            (row + 1 < rows).then(|| (row + 1, col)),
+            col.checked_sub(1).map(|col| (row, col)),
+            (col + 1 < cols).then(|| (row, col + 1)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Tunnels through walls from the region to the closest connected cell
+    /// Returns false if the region can't reach the connected cells
+    fn connect(
+        grid: &Grid,
+        walls: &mut [Vec<bool>],
+        connected: &[Vec<bool>],
+        region: &[(usize, usize)],
+    ) -> bool {
+        let mut previous = vec![vec![None; walls[0].len()]; walls.len()];
+        let mut frontier = region.iter().copied().collect::<VecDeque<(usize, usize)>>();
+        for &(row, col) in region {
+            previous[row][col] = Some((row, col));
+        }
+
+        while let Some(cell) = frontier.pop_front() {
+            if connected[cell.0][cell.1] {
+                // carve the tunnel back to the region
+                let mut cell = cell;
+                while previous[cell.0][cell.1] != Some(cell) {
+                    walls[cell.0][cell.1] = false;
+                    cell = previous[cell.0][cell.1].unwrap();
+                }
+                return true;
+            }
+
+            // masked cells can't be tunneled through
+            for neighbor in Self::adjacent(walls, cell) {
+                if previous[neighbor.0][neighbor.1].is_none()
+                    && grid.get(neighbor.0, neighbor.1).is_some()
+                {
+                    previous[neighbor.0][neighbor.1] = Some(cell);
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl Generator for Caves {
+    fn name(&self) -> &str {
+        "Caves"
+    }
+
+    fn supports(&self, grid: &Grid) -> bool {
+        grid.grid_type() == GridType::Orthogonal && !has_links(grid)
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        if !self.supports(grid) {
+            return;
+        }
+
+        let mut walls = grid
+            .rows_iter()
+            .map(|cells| {
+                cells
+                    .iter()
+                    .map(|cell| cell.is_none() || rng.gen_bool(self.wall_chance))
+                    .collect::<Vec<bool>>()
+            })
+            .collect::<Vec<Vec<bool>>>();
+
+        for _ in 0..self.steps {
+            walls = self.step(&walls);
+        }
+
+        // masked cells stay walls no matter what the rules say
+        for (row, cells) in walls.iter_mut().enumerate() {
+            for (col, wall) in cells.iter_mut().enumerate() {
+                *wall |= grid.get(row, col).is_none();
+            }
+        }
+
+        // always leave somewhere to go
+        let mut cells = Self::floors(grid, &walls);
+        if cells.is_empty() {
+            let (row, col) = grid.get_random(rng).handle().unpack();
+            walls[row][col] = false;
+            cells.push((row, col));
+        }
+
+        if cells.len() == 1 {
+            let handle = CellHandle::new(cells[0].0, cells[0].1);
+            let (row, col) = grid.get_random_neighbor(handle, rng).unpack();
+            walls[row][col] = false;
+            cells.push((row, col));
+        }
+
+        // the largest cavern is where everything else connects to
+        let mut regions = Vec::new();
+        let mut in_region = vec![vec![false; walls[0].len()]; walls.len()];
+        for cell in cells {
+            if !in_region[cell.0][cell.1] {
+                let region = Self::flood(&walls, cell);
+                for &(row, col) in &region {
+                    in_region[row][col] = true;
+                }
+                regions.push(region);
+            }
+        }
+        regions.sort_by_key(|region| std::cmp::Reverse(region.len()));
+
+        let mut connected = vec![vec![false; walls[0].len()]; walls.len()];
+        for &(row, col) in &Self::flood(&walls, regions[0][0]) {
+            connected[row][col] = true;
+        }
+
+        for region in regions.iter().skip(1) {
+            let (row, col) = region[0];
+            if connected[row][col] {
+                continue;
+            }
+
+            // regions that can't be reached are filled in
+            if !Self::connect(grid, &mut walls, &connected, region) {
+                for &(row, col) in region {
+                    walls[row][col] = true;
+                }
+                continue;
+            }
+
+            for &(row, col) in &Self::flood(&walls, region[0]) {
+                connected[row][col] = true;
+            }
+        }
+
+        // open up the floors and orphan the walls
+        let mut links = Vec::new();
+        for (row, col) in Self::floors(grid, &walls) {
+            let handle = CellHandle::new(row, col);
+            for (neighbor_row, neighbor_col) in [(row + 1, col), (row, col + 1)] {
+                let floor = walls
+                    .get(neighbor_row)
+                    .and_then(|cells| cells.get(neighbor_col))
+                    .is_some_and(|wall| !wall);
+                if floor {
+                    links.push((handle, CellHandle::new(neighbor_row, neighbor_col)));
+                }
+            }
+        }
+        link_multi(grid, links, events);
+
+        let orphans = grid
+            .iter()
+            .map(|cell| cell.handle().unpack())
+            .filter(|(row, col)| walls[*row][*col])
+            .collect::<Vec<(usize, usize)>>();
+        for (row, col) in orphans {
+            grid.orphan(row, col);
+            events(CarveEvent::Orphan(CellHandle::new(row, col)));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use rand::RngCore;
+
+use crate::Grid;
+
+use super::{visited_count, CarveEvent, Generator, StopCondition};
+
+/// Chained maze generator
+/// Runs a sequence of generators on the same grid,
+/// each until its stop condition is met
+/// Later generators continue from the cells the earlier ones visited,
+/// so the last one should usually run until the maze is complete
+/// The stop condition of the whole chain cuts short whichever stage it's met in
+pub struct Chain {
+    name: String,
+    stages: Vec<(Box<dyn Generator>, StopCondition)>,
+}
+
+impl Chain {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            stages: Vec::new(),
+        }
+    }
+
+    /// Runs the given generator after the previous ones
+    pub fn then(mut self, generator: impl Generator + 'static, stop: StopCondition) -> Self {
+        self.stages.push((Box::new(generator), stop));
+        self
+    }
+}
+
+impl Generator for Chain {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let visited = visited_count(grid);
+
+        for (generator, stage_stop) in &self.stages {
+            // each stage stops at whichever condition it meets first,
+            // counting the steps of the whole chain towards the chain's condition
+            let steps = visited_count(grid) - visited;
+            let remaining = [stop.remaining(grid, steps), stage_stop.remaining(grid, 0)]
+                .into_iter()
+                .flatten()
+                .min();
+
+            generator.run_events(
+                grid,
+                rng,
+                remaining.map_or(StopCondition::Complete, StopCondition::Steps),
+                events,
+            );
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use crate::stream::MazeRow;
+use crate::util::coin;
+use crate::{CellHandle, Grid, GridType};
+
+use super::{has_links, link_multi, CarveEvent, Generator, StopCondition};
+
+/// Eller's maze rows
+/// Carves one row at a time, only keeping track of
+/// which set each cell in the current row belongs to
+pub struct EllersRows<'a> {
+    rows: usize,
+    cols: usize,
+    row: usize,
+
+    sets: Vec<usize>,
+    next_set: usize,
+
+    rng: &'a mut dyn RngCore,
+}
+
+impl<'a> EllersRows<'a> {
+    pub fn new(rows: usize, cols: usize, rng: &'a mut dyn RngCore) -> Self {
+        Self {
+            rows,
+            cols,
+            row: 0,
+            sets: (0..cols).collect(),
+            next_set: cols,
+            rng,
+        }
+    }
+}
+
+impl Iterator for EllersRows<'_> {
+    type Item = MazeRow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row >= self.rows {
+            return None;
+        }
+
+        // the last row has to join every set that's left
+        let last_row = self.row == self.rows - 1;
+
+        let mut east = vec![false; self.cols];
+        let joinable = self.cols.saturating_sub(1);
+        for (col, linked) in east.iter_mut().enumerate().take(joinable) {
+            let (set, east_set) = (self.sets[col], self.sets[col + 1]);
+            if set != east_set && (last_row || coin(self.rng)) {
+                *linked = true;
+
+                for member in self.sets.iter_mut().filter(|member| **member == east_set) {
+                    *member = set;
+                }
+            }
+        }
+
+        let mut south = vec![false; self.cols];
+        if !last_row {
+            // ordered so that the same seed carves the same row
+            let mut members = BTreeMap::new();
+            for (col, set) in self.sets.iter().enumerate() {
+                members.entry(*set).or_insert_with(Vec::new).push(col);
+            }
+
+            // every set has to continue south at least once
+            for cols in members.values_mut() {
+                cols.shuffle(self.rng);
+                for (index, col) in cols.iter().enumerate() {
+                    if index == 0 || self.rng.gen_range(0..3) == 0 {
+                        south[*col] = true;
+                    }
+                }
+            }
+
+            // cells that didn't continue south start new sets
+            for (col, set) in self.sets.iter_mut().enumerate() {
+                if !south[col] {
+                    *set = self.next_set;
+                    self.next_set += 1;
+                }
+            }
+        }
+
+        self.row += 1;
+
+        Some(MazeRow { east, south })
+    }
+}
+
+/// Eller's maze generator
+/// Carves one row at a time, randomly joining adjacent sets of cells
+/// and continuing every set into the next row
+/// Perfect - Yes
+/// Uniform - No
+/// Bias - Horizontal passages are a little more common
+/// Runtime - Fast
+/// Only unmasked orthogonal grids that aren't already linked are supported
+/// (EllersRows can stream mazes too big to keep in memory)
+#[derive(Debug, Default)]
+pub struct Ellers;
+
+impl Generator for Ellers {
+    fn name(&self) -> &str {
+        "Eller's"
+    }
+
+    fn supports(&self, grid: &Grid) -> bool {
+        // masked out cells would break up the rows
+        let rows = grid.rows_iter().len();
+        let cols = grid.rows_iter().next().map_or(0, Vec::len);
+        grid.grid_type() == GridType::Orthogonal
+            && grid.iter().count() == rows * cols
+            && !has_links(grid)
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        if !self.supports(grid) {
+            return;
+        }
+
+        let rows = grid.rows_iter().len();
+        let cols = grid.rows_iter().next().map_or(0, Vec::len);
+
+        let links = EllersRows::new(rows, cols, rng)
+            .enumerate()
+            .flat_map(|(row, maze_row)| {
+                let mut links = Vec::new();
+                for col in 0..cols {
+                    let handle = CellHandle::new(row, col);
+                    if maze_row.east[col] {
+                        links.push((handle, CellHandle::new(row, col + 1)));
+                    }
+                    if maze_row.south[col] {
+                        links.push((handle, CellHandle::new(row + 1, col)));
+                    }
+                }
+                links
+            })
+            .collect::<Vec<(CellHandle, CellHandle)>>();
+        link_multi(grid, links, events);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{CellHandle, Grid};
+
+/// Something a generator did while carving a maze
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CarveEvent {
+    /// Moved to the given cell
+    Visit(CellHandle),
+
+    /// Linked two cells
+    Link(CellHandle, CellHandle),
+
+    /// Unlinked two cells
+    Unlink(CellHandle, CellHandle),
+
+    /// Backed up to the given cell
+    Backtrack(CellHandle),
+
+    /// Scanned the given row for somewhere to continue from
+    Scan(usize),
+
+    /// Orphaned the given cell
+    Orphan(CellHandle),
+}
+
+impl CarveEvent {
+    /// Applies the event to the grid
+    /// Events that don't change the grid are ignored
+    pub fn apply(&self, grid: &mut Grid) {
+        match *self {
+            Self::Link(a, b) => grid.link_cells(a, b),
+            Self::Unlink(a, b) => grid.unlink_cells(a, b),
+            Self::Orphan(cell) => grid.orphan(cell.row, cell.col),
+            Self::Visit(_) | Self::Backtrack(_) | Self::Scan(_) => (),
+        }
+    }
+
+    /// Returns the cell the generator is at after the event, if it's at one
+    pub fn current(&self) -> Option<CellHandle> {
+        match *self {
+            Self::Visit(cell) | Self::Backtrack(cell) => Some(cell),
+            Self::Link(_, cell) => Some(cell),
+            Self::Unlink(..) | Self::Scan(_) | Self::Orphan(_) => None,
+        }
+    }
+}
+
+/// Step-by-step replay of a generator carving a maze
+/// Each step applies the next event to the grid,
+/// so the grid shows the maze as it was after that event
+#[derive(Debug, Clone)]
+pub struct Steps {
+    grid: Grid,
+    events: std::vec::IntoIter<CarveEvent>,
+}
+
+impl Steps {
+    pub(crate) fn new(grid: Grid, events: Vec<CarveEvent>) -> Self {
+        Self {
+            grid,
+            events: events.into_iter(),
+        }
+    }
+
+    /// Returns the grid as of the last step
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Returns the number of steps left
+    pub fn remaining(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Applies the remaining steps and returns the finished grid
+    pub fn finish(mut self) -> Grid {
+        for event in self.events {
+            event.apply(&mut self.grid);
+        }
+        self.grid
+    }
+}
+
+impl Iterator for Steps {
+    type Item = CarveEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.events.next()?;
+        event.apply(&mut self.grid);
+        Some(event)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.events.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Steps {}
@@ -0,0 +1,133 @@
+use std::fmt;
+
+use rand::{Rng, RngCore};
+
+use crate::util::sample;
+use crate::{CellHandle, Grid};
+
+use super::{link, start_cells, CarveEvent, Generator, Progress, StopCondition};
+
+/// Selects the next active cell index
+pub type GrowingTreeSelector = dyn Fn(&[CellHandle], &mut dyn RngCore) -> usize + Send + Sync;
+
+/// How the Growing Tree generator selects the next active cell
+#[derive(Default)]
+pub enum GrowingTreeStrategy {
+    /// The most recently added cell (Recursive Backtracker)
+    #[default]
+    Newest,
+
+    /// A random cell (Simplified Prim's)
+    Random,
+
+    /// The least recently added cell
+    Oldest,
+
+    /// The newest cell with the given probability, otherwise a random cell
+    Mixed(f64),
+
+    /// User-supplied selection over the active cells (oldest first)
+    Custom(Box<GrowingTreeSelector>),
+}
+
+impl GrowingTreeStrategy {
+    /// Creates a user-supplied strategy
+    pub fn custom(
+        selector: impl Fn(&[CellHandle], &mut dyn RngCore) -> usize + Send + Sync + 'static,
+    ) -> Self {
+        Self::Custom(Box::new(selector))
+    }
+
+    fn select(&self, active: &[CellHandle], rng: &mut dyn RngCore) -> usize {
+        match self {
+            Self::Newest => active.len() - 1,
+            Self::Random => rng.gen_range(0..active.len()),
+            Self::Oldest => 0,
+            Self::Mixed(newest) => {
+                if rng.gen_bool(newest.clamp(0.0, 1.0)) {
+                    active.len() - 1
+                } else {
+                    rng.gen_range(0..active.len())
+                }
+            }
+            Self::Custom(selector) => {
+                let index = selector(active, rng);
+                assert!(index < active.len(), "Invalid active cell: {}", index);
+                index
+            }
+        }
+    }
+}
+
+impl fmt::Debug for GrowingTreeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Newest => write!(f, "Newest"),
+            Self::Random => write!(f, "Random"),
+            Self::Oldest => write!(f, "Oldest"),
+            Self::Mixed(newest) => write!(f, "Mixed({})", newest),
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Growing Tree maze generator
+/// Grows the maze from an active cell chosen by the strategy,
+/// linking it to a random unvisited neighbor
+/// Perfect - Yes
+/// Uniform - No
+/// Bias - Depends on the strategy
+/// (newest is Recursive Backtracker, random is Simplified Prim's)
+/// Runtime - Fast
+#[derive(Debug, Default)]
+pub struct GrowingTree {
+    strategy: GrowingTreeStrategy,
+}
+
+impl GrowingTree {
+    pub fn new(strategy: GrowingTreeStrategy) -> Self {
+        Self { strategy }
+    }
+}
+
+impl Generator for GrowingTree {
+    fn name(&self) -> &str {
+        "Growing Tree"
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let mut active = start_cells(grid, rng);
+
+        let mut progress = Progress::new(grid, stop);
+        while !active.is_empty() && !progress.is_done() {
+            let index = self.strategy.select(&active, rng);
+            let current = active[index];
+
+            let neighbors = {
+                let mut neighbors = grid.neighbors(current);
+                neighbors.retain(|neighbor_handle| {
+                    let neighbor = neighbor_handle.get_cell(grid).unwrap();
+                    !neighbor.has_links()
+                });
+                neighbors
+            };
+
+            // cells without unvisited neighbors are done
+            // (order matters to the strategies, so don't swap remove)
+            if neighbors.is_empty() {
+                active.remove(index);
+            } else {
+                let neighbor = *sample(&neighbors, rng);
+                link(grid, current, neighbor, events);
+                progress.visit();
+                active.push(neighbor);
+            }
+        }
+    }
+}
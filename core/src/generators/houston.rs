@@ -0,0 +1,52 @@
+use rand::RngCore;
+
+use crate::Grid;
+
+use super::{AldousBroder, CarveEvent, Chain, Generator, StopCondition, Wilsons};
+
+/// Houston maze generator
+/// Runs Aldous-Broder until some of the cells are visited,
+/// then finishes the maze with Wilson's Algorithm
+/// Perfect - Yes
+/// Uniform - Yes
+/// Bias - None
+/// Runtime - Fast, avoids the slow parts of both
+pub struct Houston {
+    chain: Chain,
+}
+
+impl Default for Houston {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+impl Houston {
+    /// Switches to Wilson's Algorithm once the given fraction of the cells are visited
+    pub fn new(fraction: f64) -> Self {
+        Self {
+            chain: Chain::new("Houston")
+                .then(
+                    AldousBroder,
+                    StopCondition::Visited(fraction.clamp(0.0, 1.0)),
+                )
+                .then(Wilsons, StopCondition::Complete),
+        }
+    }
+}
+
+impl Generator for Houston {
+    fn name(&self) -> &str {
+        self.chain.name()
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        self.chain.run_events(grid, rng, stop, events);
+    }
+}
@@ -1,7 +1,9 @@
+use rand::RngCore;
+
 use crate::util::sample;
 use crate::Grid;
 
-use super::Generator;
+use super::{link, start_cells, CarveEvent, Generator, Progress, StopCondition};
 
 /// Hunt-and-Kill maze generator
 /// Selects random unvisited cells to visit until all of them are visited,
@@ -19,13 +21,20 @@ impl Generator for HuntAndKill {
         "Hunt-and-Kill"
     }
 
-    fn run(&self, grid: &mut Grid) {
-        let mut cell_handle = Some(grid.get_random().handle());
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let mut cell_handle = Some(start_cells(grid, rng)[0]);
+        events(CarveEvent::Visit(cell_handle.unwrap()));
 
-        while cell_handle.is_some() {
+        let mut progress = Progress::new(grid, stop);
+        while cell_handle.is_some() && !progress.is_done() {
             let unvisited_neighbors = {
-                let cell = cell_handle.unwrap().get_cell(grid).unwrap();
-                let mut neighbors = cell.neighbors();
+                let mut neighbors = grid.neighbors(cell_handle.unwrap());
                 neighbors.retain(|neighbor_handle| {
                     let neighbor = neighbor_handle.get_cell(grid).unwrap();
                     !neighbor.has_links()
@@ -34,16 +43,23 @@ impl Generator for HuntAndKill {
             };
 
             if !unvisited_neighbors.is_empty() {
-                let neighbor_handle = sample(&unvisited_neighbors);
-                grid.link_cells(cell_handle.unwrap(), *neighbor_handle);
+                let neighbor_handle = sample(&unvisited_neighbors, rng);
+                link(grid, cell_handle.unwrap(), *neighbor_handle, events);
+                progress.visit();
                 cell_handle = Some(*neighbor_handle);
             } else {
                 cell_handle = None;
 
                 // hunt for an unvisited cell that borders a visited cell
                 let mut neighbor_handle = None;
+                let mut row = None;
                 for cell in grid.iter() {
-                    let mut visited_neighbors = cell.neighbors();
+                    if row != Some(cell.handle().row) {
+                        row = Some(cell.handle().row);
+                        events(CarveEvent::Scan(cell.handle().row));
+                    }
+
+                    let mut visited_neighbors = grid.neighbors(cell.handle());
                     visited_neighbors.retain(|neighbor_handle| {
                         let neighbor = neighbor_handle.get_cell(grid).unwrap();
                         neighbor.has_links()
@@ -51,13 +67,14 @@ impl Generator for HuntAndKill {
 
                     if !cell.has_links() && !visited_neighbors.is_empty() {
                         cell_handle = Some(cell.handle());
-                        neighbor_handle = Some(*sample(&visited_neighbors));
+                        neighbor_handle = Some(*sample(&visited_neighbors, rng));
                     }
                 }
 
                 // if we found one, link it to a neighbor and continue the random walk
                 if let Some(neighbor_handle) = neighbor_handle {
-                    grid.link_cells(cell_handle.unwrap(), neighbor_handle);
+                    link(grid, neighbor_handle, cell_handle.unwrap(), events);
+                    progress.visit();
                 }
             }
         }
@@ -0,0 +1,250 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use tracing::warn;
+
+use crate::util::coin;
+use crate::{Cell, CellHandle, Grid, GridType};
+
+use super::{link, CarveEvent, Generator, StopCondition};
+
+/// Disjoint sets of connected cells (union-find)
+#[derive(Debug, Default)]
+struct DisjointSet {
+    indices: HashMap<CellHandle, usize>,
+    parents: Vec<usize>,
+    ranks: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn index(&mut self, handle: CellHandle) -> usize {
+        let next = self.parents.len();
+        let index = *self.indices.entry(handle).or_insert(next);
+        if index == next {
+            self.parents.push(index);
+            self.ranks.push(0);
+        }
+        index
+    }
+
+    fn find(&mut self, handle: CellHandle) -> usize {
+        let mut index = self.index(handle);
+        while self.parents[index] != index {
+            // path halving
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    /// Merges the sets containing the two cells
+    /// Returns false if they were already in the same set
+    fn merge(&mut self, a: CellHandle, b: CellHandle) -> bool {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a == b {
+            return false;
+        }
+
+        let (parent, child) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+
+        self.parents[child] = parent;
+        if self.ranks[parent] == self.ranks[child] {
+            self.ranks[parent] += 1;
+        }
+
+        true
+    }
+}
+
+/// Kruskal's maze generator
+/// Links random edges between cells that aren't yet connected
+/// Perfect - Yes
+/// Uniform - No
+/// Bias - Lots of short dead ends
+/// Runtime - Fast
+/// Edges can be linked ahead of time and the maze grows around them,
+/// as can crossings on weave grids
+/// Cells that are already linked are kept as they are and can't be stopped early
+#[derive(Debug, Default)]
+pub struct Kruskals {
+    links: Vec<(CellHandle, CellHandle)>,
+    crossings: Vec<CellHandle>,
+    random_crossings: usize,
+}
+
+impl Kruskals {
+    /// Links the given neighboring cells before the random merge
+    /// Links between non-neighbors or that would create a loop are ignored
+    pub fn with_link(mut self, a: CellHandle, b: CellHandle) -> Self {
+        self.links.push((a, b));
+        self
+    }
+
+    /// Places a crossing at the given cell before the random merge
+    /// Only weave grids have crossings and invalid crossings are ignored
+    pub fn with_crossing(mut self, cell: CellHandle) -> Self {
+        self.crossings.push(cell);
+        self
+    }
+
+    /// Tries to place the given number of crossings at random cells
+    /// before the random merge
+    pub fn with_random_crossings(mut self, count: usize) -> Self {
+        self.random_crossings = count;
+        self
+    }
+
+    /// Links a crossing at the given cell, tunneling under it
+    /// Returns false if the cell can't be crossed
+    fn add_crossing(
+        grid: &mut Grid,
+        sets: &mut DisjointSet,
+        handle: CellHandle,
+        rng: &mut dyn RngCore,
+        events: &mut dyn FnMut(CarveEvent),
+    ) -> bool {
+        if handle.under {
+            return false;
+        }
+
+        let cell = match grid.get_cell(handle) {
+            Some(cell) if !cell.has_links() => cell,
+            _ => return false,
+        };
+
+        let (north, south, east, west) = match cell {
+            Cell::Orthogonal(cell) => match (cell.north, cell.south, cell.east, cell.west) {
+                (Some(north), Some(south), Some(east), Some(west)) => (north, south, east, west),
+                _ => return false,
+            },
+            _ => panic!("Invalid cell type"),
+        };
+
+        // neighbors already tunneled under can't be crossed again
+        if [north, south, east, west]
+            .iter()
+            .any(|neighbor| neighbor.under)
+        {
+            return false;
+        }
+
+        // linking any two of the neighbors through the crossing
+        // would close a loop if they're already connected
+        let sets_crossed = [north, south, east, west]
+            .map(|neighbor| sets.find(neighbor))
+            .into_iter()
+            .collect::<HashSet<usize>>();
+        if sets_crossed.len() < 4 {
+            return false;
+        }
+
+        // the passage over the crossing goes one way
+        // and the tunnel under it goes the other
+        let ((a, b), (c, d)) = if coin(rng) {
+            ((west, east), (north, south))
+        } else {
+            ((north, south), (west, east))
+        };
+
+        link(grid, handle, a, events);
+        link(grid, handle, b, events);
+        sets.merge(handle, a);
+        sets.merge(handle, b);
+
+        link(grid, c, d, events);
+        let under = CellHandle::new_under(handle.row, handle.col);
+        sets.merge(under, c);
+        sets.merge(under, d);
+
+        true
+    }
+}
+
+impl Generator for Kruskals {
+    fn name(&self) -> &str {
+        "Kruskal's"
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let mut sets = DisjointSet::default();
+
+        // cells that are already linked are already connected
+        for cell in grid.iter() {
+            for &link in cell.links() {
+                sets.merge(cell.handle(), link);
+            }
+        }
+
+        for &(a, b) in &self.links {
+            let neighbors = grid
+                .get_cell(a)
+                .is_some_and(|cell| cell.neighbors().contains(&b));
+            if !neighbors {
+                warn!("Cannot link non-neighbors {:?} and {:?}", a, b);
+                continue;
+            }
+
+            if sets.merge(a, b) {
+                link(grid, a, b, events);
+            }
+        }
+
+        let mut crossed = HashSet::new();
+        if grid.grid_type() == GridType::Weave {
+            for &cell in &self.crossings {
+                if Self::add_crossing(grid, &mut sets, cell, rng, events) {
+                    crossed.insert(cell);
+                }
+            }
+
+            let handles = grid.handles_iter().collect::<Vec<CellHandle>>();
+            for _ in 0..self.random_crossings {
+                let cell = handles[rng.gen_range(0..handles.len())];
+                if Self::add_crossing(grid, &mut sets, cell, rng, events) {
+                    crossed.insert(cell);
+                }
+            }
+        }
+
+        // crossings and the cells under them are already complete
+        let mut edges = Vec::new();
+        let mut seen = HashSet::new();
+        for cell in grid.iter() {
+            let handle = cell.handle();
+            if crossed.contains(&handle) {
+                continue;
+            }
+
+            for neighbor in cell.neighbors() {
+                if neighbor.under || crossed.contains(&neighbor) {
+                    continue;
+                }
+
+                // each edge is seen from both of its cells
+                if !seen.contains(&(neighbor, handle)) {
+                    seen.insert((handle, neighbor));
+                    edges.push((handle, neighbor));
+                }
+            }
+        }
+        edges.shuffle(rng);
+
+        for (a, b) in edges {
+            if sets.merge(a, b) {
+                link(grid, a, b, events);
+            }
+        }
+    }
+}
@@ -1,21 +1,44 @@
 pub mod aldous_broder;
 pub mod binarytree;
 pub mod binarytree_par;
+pub mod caves;
+pub mod chain;
+pub mod ellers;
+pub mod events;
+pub mod growing_tree;
+pub mod houston;
 pub mod hunt_and_kill;
+pub mod kruskals;
 pub mod recursive_backtracker;
+pub mod recursive_division;
 pub mod sidewinder;
 pub mod sidewinder_par;
+pub mod simplified_prims;
+pub mod true_prims;
 pub mod wilsons;
 
-use crate::Grid;
+use rand::seq::SliceRandom;
+use rand::RngCore;
+
+use crate::{CellHandle, Grid, GridType};
 
 pub use aldous_broder::*;
 pub use binarytree::*;
 pub use binarytree_par::*;
+pub use caves::*;
+pub use chain::*;
+pub use ellers::*;
+pub use events::*;
+pub use growing_tree::*;
+pub use houston::*;
 pub use hunt_and_kill::*;
+pub use kruskals::*;
 pub use recursive_backtracker::*;
+pub use recursive_division::*;
 pub use sidewinder::*;
 pub use sidewinder_par::*;
+pub use simplified_prims::*;
+pub use true_prims::*;
 pub use wilsons::*;
 
 /// All maze generators implement this trait
@@ -25,20 +48,184 @@ pub trait Generator {
     fn name(&self) -> &str;
 
     /// Generates a new grid-based maze
-    fn generate(&self, rows: usize, cols: usize, polar: bool) -> Grid {
-        let mut grid = if polar {
-            Grid::new_polar(rows, cols)
-        } else {
-            Grid::new_ortho(rows, cols)
-        };
+    /// The same RNG state always generates the same maze
+    fn generate(
+        &self,
+        rows: usize,
+        cols: usize,
+        grid_type: GridType,
+        rng: &mut dyn RngCore,
+    ) -> Grid {
+        let mut grid = Grid::new(rows, cols, grid_type);
 
-        self.run(&mut grid);
+        self.run(&mut grid, rng);
 
         grid
     }
 
     /// Runs the generator on the given grid
-    fn run(&self, grid: &mut Grid);
+    fn run(&self, grid: &mut Grid, rng: &mut dyn RngCore) {
+        self.run_until(grid, rng, StopCondition::Complete);
+    }
+
+    /// Runs the generator on the given grid until the stop condition is met
+    /// Cells that are already linked are treated as visited,
+    /// so this can continue a maze another generator started
+    /// Generators that can't stop early ignore the stop condition
+    fn run_until(&self, grid: &mut Grid, rng: &mut dyn RngCore, stop: StopCondition) {
+        self.run_events(grid, rng, stop, &mut |_| ());
+    }
+
+    /// Runs the generator on the given grid until the stop condition is met,
+    /// reporting each carve event as it happens
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    );
+
+    /// Returns true if the generator can carve a maze on the given grid
+    /// Grids that aren't supported are left as they are
+    fn supports(&self, _grid: &Grid) -> bool {
+        true
+    }
+
+    /// Runs the generator on the given grid and returns
+    /// a step-by-step replay of it carving the maze
+    /// The generator runs to completion up front,
+    /// so the same RNG state replays the same maze that generate would
+    fn steps(&self, grid: Grid, rng: &mut dyn RngCore) -> Steps {
+        let mut carved = grid.clone();
+        let mut events = Vec::new();
+        self.run_events(&mut carved, rng, StopCondition::Complete, &mut |event| {
+            events.push(event)
+        });
+
+        Steps::new(grid, events)
+    }
+}
+
+/// When a generator stops running
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum StopCondition {
+    /// Run until the maze is complete
+    #[default]
+    Complete,
+
+    /// Stop once the given fraction of the cells have been visited
+    Visited(f64),
+
+    /// Stop after linking the given number of cells
+    Steps(usize),
+}
+
+impl StopCondition {
+    /// Returns how many more cells can be linked before the condition is met
+    /// given the number already linked towards it,
+    /// or None if it runs until the maze is complete
+    pub(crate) fn remaining(&self, grid: &Grid, steps: usize) -> Option<usize> {
+        match *self {
+            Self::Complete => None,
+            Self::Visited(fraction) => {
+                let cells = (fraction * grid.enabled_count() as f64).ceil() as usize;
+                Some(cells.saturating_sub(visited_count(grid)))
+            }
+            Self::Steps(max) => Some(max.saturating_sub(steps)),
+        }
+    }
+}
+
+/// Tracks a generator's progress towards its stop condition
+#[derive(Debug)]
+pub(crate) struct Progress {
+    stop: StopCondition,
+    cells: usize,
+    visited: usize,
+    steps: usize,
+}
+
+impl Progress {
+    pub(crate) fn new(grid: &Grid, stop: StopCondition) -> Self {
+        Self {
+            stop,
+            cells: grid.enabled_count(),
+            visited: visited_count(grid),
+            steps: 0,
+        }
+    }
+
+    /// Records linking an unvisited cell
+    pub(crate) fn visit(&mut self) {
+        self.visited += 1;
+        self.steps += 1;
+    }
+
+    /// Returns true once the stop condition is met
+    /// or there's nothing left to visit
+    pub(crate) fn is_done(&self) -> bool {
+        if self.visited >= self.cells {
+            return true;
+        }
+
+        match self.stop {
+            StopCondition::Complete => false,
+            StopCondition::Visited(fraction) => self.visited as f64 >= fraction * self.cells as f64,
+            StopCondition::Steps(steps) => self.steps >= steps,
+        }
+    }
+}
+
+/// Returns the number of cells in the grid that have been visited
+/// The first cell is visited before anything is linked to it
+pub(crate) fn visited_count(grid: &Grid) -> usize {
+    grid.iter().filter(|cell| cell.has_links()).count().max(1)
+}
+
+/// Returns the cells to grow a maze from
+/// This is every visited cell, in random order,
+/// or a single random cell if nothing has been visited yet
+pub(crate) fn start_cells(grid: &Grid, rng: &mut dyn RngCore) -> Vec<CellHandle> {
+    let mut visited = grid
+        .iter()
+        .filter(|cell| cell.has_links())
+        .map(|cell| cell.handle())
+        .collect::<Vec<CellHandle>>();
+
+    if visited.is_empty() {
+        return vec![grid.get_random(rng).handle()];
+    }
+
+    visited.shuffle(rng);
+    visited
+}
+
+/// Links two cells and reports it
+pub(crate) fn link(
+    grid: &mut Grid,
+    a: CellHandle,
+    b: CellHandle,
+    events: &mut dyn FnMut(CarveEvent),
+) {
+    grid.link_cells(a, b);
+    events(CarveEvent::Link(a, b));
+}
+
+/// Links each pair of cells and reports it
+pub(crate) fn link_multi(
+    grid: &mut Grid,
+    links: impl AsRef<[(CellHandle, CellHandle)]>,
+    events: &mut dyn FnMut(CarveEvent),
+) {
+    for &(a, b) in links.as_ref() {
+        link(grid, a, b, events);
+    }
+}
+
+/// Returns true if any cells in the grid are linked
+pub(crate) fn has_links(grid: &Grid) -> bool {
+    grid.iter().any(|cell| cell.has_links())
 }
 
 /// Generator that doesn't generate anything
@@ -50,5 +237,388 @@ impl Generator for NoneGenerator {
         "None"
     }
 
-    fn run(&self, _grid: &mut Grid) {}
+    fn run_events(
+        &self,
+        _grid: &mut Grid,
+        _rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        _events: &mut dyn FnMut(CarveEvent),
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::util::seeded_rng;
+    use crate::{CellHandle, Mask};
+
+    fn generators() -> Vec<Box<dyn Generator>> {
+        vec![
+            Box::new(BinaryTree::default()),
+            Box::new(BinaryTreeParallel::default()),
+            Box::new(Sidewinder::default()),
+            Box::new(SidewinderParallel::default()),
+            Box::new(BinaryTree::default().with_diagonal(Diagonal::SouthWest)),
+            Box::new(BinaryTreeParallel::default().with_diagonal(Diagonal::NorthWest)),
+            Box::new(
+                Sidewinder::default()
+                    .with_edge(SidewinderEdge::South)
+                    .with_close_chance(0.2),
+            ),
+            Box::new(SidewinderParallel::default().with_edge(SidewinderEdge::South)),
+            Box::new(AldousBroder),
+            Box::new(Wilsons),
+            Box::new(HuntAndKill),
+            Box::new(RecursiveBacktracker),
+            Box::new(SimplifiedPrims),
+            Box::new(TruePrims),
+            Box::new(GrowingTree::new(GrowingTreeStrategy::Random)),
+            Box::new(GrowingTree::new(GrowingTreeStrategy::Mixed(0.5))),
+            Box::new(GrowingTree::new(GrowingTreeStrategy::custom(
+                |active, _| active.len() / 2,
+            ))),
+            Box::new(Kruskals::default().with_random_crossings(50)),
+            Box::new(Ellers),
+            Box::new(RecursiveDivision::default().with_rooms(5, 0.5)),
+            Box::new(Houston::default()),
+            Box::new(Caves::default()),
+            Box::new(
+                Caves::default()
+                    .with_rules("B5678/S45678".parse().unwrap())
+                    .with_wall_chance(0.4),
+            ),
+            Box::new(
+                Chain::new("Chain")
+                    .then(RecursiveBacktracker, StopCondition::Steps(20))
+                    .then(Wilsons, StopCondition::Visited(0.5))
+                    .then(GrowingTree::default(), StopCondition::Steps(10))
+                    .then(Kruskals::default(), StopCondition::Complete),
+            ),
+        ]
+    }
+
+    const GRID_TYPES: [GridType; 9] = [
+        GridType::Orthogonal,
+        GridType::Polar,
+        GridType::Hex,
+        GridType::Weave,
+        GridType::Triangle,
+        GridType::Torus,
+        GridType::Layered(2),
+        GridType::Cube,
+        GridType::Sphere,
+    ];
+
+    fn grid_types(generator: &dyn Generator) -> Vec<GridType> {
+        GRID_TYPES
+            .into_iter()
+            .filter(|&grid_type| generator.supports(&Grid::new(12, 12, grid_type)))
+            .collect()
+    }
+
+    fn links(grid: &Grid) -> HashSet<(CellHandle, CellHandle)> {
+        grid.iter()
+            .flat_map(|cell| cell.links().map(move |link| (cell.handle(), *link)))
+            .collect()
+    }
+
+    #[test]
+    fn unsupported_grids_are_left_as_they_are() {
+        let mut unsupported = 0;
+        for generator in generators() {
+            for grid_type in GRID_TYPES {
+                let grid = Grid::new(12, 12, grid_type);
+                if generator.supports(&grid) {
+                    continue;
+                }
+
+                let mut carved = grid.clone();
+                generator.run(&mut carved, &mut seeded_rng(6));
+                assert!(links(&carved).is_empty(), "{}", generator.name());
+                unsupported += 1;
+            }
+        }
+        assert!(unsupported > 0);
+
+        // only some generators can continue a maze or fill a mask
+        let mut linked = Grid::new(12, 12, GridType::Orthogonal);
+        RecursiveBacktracker.run_until(&mut linked, &mut seeded_rng(7), StopCondition::Steps(5));
+        let mut mask = Mask::new(12, 12);
+        mask.set(6, 6, false);
+        let masked = Grid::from_mask(mask, GridType::Orthogonal);
+        for (generator, supported) in [
+            (Box::new(BinaryTree::default()) as Box<dyn Generator>, false),
+            (Box::new(Sidewinder::default()), false),
+            (Box::new(Ellers), false),
+            (Box::new(RecursiveDivision::default()), false),
+            (Box::new(Caves::default()), false),
+            (Box::new(Wilsons), true),
+        ] {
+            assert_eq!(
+                generator.supports(&linked),
+                supported,
+                "{}",
+                generator.name()
+            );
+        }
+        assert!(!Ellers.supports(&masked));
+        assert!(!RecursiveDivision::default().supports(&masked));
+        assert!(Caves::default().supports(&masked));
+    }
+
+    #[test]
+    fn same_seed_same_maze() {
+        for generator in generators() {
+            for grid_type in grid_types(&*generator) {
+                for seed in [0, 1, 0xdead_beef] {
+                    let a = generator.generate(12, 12, grid_type, &mut seeded_rng(seed));
+                    let b = generator.generate(12, 12, grid_type, &mut seeded_rng(seed));
+
+                    assert!(
+                        !links(&a).is_empty(),
+                        "{} generated nothing on a {} grid",
+                        generator.name(),
+                        grid_type
+                    );
+                    assert_eq!(
+                        links(&a),
+                        links(&b),
+                        "{} is not reproducible on a {} grid (seed={})",
+                        generator.name(),
+                        grid_type,
+                        seed
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_steps_are_in_order() {
+        let generators: Vec<Box<dyn Generator + Sync>> = vec![
+            Box::new(BinaryTreeParallel::default()),
+            Box::new(SidewinderParallel::default()),
+        ];
+
+        for generator in generators {
+            let steps = |seed| {
+                generator
+                    .steps(
+                        Grid::new(32, 32, GridType::Orthogonal),
+                        &mut seeded_rng(seed),
+                    )
+                    .collect::<Vec<CarveEvent>>()
+            };
+
+            // more threads than cores to shuffle the work around
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(8)
+                .build()
+                .unwrap();
+            let expected = pool.install(|| steps(8));
+            for _ in 0..10 {
+                assert_eq!(pool.install(|| steps(8)), expected, "{}", generator.name());
+            }
+        }
+
+        // each cell links to its neighbor in turn
+        let grid = Grid::new(32, 32, GridType::Orthogonal);
+        let cells = grid
+            .iter()
+            .map(|cell| cell.handle())
+            .collect::<Vec<CellHandle>>();
+        let linked = BinaryTreeParallel::default()
+            .steps(grid, &mut seeded_rng(9))
+            .filter_map(|event| match event {
+                CarveEvent::Link(a, _) => Some(a),
+                _ => None,
+            })
+            .collect::<Vec<CellHandle>>();
+        let mut expected = linked.clone();
+        expected.sort_by_key(|a| cells.iter().position(|cell| cell == a));
+        assert_eq!(linked, expected);
+    }
+
+    fn root(parents: &HashMap<CellHandle, CellHandle>, mut cell: CellHandle) -> CellHandle {
+        while let Some(&parent) = parents.get(&cell) {
+            cell = parent;
+        }
+        cell
+    }
+
+    #[test]
+    fn steps_replay_the_maze() {
+        for generator in generators() {
+            for grid_type in grid_types(&*generator) {
+                let grid = generator.generate(12, 12, grid_type, &mut seeded_rng(3));
+                let steps = generator.steps(Grid::new(12, 12, grid_type), &mut seeded_rng(3));
+                let replayed = steps.finish();
+
+                assert_eq!(
+                    links(&grid),
+                    links(&replayed),
+                    "{} replays a different maze on a {} grid",
+                    generator.name(),
+                    grid_type
+                );
+                assert_eq!(grid.enabled_count(), replayed.enabled_count());
+            }
+        }
+    }
+
+    #[test]
+    fn perfect_steps_never_link_connected_cells() {
+        for generator in generators() {
+            // rooms and caves have loops
+            if ["Recursive Division", "Caves"].contains(&generator.name()) {
+                continue;
+            }
+
+            for grid_type in grid_types(&*generator) {
+                let mut parents = HashMap::new();
+                let steps = generator.steps(Grid::new(12, 12, grid_type), &mut seeded_rng(4));
+                for event in steps {
+                    if let CarveEvent::Link(a, b) = event {
+                        let (a, b) = (root(&parents, a), root(&parents, b));
+                        assert_ne!(
+                            a,
+                            b,
+                            "{} linked connected cells on a {} grid",
+                            generator.name(),
+                            grid_type
+                        );
+                        parents.insert(a, b);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Asserts every cell, including those under crossings,
+    /// is connected to every other by exactly one path
+    fn assert_perfect(grid: &Grid) {
+        let mut edges = HashSet::new();
+        for (a, b) in links(grid) {
+            edges.insert((a, b));
+            edges.insert((b, a));
+        }
+
+        let cells = edges
+            .iter()
+            .map(|&(a, _)| a)
+            .chain(grid.iter().map(|cell| cell.handle()))
+            .collect::<HashSet<CellHandle>>();
+        assert_eq!(edges.len() / 2, cells.len() - 1, "the maze has loops");
+
+        let start = grid.iter().next().unwrap().handle();
+        let mut seen = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(cell) = stack.pop() {
+            for &link in grid.get_cell(cell).unwrap().links() {
+                if seen.insert(link) {
+                    stack.push(link);
+                }
+            }
+        }
+        assert_eq!(seen.len(), cells.len(), "the maze is disconnected");
+    }
+
+    #[test]
+    fn kruskals_grows_around_links_and_crossings() {
+        let (n, s, e, w) = (
+            CellHandle::new(1, 2),
+            CellHandle::new(3, 2),
+            CellHandle::new(2, 3),
+            CellHandle::new(2, 1),
+        );
+
+        let generators = [
+            // the crossing would close loops through the links
+            // joining north to west and south to east
+            Kruskals::default()
+                .with_link(n, CellHandle::new(1, 1))
+                .with_link(CellHandle::new(1, 1), w)
+                .with_link(e, CellHandle::new(3, 3))
+                .with_link(CellHandle::new(3, 3), s)
+                .with_crossing(CellHandle::new(2, 2)),
+            Kruskals::default()
+                .with_link(n, CellHandle::new(1, 1))
+                .with_link(s, CellHandle::new(3, 3))
+                .with_crossing(CellHandle::new(2, 2))
+                .with_random_crossings(10),
+        ];
+
+        for (i, generator) in generators.iter().enumerate() {
+            for seed in 0..20 {
+                let grid = generator.generate(6, 6, GridType::Weave, &mut seeded_rng(seed));
+                assert_perfect(&grid);
+
+                for (a, b) in [(n, CellHandle::new(1, 1)), (s, CellHandle::new(3, 3))] {
+                    assert!(grid.get_cell(a).unwrap().links().any(|link| *link == b));
+                }
+
+                let crossed = grid
+                    .get_cell(CellHandle::new_under(2, 2))
+                    .is_some_and(|cell| cell.has_links());
+                assert_eq!(crossed, i == 1, "seed={}", seed);
+            }
+        }
+
+        for seed in 0..20 {
+            let generator = Kruskals::default().with_random_crossings(50);
+            assert_perfect(&generator.generate(12, 12, GridType::Weave, &mut seeded_rng(seed)));
+        }
+    }
+
+    #[test]
+    fn chains_meet_their_stop_condition() {
+        let chains: Vec<Box<dyn Generator>> = vec![
+            Box::new(Houston::default()),
+            Box::new(Houston::new(0.1)),
+            Box::new(
+                Chain::new("Chain")
+                    .then(RecursiveBacktracker, StopCondition::Steps(20))
+                    .then(Wilsons, StopCondition::Complete),
+            ),
+            Box::new(
+                Chain::new("Nested")
+                    .then(Houston::new(0.2), StopCondition::Steps(30))
+                    .then(Houston::default(), StopCondition::Complete),
+            ),
+        ];
+
+        for chain in chains {
+            for (stop, visited) in [
+                (StopCondition::Steps(5), 6),
+                (StopCondition::Steps(50), 51),
+                (StopCondition::Visited(0.25), 36),
+                (StopCondition::Visited(0.75), 108),
+                (StopCondition::Complete, 144),
+            ] {
+                let mut grid = Grid::new(12, 12, GridType::Orthogonal);
+                chain.run_until(&mut grid, &mut seeded_rng(5), stop);
+
+                assert_eq!(
+                    visited_count(&grid),
+                    visited,
+                    "{} didn't stop at {:?}",
+                    chain.name(),
+                    stop
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn different_seed_different_maze() {
+        for generator in generators() {
+            let a = generator.generate(12, 12, GridType::Orthogonal, &mut seeded_rng(1));
+            let b = generator.generate(12, 12, GridType::Orthogonal, &mut seeded_rng(2));
+
+            assert_ne!(links(&a), links(&b), "{}", generator.name());
+        }
+    }
 }
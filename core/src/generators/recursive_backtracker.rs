@@ -1,7 +1,9 @@
+use rand::RngCore;
+
 use crate::util::sample;
 use crate::Grid;
 
-use super::Generator;
+use super::{link, start_cells, CarveEvent, Generator, Progress, StopCondition};
 
 /// Recursive backtracker maze generator
 /// Selects random unvisited cells to visit until all of them are visited,
@@ -19,16 +21,21 @@ impl Generator for RecursiveBacktracker {
         "Recursive Backtracker"
     }
 
-    fn run(&self, grid: &mut Grid) {
-        let start = grid.get_random().handle();
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let mut stack = start_cells(grid, rng);
 
-        let mut stack = vec![start];
-        while !stack.is_empty() {
+        let mut progress = Progress::new(grid, stop);
+        while !stack.is_empty() && !progress.is_done() {
             let current = *stack.last().unwrap();
 
             let neighbors = {
-                let cell = current.get_cell(grid).unwrap();
-                let mut neighbors = cell.neighbors();
+                let mut neighbors = grid.neighbors(current);
                 neighbors.retain(|neighbor_handle| {
                     let neighbor = neighbor_handle.get_cell(grid).unwrap();
                     !neighbor.has_links()
@@ -40,9 +47,13 @@ impl Generator for RecursiveBacktracker {
             // we need to backtrack
             if neighbors.is_empty() {
                 stack.pop();
+                if let Some(&previous) = stack.last() {
+                    events(CarveEvent::Backtrack(previous));
+                }
             } else {
-                let neighbor = *sample(&neighbors);
-                grid.link_cells(current, neighbor);
+                let neighbor = *sample(&neighbors, rng);
+                link(grid, current, neighbor, events);
+                progress.visit();
                 stack.push(neighbor);
             }
         }
@@ -0,0 +1,174 @@
+use rand::{Rng, RngCore};
+
+use crate::{CellHandle, Grid, GridType};
+
+use super::{has_links, CarveEvent, Generator, StopCondition};
+
+/// Recursive division maze generator
+/// Starts with every cell linked and recursively divides the grid with walls,
+/// leaving a single passage through each wall
+/// Perfect - Yes, unless regions are left undivided
+/// Uniform - No
+/// Bias - Long straight walls, rectangular texture
+/// Runtime - Fast
+/// Only unmasked orthogonal grids that aren't already linked are supported
+#[derive(Debug)]
+pub struct RecursiveDivision {
+    min_size: usize,
+    room_size: usize,
+    room_chance: f64,
+}
+
+impl Default for RecursiveDivision {
+    fn default() -> Self {
+        Self {
+            min_size: 1,
+            room_size: 0,
+            room_chance: 0.0,
+        }
+    }
+}
+
+impl RecursiveDivision {
+    /// Regions this many cells tall or wide or smaller are not divided
+    pub fn with_min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size.max(1);
+        self
+    }
+
+    /// Regions smaller than the given size in both directions
+    /// are left undivided as open rooms with the given chance
+    pub fn with_rooms(mut self, room_size: usize, room_chance: f64) -> Self {
+        self.room_size = room_size;
+        self.room_chance = room_chance.clamp(0.0, 1.0);
+        self
+    }
+
+    fn divide(
+        &self,
+        grid: &mut Grid,
+        (row, col): (usize, usize),
+        (height, width): (usize, usize),
+        rng: &mut dyn RngCore,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        if height <= self.min_size || width <= self.min_size {
+            return;
+        }
+
+        if height < self.room_size && width < self.room_size && rng.gen_bool(self.room_chance) {
+            return;
+        }
+
+        if height > width {
+            self.divide_horizontally(grid, (row, col), (height, width), rng, events);
+        } else {
+            self.divide_vertically(grid, (row, col), (height, width), rng, events);
+        }
+    }
+
+    fn divide_horizontally(
+        &self,
+        grid: &mut Grid,
+        (row, col): (usize, usize),
+        (height, width): (usize, usize),
+        rng: &mut dyn RngCore,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let divide_south_of = rng.gen_range(0..height - 1);
+        let passage_at = rng.gen_range(0..width);
+
+        for x in 0..width {
+            if x != passage_at {
+                let cell = CellHandle::new(row + divide_south_of, col + x);
+                let south = CellHandle::new(row + divide_south_of + 1, col + x);
+                grid.unlink_cells(cell, south);
+                events(CarveEvent::Unlink(cell, south));
+            }
+        }
+
+        let north_height = divide_south_of + 1;
+        self.divide(grid, (row, col), (north_height, width), rng, events);
+        self.divide(
+            grid,
+            (row + north_height, col),
+            (height - north_height, width),
+            rng,
+            events,
+        );
+    }
+
+    fn divide_vertically(
+        &self,
+        grid: &mut Grid,
+        (row, col): (usize, usize),
+        (height, width): (usize, usize),
+        rng: &mut dyn RngCore,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let divide_east_of = rng.gen_range(0..width - 1);
+        let passage_at = rng.gen_range(0..height);
+
+        for y in 0..height {
+            if y != passage_at {
+                let cell = CellHandle::new(row + y, col + divide_east_of);
+                let east = CellHandle::new(row + y, col + divide_east_of + 1);
+                grid.unlink_cells(cell, east);
+                events(CarveEvent::Unlink(cell, east));
+            }
+        }
+
+        let west_width = divide_east_of + 1;
+        self.divide(grid, (row, col), (height, west_width), rng, events);
+        self.divide(
+            grid,
+            (row, col + west_width),
+            (height, width - west_width),
+            rng,
+            events,
+        );
+    }
+}
+
+impl Generator for RecursiveDivision {
+    fn name(&self) -> &str {
+        "Recursive Division"
+    }
+
+    fn supports(&self, grid: &Grid) -> bool {
+        // masked out cells would break up the rows
+        let rows = grid.rows_iter().len();
+        let cols = grid.rows_iter().next().map_or(0, Vec::len);
+        grid.grid_type() == GridType::Orthogonal
+            && grid.iter().count() == rows * cols
+            && !has_links(grid)
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        if !self.supports(grid) {
+            return;
+        }
+
+        let rows = grid.rows_iter().len();
+        let cols = grid.rows_iter().next().map_or(0, Vec::len);
+
+        grid.link_all();
+        for cell in grid.iter() {
+            let handle = cell.handle();
+            for &link in cell.links() {
+                // each link is seen from both of its cells
+                if (link.row, link.col) > (handle.row, handle.col) {
+                    events(CarveEvent::Link(handle, link));
+                }
+            }
+        }
+
+        self.divide(grid, (0, 0), (rows, cols), rng, events);
+    }
+}
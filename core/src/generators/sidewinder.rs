@@ -1,47 +1,177 @@
-use crate::util::{coin, sample};
-use crate::{Cell, CellHandle, Grid};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
-use super::Generator;
+use rand::{Rng, RngCore};
 
-// TODO: this has broken :(
+use crate::util::sample;
+use crate::{Cell, CellHandle, Grid, GridType};
+
+use super::{has_links, link_multi, CarveEvent, Generator, StopCondition};
+
+/// The grid edge a sidewinder maze runs along
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum SidewinderEdge {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl fmt::Display for SidewinderEdge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::North => write!(f, "north"),
+            Self::East => write!(f, "east"),
+            Self::South => write!(f, "south"),
+            Self::West => write!(f, "west"),
+        }
+    }
+}
+
+impl FromStr for SidewinderEdge {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "north" | "n" => Ok(Self::North),
+            "east" | "e" => Ok(Self::East),
+            "south" | "s" => Ok(Self::South),
+            "west" | "w" => Ok(Self::West),
+            _ => Err(format!("Invalid edge: {}", s)),
+        }
+    }
+}
 
 /// Sidewinder maze generator
 /// Groups adjacent cells as a run before path carving
+/// (runs go clockwise and close out inward on polar grids)
 /// Perfect - Yes
 /// Uniform - No
-/// Bias - North edge path is always unbroken
+/// Bias - The path along the edge (North by default) is always unbroken
 /// Runtime - Fast
-#[derive(Debug, Default)]
-pub struct Sidewinder;
+/// Runs go parallel to the edge and close out toward it
+/// (polar grids only support the north / inward and south / outward edges)
+/// Only orthogonal, polar, hex and weave grids are supported
+/// and grids that are already linked are left as they are
+#[derive(Debug)]
+pub struct Sidewinder {
+    edge: SidewinderEdge,
+    close_chance: f64,
+}
+
+impl Default for Sidewinder {
+    fn default() -> Self {
+        Self {
+            edge: SidewinderEdge::default(),
+            close_chance: 0.5,
+        }
+    }
+}
 
 impl Sidewinder {
+    /// Runs along the given edge
+    pub fn with_edge(mut self, edge: SidewinderEdge) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    /// Closes out each run after a cell with the given chance
+    /// Lower chances make longer runs
+    pub fn with_close_chance(mut self, close_chance: f64) -> Self {
+        self.close_chance = close_chance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns each line of cells that runs are made from, in order
+    pub(crate) fn lines(&self, grid: &Grid) -> Vec<Vec<CellHandle>> {
+        let continued = grid
+            .iter()
+            .filter_map(|cell| self.run_neighbor(cell))
+            .collect::<HashSet<CellHandle>>();
+
+        grid.iter()
+            .filter(|cell| !continued.contains(&cell.handle()))
+            .map(|cell| {
+                let mut line = vec![cell.handle()];
+                let mut cell = cell;
+                while let Some(next) = self.run_neighbor(cell) {
+                    line.push(next);
+                    cell = next.get_cell(grid).unwrap();
+                }
+                line
+            })
+            .collect()
+    }
+
     pub(crate) fn link(
+        &self,
         grid: &Grid,
         cell: &Cell,
         run: &mut Vec<CellHandle>,
+        rng: &mut dyn RngCore,
     ) -> Option<(CellHandle, CellHandle)> {
         let cell_handle = cell.handle();
         run.push(cell_handle);
 
-        let at_eastern_boundary = cell.east.is_none();
-        let at_northern_boundary = cell.north.is_none();
+        let next = self.run_neighbor(cell);
+
+        let at_run_boundary = next.is_none();
+        let at_edge = self.close_neighbors(cell).is_empty();
 
-        // close out a run either at the eastern border
-        // or randomly within a row, except at the northern border
-        let should_close_out = at_eastern_boundary || (!at_northern_boundary && coin());
+        // close out a run either at the end of the line
+        // or randomly within it, except along the edge
+        let should_close_out = at_run_boundary || (!at_edge && rng.gen_bool(self.close_chance));
 
         if should_close_out {
-            let member_handle = *sample(run);
+            let member_handle = *sample(run, rng);
             run.clear();
 
             let member = member_handle.get_cell(grid).unwrap();
-            if let Some(north) = member.north {
-                return Some((member_handle, north));
+            let close_neighbors = self.close_neighbors(member);
+            if !close_neighbors.is_empty() {
+                return Some((member_handle, *sample(&close_neighbors, rng)));
             }
 
             None
         } else {
-            Some((cell_handle, cell.east.unwrap()))
+            Some((cell_handle, next.unwrap()))
+        }
+    }
+
+    /// The neighbor a run continues to
+    fn run_neighbor(&self, cell: &Cell) -> Option<CellHandle> {
+        match (cell, self.edge) {
+            (Cell::Orthogonal(cell), SidewinderEdge::North) => cell.east,
+            (Cell::Orthogonal(cell), SidewinderEdge::East) => cell.south,
+            (Cell::Orthogonal(cell), SidewinderEdge::South) => cell.west,
+            (Cell::Orthogonal(cell), SidewinderEdge::West) => cell.north,
+            // the first / last cell in the ring can't wrap around
+            (Cell::Polar(cell), SidewinderEdge::North) => cell.cw.filter(|cw| cw.col > cell.col),
+            (Cell::Polar(cell), SidewinderEdge::South) => cell.ccw.filter(|ccw| ccw.col < cell.col),
+            (Cell::Hex(cell), SidewinderEdge::North) => cell.east(),
+            (Cell::Hex(cell), SidewinderEdge::East) => cell.south,
+            (Cell::Hex(cell), SidewinderEdge::South) => cell.west(),
+            (Cell::Hex(cell), SidewinderEdge::West) => cell.north,
+            _ => None,
+        }
+    }
+
+    /// The neighbors a run can be closed out to
+    fn close_neighbors(&self, cell: &Cell) -> Vec<CellHandle> {
+        match (cell, self.edge) {
+            (Cell::Orthogonal(cell), SidewinderEdge::North) => cell.north.into_iter().collect(),
+            (Cell::Orthogonal(cell), SidewinderEdge::East) => cell.east.into_iter().collect(),
+            (Cell::Orthogonal(cell), SidewinderEdge::South) => cell.south.into_iter().collect(),
+            (Cell::Orthogonal(cell), SidewinderEdge::West) => cell.west.into_iter().collect(),
+            (Cell::Polar(cell), SidewinderEdge::North) => cell.inward.into_iter().collect(),
+            (Cell::Polar(cell), SidewinderEdge::South) => cell.outward.clone(),
+            (Cell::Hex(cell), SidewinderEdge::North) => cell.north.into_iter().collect(),
+            (Cell::Hex(cell), SidewinderEdge::East) => cell.east().into_iter().collect(),
+            (Cell::Hex(cell), SidewinderEdge::South) => cell.south.into_iter().collect(),
+            (Cell::Hex(cell), SidewinderEdge::West) => cell.west().into_iter().collect(),
+            _ => Vec::new(),
         }
     }
 }
@@ -51,21 +181,44 @@ impl Generator for Sidewinder {
         "Sidewinder"
     }
 
-    fn run(&self, grid: &mut Grid) {
-        if grid.has_orphans() {
+    fn supports(&self, grid: &Grid) -> bool {
+        // this only produces a perfect maze when every cell but one
+        // has a neighbor along or toward the edge
+        // and nothing is linked yet
+        if grid.has_orphans() || has_links(grid) {
+            return false;
+        }
+
+        match grid.grid_type() {
+            GridType::Orthogonal | GridType::Hex | GridType::Weave => true,
+            GridType::Polar => matches!(self.edge, SidewinderEdge::North | SidewinderEdge::South),
+            _ => false,
+        }
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        if !self.supports(grid) {
             return;
         }
 
-        let links = grid
-            .rows_iter()
-            .map(|row| {
+        let links = self
+            .lines(grid)
+            .iter()
+            .flat_map(|line| {
                 let mut run = Vec::new();
-                row.iter()
-                    .filter_map(|cell| Self::link(grid, cell.as_ref().unwrap(), &mut run))
+                line.iter()
+                    .filter_map(|handle| {
+                        self.link(grid, handle.get_cell(grid).unwrap(), &mut run, rng)
+                    })
                     .collect::<Vec<(CellHandle, CellHandle)>>()
             })
-            .flatten()
             .collect::<Vec<(CellHandle, CellHandle)>>();
-        grid.link_cells_multi(links);
+        link_multi(grid, links, events);
     }
 }
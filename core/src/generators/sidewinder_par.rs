@@ -1,39 +1,76 @@
+use rand::{Rng, RngCore};
 use rayon::prelude::*;
 
+use crate::util::stream_rng;
 use crate::{CellHandle, Grid};
 
-use super::{Generator, Sidewinder};
+use super::{link_multi, CarveEvent, Generator, Sidewinder, SidewinderEdge, StopCondition};
 
 /// Sidewinder maze generator (parallelized)
 /// Groups adjacent cells as a run before path carving
 /// Perfect - Yes
 /// Uniform - No
-/// Bias - North edge path is always unbroken
+/// Bias - The path along the edge (North by default) is always unbroken
 /// Runtime - Fast
+/// Only orthogonal, polar, hex and weave grids are supported
+/// and grids that are already linked are left as they are
 #[derive(Debug, Default)]
-pub struct SidewinderParallel;
+pub struct SidewinderParallel {
+    sidewinder: Sidewinder,
+}
+
+impl SidewinderParallel {
+    /// Runs along the given edge
+    pub fn with_edge(mut self, edge: SidewinderEdge) -> Self {
+        self.sidewinder = self.sidewinder.with_edge(edge);
+        self
+    }
+
+    /// Closes out each run after a cell with the given chance
+    /// Lower chances make longer runs
+    pub fn with_close_chance(mut self, close_chance: f64) -> Self {
+        self.sidewinder = self.sidewinder.with_close_chance(close_chance);
+        self
+    }
+}
 
 impl Generator for SidewinderParallel {
     fn name(&self) -> &str {
         "Sidewinder (Parallel)"
     }
 
-    fn run(&self, grid: &mut Grid) {
-        if grid.has_orphans() {
+    fn supports(&self, grid: &Grid) -> bool {
+        self.sidewinder.supports(grid)
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        _stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        if !self.supports(grid) {
             return;
         }
 
-        let links = grid
-            .rows_iter()
-            .par_bridge()
-            .map(|row| {
+        let seed = rng.gen();
+        let links = self
+            .sidewinder
+            .lines(grid)
+            .par_iter()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                let mut rng = stream_rng(seed, index as u64);
                 let mut run = Vec::new();
-                row.iter()
-                    .filter_map(|cell| Sidewinder::link(grid, cell.as_ref().unwrap(), &mut run))
+                line.iter()
+                    .filter_map(|handle| {
+                        let cell = handle.get_cell(grid).unwrap();
+                        self.sidewinder.link(grid, cell, &mut run, &mut rng)
+                    })
                     .collect::<Vec<(CellHandle, CellHandle)>>()
             })
-            .flatten()
             .collect::<Vec<(CellHandle, CellHandle)>>();
-        grid.link_cells_multi(links);
+        link_multi(grid, links, events);
     }
 }
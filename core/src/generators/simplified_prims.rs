@@ -0,0 +1,57 @@
+use rand::{Rng, RngCore};
+
+use crate::util::sample;
+use crate::Grid;
+
+use super::{link, start_cells, CarveEvent, Generator, Progress, StopCondition};
+
+/// Simplified Prim's maze generator
+/// Grows the maze from a random active cell,
+/// linking it to a random unvisited neighbor
+/// Perfect - Yes
+/// Uniform - No
+/// Bias - Radial texture, lots of short dead ends
+/// Runtime - Fast
+#[derive(Debug, Default)]
+pub struct SimplifiedPrims;
+
+impl Generator for SimplifiedPrims {
+    fn name(&self) -> &str {
+        "Simplified Prim's"
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let mut active = start_cells(grid, rng);
+
+        let mut progress = Progress::new(grid, stop);
+        while !active.is_empty() && !progress.is_done() {
+            let index = rng.gen_range(0..active.len());
+            let current = active[index];
+
+            let neighbors = {
+                let mut neighbors = grid.neighbors(current);
+                neighbors.retain(|neighbor_handle| {
+                    let neighbor = neighbor_handle.get_cell(grid).unwrap();
+                    !neighbor.has_links()
+                });
+                neighbors
+            };
+
+            // cells without unvisited neighbors are done
+            if neighbors.is_empty() {
+                active.swap_remove(index);
+            } else {
+                let neighbor = *sample(&neighbors, rng);
+                link(grid, current, neighbor, events);
+                progress.visit();
+                active.push(neighbor);
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use rand::{Rng, RngCore};
+
+use crate::{CellHandle, Grid};
+
+use super::{link, start_cells, CarveEvent, Generator, Progress, StopCondition};
+
+/// True Prim's maze generator
+/// Assigns every cell a random cost and grows the maze
+/// from the cheapest active cell to its cheapest unvisited neighbor
+/// Perfect - Yes
+/// Uniform - No
+/// Bias - Radial texture, lots of short dead ends
+/// Runtime - Fast
+#[derive(Debug, Default)]
+pub struct TruePrims;
+
+impl Generator for TruePrims {
+    fn name(&self) -> &str {
+        "True Prim's"
+    }
+
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        let costs = grid
+            .handles_iter()
+            .map(|handle| (handle, rng.gen_range(0..100)))
+            .collect::<HashMap<CellHandle, usize>>();
+
+        // TODO: a priority queue would avoid the linear searches
+        let mut active = start_cells(grid, rng);
+
+        let mut progress = Progress::new(grid, stop);
+        while !active.is_empty() && !progress.is_done() {
+            let (index, current) = active
+                .iter()
+                .copied()
+                .enumerate()
+                .min_by_key(|(_, handle)| costs[handle])
+                .unwrap();
+
+            let neighbor = grid
+                .neighbors(current)
+                .into_iter()
+                .filter(|neighbor_handle| {
+                    let neighbor = neighbor_handle.get_cell(grid).unwrap();
+                    !neighbor.has_links()
+                })
+                .min_by_key(|neighbor_handle| costs[neighbor_handle]);
+
+            // cells without unvisited neighbors are done
+            if let Some(neighbor) = neighbor {
+                link(grid, current, neighbor, events);
+                progress.visit();
+                active.push(neighbor);
+            } else {
+                active.swap_remove(index);
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
+use rand::RngCore;
+
 use crate::util::sample;
 use crate::{CellHandle, Grid};
 
-use super::Generator;
+use super::{link, CarveEvent, Generator, Progress, StopCondition};
 
 /// Aldous-Broder maze generator
 /// Loop-erasing random walk of cells until all are visited
@@ -17,38 +19,64 @@ impl Generator for Wilsons {
         "Wilson's Algorithm"
     }
 
-    fn run(&self, grid: &mut Grid) {
-        let mut unvisited = grid.handles_iter().collect::<Vec<CellHandle>>();
+    fn run_events(
+        &self,
+        grid: &mut Grid,
+        rng: &mut dyn RngCore,
+        stop: StopCondition,
+        events: &mut dyn FnMut(CarveEvent),
+    ) {
+        // linked cells have already been visited
+        let mut unvisited = grid
+            .iter()
+            .filter(|cell| !cell.has_links())
+            .map(|cell| cell.handle())
+            .collect::<Vec<CellHandle>>();
+        if unvisited.is_empty() {
+            return;
+        }
 
-        // visit the first cell
-        let first = *sample(&unvisited);
-        let unvisited_index = unvisited.iter().position(|&c| c == first).unwrap();
-        unvisited.swap_remove(unvisited_index);
+        // visit the first cell if nothing has been yet
+        if unvisited.len() == grid.enabled_count() {
+            let first = *sample(&unvisited, rng);
+            let unvisited_index = unvisited.iter().position(|&c| c == first).unwrap();
+            unvisited.swap_remove(unvisited_index);
+        }
 
         // visit everything else starting with a random unvisited cell
-        while !unvisited.is_empty() {
-            let mut cell_handle = *sample(&unvisited);
+        let mut progress = Progress::new(grid, stop);
+        while !unvisited.is_empty() && !progress.is_done() {
+            let mut cell_handle = *sample(&unvisited, rng);
             let mut path = vec![cell_handle];
+            events(CarveEvent::Visit(cell_handle));
 
             // random walk unvisited cells
             // building a path between them
             // erasing loops as we go
             while unvisited.contains(&cell_handle) {
                 let cell = cell_handle.get_cell(grid).unwrap();
-                cell_handle = cell.get_random_neighbor();
+                cell_handle = cell.get_random_neighbor(rng);
 
                 let position = path.iter().position(|&c| c == cell_handle);
                 if let Some(position) = position {
                     // we've hit a loop, so erase it
                     path.truncate(position + 1);
+                    events(CarveEvent::Backtrack(cell_handle));
                 } else {
                     path.push(cell_handle);
+                    events(CarveEvent::Visit(cell_handle));
                 }
             }
 
-            // carve the path
-            for index in 0..=path.len() - 2 {
-                grid.link_cells(path[index], path[index + 1]);
+            // carve the path back from the visited cell it hit,
+            // so that stopping part way leaves it connected
+            for index in (0..path.len() - 1).rev() {
+                if progress.is_done() {
+                    break;
+                }
+
+                link(grid, path[index + 1], path[index], events);
+                progress.visit();
 
                 let unvisited_index = unvisited.iter().position(|&c| c == path[index]).unwrap();
                 unvisited.swap_remove(unvisited_index);
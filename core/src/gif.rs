@@ -0,0 +1,244 @@
+use std::io::{self, Write};
+
+/// Quantization speed for frames with more than 256 colors (1 is best, 30 is fastest)
+const SPEED: i32 = 10;
+
+/// Rectangle of an image (x, y, width, height)
+type Rect = (usize, usize, usize, usize);
+
+/// Animated GIF encoder
+/// Frames are RGBA and are flattened onto white,
+/// and only the part of each frame that changed is written
+pub(crate) struct GifEncoder<W: Write> {
+    encoder: gif::Encoder<W>,
+    size: (usize, usize),
+    previous: Vec<[u8; 3]>,
+    pending: Option<gif::Frame<'static>>,
+}
+
+impl<W: Write> GifEncoder<W> {
+    /// Writes the GIF header for an animation of the given size
+    /// that repeats the given number of times (0 loops forever)
+    pub(crate) fn new(writer: W, size: (usize, usize), loops: u16) -> io::Result<Self> {
+        let (width, height) = (dimension(size.0)?, dimension(size.1)?);
+
+        // every frame has its own palette
+        let mut encoder =
+            gif::Encoder::new(writer, width, height, &[]).map_err(io::Error::other)?;
+        let repeat = match loops {
+            0 => gif::Repeat::Infinite,
+            loops => gif::Repeat::Finite(loops),
+        };
+        encoder.set_repeat(repeat).map_err(io::Error::other)?;
+
+        Ok(Self {
+            encoder,
+            size,
+            previous: Vec::new(),
+            pending: None,
+        })
+    }
+
+    /// Adds a frame that's shown for the given delay in hundredths of a second
+    /// Frames that don't change anything extend the previous frame instead
+    pub(crate) fn add_frame(&mut self, pixels: &[u8], delay: usize) -> io::Result<()> {
+        let pixels = pixels
+            .chunks_exact(4)
+            .map(flatten)
+            .collect::<Vec<[u8; 3]>>();
+        assert_eq!(pixels.len(), self.size.0 * self.size.1);
+
+        let rect = if self.previous.is_empty() {
+            Some((0, 0, self.size.0, self.size.1))
+        } else {
+            changed_rect(self.size.0, &self.previous, &pixels)
+        };
+
+        let (x, y, width, height) = match rect {
+            Some(rect) => rect,
+            None => {
+                if let Some(pending) = &mut self.pending {
+                    pending.delay = frame_delay(pending.delay as usize + delay);
+                }
+                return Ok(());
+            }
+        };
+
+        let rgb = (y..y + height)
+            .flat_map(|row| &pixels[row * self.size.0 + x..row * self.size.0 + x + width])
+            .flatten()
+            .copied()
+            .collect::<Vec<u8>>();
+
+        // the rest of the previous frame is left in place
+        let mut frame =
+            gif::Frame::from_rgb_speed(dimension(width)?, dimension(height)?, &rgb, SPEED);
+        frame.left = dimension(x)?;
+        frame.top = dimension(y)?;
+        frame.delay = frame_delay(delay);
+        frame.dispose = gif::DisposalMethod::Keep;
+
+        if let Some(pending) = self.pending.replace(frame) {
+            self.encoder
+                .write_frame(&pending)
+                .map_err(io::Error::other)?;
+        }
+
+        self.previous = pixels;
+
+        Ok(())
+    }
+
+    /// Writes the last frame and the GIF trailer
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        if let Some(pending) = self.pending.take() {
+            self.encoder
+                .write_frame(&pending)
+                .map_err(io::Error::other)?;
+        }
+
+        let mut writer = self.encoder.into_inner()?;
+        writer.flush()?;
+
+        Ok(writer)
+    }
+}
+
+/// Returns a GIF dimension, which can't be bigger than 16 bits
+fn dimension(value: usize) -> io::Result<u16> {
+    u16::try_from(value)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "GIF frame too large"))
+}
+
+/// Returns a GIF frame delay, which is capped at 16 bits
+fn frame_delay(delay: usize) -> u16 {
+    delay.min(u16::MAX as usize) as u16
+}
+
+/// Blends an RGBA pixel onto white
+fn flatten(pixel: &[u8]) -> [u8; 3] {
+    let alpha = pixel[3] as u32;
+    let blend = |value: u8| ((value as u32 * alpha + 255 * (255 - alpha)) / 255) as u8;
+    [blend(pixel[0]), blend(pixel[1]), blend(pixel[2])]
+}
+
+/// Returns the smallest rectangle containing every pixel that changed
+fn changed_rect(width: usize, previous: &[[u8; 3]], pixels: &[[u8; 3]]) -> Option<Rect> {
+    let mut changed = None;
+    for (index, _) in pixels
+        .iter()
+        .zip(previous)
+        .enumerate()
+        .filter(|(_, (pixel, previous))| pixel != previous)
+    {
+        let (x, y) = (index % width, index / width);
+        let (min_x, min_y, max_x, max_y) = changed.unwrap_or((x, y, x, y));
+        changed = Some((min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)));
+    }
+
+    changed.map(|(min_x, min_y, max_x, max_y)| (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::util::seeded_rng;
+
+    const SIZE: (usize, usize) = (120, 90);
+
+    /// The delay and composited RGB pixels of a decoded frame
+    type DecodedFrame = (u16, Vec<[u8; 3]>);
+
+    /// Decodes a GIF, returning its loop count and frames
+    fn decode(bytes: &[u8]) -> (gif::Repeat, Vec<DecodedFrame>) {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes).unwrap();
+
+        let mut canvas = vec![[0; 3]; SIZE.0 * SIZE.1];
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            let (left, top) = (frame.left as usize, frame.top as usize);
+            for (index, pixel) in frame.buffer.chunks_exact(4).enumerate() {
+                let (x, y) = (
+                    left + index % frame.width as usize,
+                    top + index / frame.width as usize,
+                );
+                canvas[y * SIZE.0 + x] = [pixel[0], pixel[1], pixel[2]];
+            }
+            frames.push((frame.delay, canvas.clone()));
+        }
+
+        (decoder.repeat(), frames)
+    }
+
+    fn flattened(pixels: &[u8]) -> Vec<[u8; 3]> {
+        pixels.chunks_exact(4).map(flatten).collect()
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let mut rng = seeded_rng(1);
+
+        // noisy enough that every frame needs its own palette
+        let noise = (0..SIZE.0 * SIZE.1)
+            .flat_map(|_| [rng.gen_range(0..4) * 60, rng.gen_range(0..8) * 30, 0, 255])
+            .collect::<Vec<u8>>();
+
+        // away from the corner so the frame is offset
+        let mut changed = noise.clone();
+        let start = 30 * SIZE.0 + 40;
+        for pixel in changed[4 * start..4 * (start + 30)].chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[0, 0, 255, 128]);
+        }
+
+        let mut encoder = GifEncoder::new(Vec::new(), SIZE, 2).unwrap();
+        encoder.add_frame(&noise, 3).unwrap();
+        encoder.add_frame(&noise, 4).unwrap();
+        encoder.add_frame(&changed, 5).unwrap();
+        let bytes = encoder.finish().unwrap();
+
+        let (repeat, frames) = decode(&bytes);
+        assert_eq!(repeat, gif::Repeat::Finite(2));
+
+        // the unchanged frame only extends the one before it
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].0, 7);
+        assert_eq!(frames[1].0, 5);
+        assert!(frames[0].1 == flattened(&noise));
+        assert!(frames[1].1 == flattened(&changed));
+    }
+
+    #[test]
+    fn too_many_colors_are_reduced() {
+        let pixels = (0..SIZE.0 * SIZE.1)
+            .flat_map(|index| [(index % 256) as u8, (index / 256 * 13) as u8, 77, 255])
+            .collect::<Vec<u8>>();
+
+        let mut encoder = GifEncoder::new(Vec::new(), SIZE, 0).unwrap();
+        encoder.add_frame(&pixels, 1).unwrap();
+        let bytes = encoder.finish().unwrap();
+
+        let (repeat, frames) = decode(&bytes);
+        assert_eq!(repeat, gif::Repeat::Infinite);
+        assert_eq!(frames.len(), 1);
+
+        // the palette is reduced, but every pixel stays close to its color
+        let errors = frames[0]
+            .1
+            .iter()
+            .zip(flattened(&pixels))
+            .map(|(decoded, pixel)| {
+                (0..3)
+                    .map(|channel| (decoded[channel] as i32 - pixel[channel] as i32).abs())
+                    .max()
+                    .unwrap()
+            })
+            .collect::<Vec<i32>>();
+        let mean = errors.iter().sum::<i32>() as f64 / errors.len() as f64;
+        assert!(mean < 16.0, "mean error {}", mean);
+        assert!(errors.iter().all(|&error| error < 64));
+    }
+}
@@ -1,28 +1,266 @@
-use std::fs;
-use std::io;
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt;
 use std::iter::Iterator;
-use std::path::Path;
+use std::str::FromStr;
 
-use rand::Rng;
-use tracing::debug;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
 
+use crate::render::{point, Point, RenderOptions, Renderer, TerminalRenderer};
 use crate::solvers::Solver;
-use crate::util::{circle, quad, Color};
-use crate::{Cell, CellHandle, Mask, Renderable};
+use crate::util::{sample, Color};
+use crate::{Cell, CellHandle, LayeredCell, Mask, OrthogonalCell, Renderable};
+
+/// Supported grid topologies
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum GridType {
+    #[default]
+    Orthogonal,
+    Polar,
+    Hex,
+    Triangle,
+    Weave,
+    Cylinder,
+    Torus,
+    Mobius,
+    Layered(usize),
+    Cube,
+    Sphere,
+}
+
+impl GridType {
+    /// Returns true if the grid type wraps around any of its edges
+    pub fn is_wrapped(&self) -> bool {
+        matches!(self, Self::Cylinder | Self::Torus | Self::Mobius)
+    }
+
+    /// Returns the smallest number of rows and columns a grid of this type can have
+    /// Smaller grids would leave cells without any neighbors
+    pub fn min_size(&self) -> (usize, usize) {
+        match self {
+            // the center cell needs a ring around it
+            Self::Polar => (2, 1),
+
+            // a single column of triangles only pairs up cells sharing a base
+            Self::Triangle => (1, 2),
+
+            // wrapping too few rows or columns would make cells neighbor themselves
+            Self::Cylinder | Self::Mobius => (1, 3),
+            Self::Torus => (3, 3),
+            _ => (1, 1),
+        }
+    }
+
+    /// Returns an error if a grid of this type can't have the given number of rows and columns
+    /// Polar, cube and sphere grids only use the rows
+    pub fn check_size(&self, rows: usize, cols: usize) -> Result<(), String> {
+        let (min_rows, min_cols) = self.min_size();
+        if matches!(self, Self::Polar | Self::Cube | Self::Sphere) {
+            if rows < min_rows {
+                return Err(format!(
+                    "Invalid {} grid size {}, must be at least {}",
+                    self, rows, min_rows
+                ));
+            }
+            return Ok(());
+        }
+
+        if rows < min_rows || cols < min_cols {
+            return Err(format!(
+                "Invalid {} grid size {}x{}, must be at least {}x{}",
+                self, rows, cols, min_rows, min_cols
+            ));
+        }
+
+        // a lone cell has nothing to link to
+        let cells = match self {
+            Self::Layered(levels) => levels * rows * cols,
+            _ => rows * cols,
+        };
+        if cells < 2 {
+            return Err(format!(
+                "Invalid {} grid size {}x{}, must have more than one cell",
+                self, rows, cols
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for GridType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Orthogonal => write!(f, "orthogonal"),
+            Self::Polar => write!(f, "polar"),
+            Self::Hex => write!(f, "hex"),
+            Self::Triangle => write!(f, "triangle"),
+            Self::Weave => write!(f, "weave"),
+            Self::Cylinder => write!(f, "cylinder"),
+            Self::Torus => write!(f, "torus"),
+            Self::Mobius => write!(f, "mobius"),
+            Self::Layered(_) => write!(f, "layered"),
+            Self::Cube => write!(f, "cube"),
+            Self::Sphere => write!(f, "sphere"),
+        }
+    }
+}
+
+impl FromStr for GridType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "orthogonal" | "ortho" => Ok(Self::Orthogonal),
+            "polar" => Ok(Self::Polar),
+            "hex" => Ok(Self::Hex),
+            "triangle" | "delta" => Ok(Self::Triangle),
+            "weave" => Ok(Self::Weave),
+            "cylinder" => Ok(Self::Cylinder),
+            "torus" => Ok(Self::Torus),
+            "mobius" | "möbius" => Ok(Self::Mobius),
+            "layered" => Ok(Self::Layered(LayeredGrid::DEFAULT_LEVELS)),
+            "cube" => Ok(Self::Cube),
+            "sphere" => Ok(Self::Sphere),
+            // layered grids may specify their number of levels (layered:levels)
+            _ => match s.strip_prefix("layered:") {
+                Some(levels) => levels
+                    .parse()
+                    .map(Self::Layered)
+                    .map_err(|_| format!("Invalid level count: {}", levels)),
+                None => Err(format!("Invalid grid type: {}", s)),
+            },
+        }
+    }
+}
+
+/// How far to cull a maze's dead ends
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CullLimit {
+    /// Cull every dead end this many times
+    Iterations(usize),
+
+    /// Cull dead ends until this fraction of the cells remain
+    Remaining(f64),
+}
+
+impl fmt::Display for CullLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Iterations(iterations) => write!(f, "{}", iterations),
+            Self::Remaining(fraction) => write!(f, "{}%", fraction * 100.0),
+        }
+    }
+}
+
+impl FromStr for CullLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // percentages are the fraction of cells to keep (percent%)
+        match s.strip_suffix('%') {
+            Some(percent) => percent
+                .parse::<f64>()
+                .map(|percent| Self::Remaining(percent / 100.0))
+                .map_err(|_| format!("Invalid percentage: {}", percent)),
+            None => s
+                .parse()
+                .map(Self::Iterations)
+                .map_err(|_| format!("Invalid iteration count: {}", s)),
+        }
+    }
+}
+
+/// Orthogonal grid edges that wrap around to the opposite edge
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Wrap {
+    /// No edges wrap
+    #[default]
+    None,
+
+    /// The east and west edges wrap
+    Cylinder,
+
+    /// The east and west edges and the north and south edges wrap
+    Torus,
+
+    /// The east and west edges wrap with the seam flipped
+    Mobius,
+}
+
+impl Wrap {
+    fn wraps_columns(&self) -> bool {
+        !matches!(self, Self::None)
+    }
+
+    fn wraps_rows(&self) -> bool {
+        matches!(self, Self::Torus)
+    }
+
+    // the flipped seam of a Möbius strip can't be tiled
+    fn tiles_columns(&self) -> bool {
+        matches!(self, Self::Cylinder | Self::Torus)
+    }
+
+    fn tiles_rows(&self) -> bool {
+        matches!(self, Self::Torus)
+    }
+}
 
 /// Grid-based maze data structure
 #[derive(Debug, Clone)]
 pub enum Grid {
     Orthogonal(OrthogonalGrid),
     Polar(PolarGrid),
+    Hex(HexGrid),
+    Triangle(TriangleGrid),
+    Weave(WeaveGrid),
+    Layered(LayeredGrid),
+    Cube(CubeGrid),
+    Sphere(SphereGrid),
 }
 
 impl Grid {
+    /// Creates a new grid of the given type and size
+    pub fn new(rows: usize, cols: usize, grid_type: GridType) -> Self {
+        match grid_type {
+            GridType::Orthogonal => Self::new_ortho(rows, cols),
+            GridType::Polar => Self::new_polar(rows),
+            GridType::Hex => Self::new_hex(rows, cols),
+            GridType::Triangle => Self::new_triangle(rows, cols),
+            GridType::Weave => Self::new_weave(rows, cols),
+            GridType::Cylinder => Self::new_wrapped(rows, cols, Wrap::Cylinder),
+            GridType::Torus => Self::new_wrapped(rows, cols, Wrap::Torus),
+            GridType::Mobius => Self::new_wrapped(rows, cols, Wrap::Mobius),
+            GridType::Layered(levels) => Self::new_layered(levels, rows, cols),
+            GridType::Cube => Self::new_cube(rows),
+            GridType::Sphere => Self::new_sphere(rows),
+        }
+    }
+
+    /// Creates a new grid of the given type from the given mask
+    pub fn from_mask(mask: Mask, grid_type: GridType) -> Self {
+        match grid_type {
+            GridType::Orthogonal => Self::from_ortho_mask(mask),
+            GridType::Polar => Self::from_polar_mask(mask),
+            GridType::Hex => Self::from_hex_mask(mask),
+            GridType::Triangle => Self::from_triangle_mask(mask),
+            GridType::Weave => Self::from_weave_mask(mask),
+            GridType::Cylinder => Self::from_wrapped_mask(mask, Wrap::Cylinder),
+            GridType::Torus => Self::from_wrapped_mask(mask, Wrap::Torus),
+            GridType::Mobius => Self::from_wrapped_mask(mask, Wrap::Mobius),
+            GridType::Layered(levels) => Self::from_layered_mask(levels, mask),
+            GridType::Cube => Self::from_cube_mask(mask),
+            GridType::Sphere => Self::from_sphere_mask(mask),
+        }
+    }
+
     /// Creates a new orthogonal grid of the given size
     pub fn new_ortho(rows: usize, cols: usize) -> Self {
         assert!(rows > 0 && cols > 0);
 
-        let grid = OrthogonalGrid::new(rows, cols);
+        let mut grid = OrthogonalGrid::new(rows, cols);
         grid.init_grid();
         grid.init_cells();
 
@@ -33,18 +271,49 @@ impl Grid {
     pub fn from_ortho_mask(mask: Mask) -> Self {
         assert!(mask.rows > 0 && mask.cols > 0);
 
-        let grid = OrthogonalGrid::from_mask(mask);
+        let mut grid = OrthogonalGrid::from_mask(mask);
         grid.init_grid();
         grid.init_cells();
 
         Self::Orthogonal(grid)
     }
 
-    /// Creates a new polar grid of the given size
-    pub fn new_polar(rows: usize, cols: usize) -> Self {
+    /// Creates a new orthogonal grid of the given size
+    /// with edges that wrap around to the opposite edge
+    pub fn new_wrapped(rows: usize, cols: usize, wrap: Wrap) -> Self {
+        // wrapping too few rows or columns would make cells neighbor themselves
         assert!(rows > 0 && cols > 0);
+        assert!(!wrap.wraps_columns() || cols > 2);
+        assert!(!wrap.wraps_rows() || rows > 2);
+
+        let mut grid = OrthogonalGrid::new(rows, cols);
+        grid.wrap = wrap;
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Orthogonal(grid)
+    }
+
+    /// Creates a new orthogonal grid from the given mask
+    /// with edges that wrap around to the opposite edge
+    pub fn from_wrapped_mask(mask: Mask, wrap: Wrap) -> Self {
+        assert!(mask.rows > 0 && mask.cols > 0);
+        assert!(!wrap.wraps_columns() || mask.cols > 2);
+        assert!(!wrap.wraps_rows() || mask.rows > 2);
 
-        let grid = PolarGrid::new(rows, cols);
+        let mut grid = OrthogonalGrid::from_mask(mask);
+        grid.wrap = wrap;
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Orthogonal(grid)
+    }
+
+    /// Creates a new polar grid with the given number of rings
+    pub fn new_polar(rows: usize) -> Self {
+        assert!(rows > 1);
+
+        let mut grid = PolarGrid::new(rows);
         grid.init_grid();
         grid.init_cells();
 
@@ -52,55 +321,243 @@ impl Grid {
     }
 
     /// Creates a new polar grid from the given mask
+    /// Each mask row is a ring, with the mask columns
+    /// spread evenly around the ring
     pub fn from_polar_mask(mask: Mask) -> Self {
-        assert!(mask.rows > 0 && mask.cols > 0);
+        assert!(mask.rows > 1 && mask.cols > 0);
 
-        let grid = PolarGrid::from_mask(mask);
+        let mut grid = PolarGrid::from_mask(mask);
         grid.init_grid();
         grid.init_cells();
 
         Self::Polar(grid)
     }
 
+    /// Creates a new hex grid of the given size
+    pub fn new_hex(rows: usize, cols: usize) -> Self {
+        assert!(rows > 0 && cols > 0);
+
+        let mut grid = HexGrid::new(rows, cols);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Hex(grid)
+    }
+
+    /// Creates a new hex grid from the given mask
+    pub fn from_hex_mask(mask: Mask) -> Self {
+        assert!(mask.rows > 0 && mask.cols > 0);
+
+        let mut grid = HexGrid::from_mask(mask);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Hex(grid)
+    }
+
+    /// Creates a new triangle grid of the given size
+    pub fn new_triangle(rows: usize, cols: usize) -> Self {
+        // a single column of triangles falls apart into pairs
+        assert!(rows > 0 && cols > 1);
+
+        let mut grid = TriangleGrid::new(rows, cols);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Triangle(grid)
+    }
+
+    /// Creates a new triangle grid from the given mask
+    pub fn from_triangle_mask(mask: Mask) -> Self {
+        assert!(mask.rows > 0 && mask.cols > 1);
+
+        let mut grid = TriangleGrid::from_mask(mask);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Triangle(grid)
+    }
+
+    /// Creates a new weave grid of the given size
+    pub fn new_weave(rows: usize, cols: usize) -> Self {
+        assert!(rows > 0 && cols > 0);
+
+        let mut grid = OrthogonalGrid::new(rows, cols);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Weave(WeaveGrid::new(grid))
+    }
+
+    /// Creates a new weave grid from the given mask
+    pub fn from_weave_mask(mask: Mask) -> Self {
+        assert!(mask.rows > 0 && mask.cols > 0);
+
+        let mut grid = OrthogonalGrid::from_mask(mask);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Weave(WeaveGrid::new(grid))
+    }
+
+    /// Creates a new layered grid with the given number of levels of the given size
+    pub fn new_layered(levels: usize, rows: usize, cols: usize) -> Self {
+        assert!(levels > 0 && rows > 0 && cols > 0);
+
+        let mut grid = LayeredGrid::new(levels, rows, cols);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Layered(grid)
+    }
+
+    /// Creates a new layered grid with the given number of levels from the given mask
+    /// The mask is applied to every level
+    pub fn from_layered_mask(levels: usize, mask: Mask) -> Self {
+        assert!(levels > 0 && mask.rows > 0 && mask.cols > 0);
+
+        let mut grid = LayeredGrid::from_mask(levels, mask);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Layered(grid)
+    }
+
+    /// Creates a new cube grid with faces of the given size
+    pub fn new_cube(size: usize) -> Self {
+        assert!(size > 0);
+
+        let mut grid = CubeGrid::new(size);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Cube(grid)
+    }
+
+    /// Creates a new cube grid from the given square mask
+    /// The mask is applied to every face
+    pub fn from_cube_mask(mask: Mask) -> Self {
+        assert!(mask.rows > 0 && mask.rows == mask.cols);
+
+        let mut grid = CubeGrid::from_mask(mask);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Cube(grid)
+    }
+
+    /// Creates a new sphere grid with the given number of rings per hemisphere
+    pub fn new_sphere(rows: usize) -> Self {
+        assert!(rows > 0);
+
+        let mut grid = SphereGrid::new(rows);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Sphere(grid)
+    }
+
+    /// Creates a new sphere grid from the given mask
+    /// Each mask row is a ring in both hemispheres, with the mask columns
+    /// spread evenly around the ring
+    pub fn from_sphere_mask(mask: Mask) -> Self {
+        assert!(mask.rows > 0 && mask.cols > 0);
+
+        let mut grid = SphereGrid::from_mask(mask);
+        grid.init_grid();
+        grid.init_cells();
+
+        Self::Sphere(grid)
+    }
+
+    /// The type of the grid
+    pub fn grid_type(&self) -> GridType {
+        match self {
+            Self::Orthogonal(grid) => match grid.wrap {
+                Wrap::None => GridType::Orthogonal,
+                Wrap::Cylinder => GridType::Cylinder,
+                Wrap::Torus => GridType::Torus,
+                Wrap::Mobius => GridType::Mobius,
+            },
+            Self::Polar(_) => GridType::Polar,
+            Self::Hex(_) => GridType::Hex,
+            Self::Triangle(_) => GridType::Triangle,
+            Self::Weave(_) => GridType::Weave,
+            Self::Layered(grid) => GridType::Layered(grid.levels),
+            Self::Cube(_) => GridType::Cube,
+            Self::Sphere(_) => GridType::Sphere,
+        }
+    }
+
     /// The number of rows in the grid
     pub fn rows(&self) -> usize {
         match self {
             Self::Orthogonal(grid) => grid.rows,
             Self::Polar(grid) => grid.rows,
+            Self::Hex(grid) => grid.rows,
+            Self::Triangle(grid) => grid.rows,
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.rows,
+            Self::Layered(grid) => grid.rows,
+            Self::Cube(grid) => grid.size,
+            Self::Sphere(grid) => grid.grid.len(),
         }
     }
 
     /// The number of columns in the grid
+    /// For polar grids this is the number of cells in the outermost ring
+    /// and for sphere grids the number of cells around the equator
     pub fn columns(&self) -> usize {
         match self {
             Self::Orthogonal(grid) => grid.cols,
             Self::Polar(grid) => grid.cols,
+            Self::Hex(grid) => grid.cols,
+            Self::Triangle(grid) => grid.cols,
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.cols,
+            Self::Layered(grid) => grid.cols,
+            Self::Cube(grid) => grid.size,
+            Self::Sphere(grid) => grid.cols,
         }
     }
 
     /// The number of cells in the grid
     pub fn size(&self) -> usize {
-        self.rows() * self.columns()
+        match self {
+            Self::Orthogonal(grid) => grid.size(),
+            Self::Polar(grid) => grid.grid.iter().map(Vec::len).sum(),
+            Self::Hex(grid) => grid.size(),
+            Self::Triangle(grid) => grid.size(),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.size(),
+            Self::Layered(grid) => grid.size(),
+            Self::Cube(grid) => grid.cell_count(),
+            Self::Sphere(grid) => grid.grid.iter().map(Vec::len).sum(),
+        }
     }
 
-    pub(crate) fn index(&self, row: usize, col: usize) -> usize {
-        row * self.columns() + col
+    /// The number of rows of cells stored in the grid
+    /// For layered grids this includes the rows of every level
+    fn row_count(&self) -> usize {
+        self.rows_iter().len()
     }
 
-    fn mask(&self) -> &Option<Mask> {
+    /// The number of cells in the given row
+    fn row_len(&self, row: usize) -> usize {
         match self {
-            Self::Orthogonal(grid) => &grid.mask,
-            Self::Polar(grid) => &grid.mask,
+            Self::Orthogonal(grid) => grid.grid.get(row).map_or(0, Vec::len),
+            Self::Polar(grid) => grid.grid.get(row).map_or(0, Vec::len),
+            Self::Hex(grid) => grid.grid.get(row).map_or(0, Vec::len),
+            Self::Triangle(grid) => grid.grid.get(row).map_or(0, Vec::len),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.grid.get(row).map_or(0, Vec::len),
+            Self::Layered(grid) => grid.grid.get(row).map_or(0, Vec::len),
+            Self::Cube(grid) => grid.grid.get(row).map_or(0, Vec::len),
+            Self::Sphere(grid) => grid.grid.get(row).map_or(0, Vec::len),
         }
     }
 
     /// The number of enabled cells in the grid
     pub fn enabled_count(&self) -> usize {
-        if let Some(mask) = self.mask() {
-            mask.count()
-        } else {
-            self.rows() * self.columns()
-        }
+        // orphaned cells are disabled without updating the mask,
+        // so this has to count the cells that are left
+        self.iter().count()
     }
 
     /// Returns true if the grid contains any orphaned cells
@@ -115,77 +572,217 @@ impl Grid {
             .collect()
     }
 
+    /// Removes the given fraction of dead ends by linking them to a neighbor,
+    /// preferring neighbors that are also dead ends
+    /// This adds loops, so the maze is no longer perfect
+    pub fn braid(&mut self, p: f64, rng: &mut dyn RngCore) {
+        let mut dead_ends = self
+            .get_dead_ends()
+            .iter()
+            .map(|cell| cell.handle())
+            .collect::<Vec<CellHandle>>();
+        dead_ends.shuffle(rng);
+
+        for handle in dead_ends {
+            // linking an earlier dead end may have already fixed this one
+            let cell = self.get_cell(handle).unwrap();
+            if cell.links().len() != 1 || !rng.gen_bool(p.clamp(0.0, 1.0)) {
+                continue;
+            }
+
+            let neighbors = self
+                .neighbors(handle)
+                .into_iter()
+                .filter(|neighbor| !cell.is_linked(*neighbor))
+                .collect::<Vec<CellHandle>>();
+
+            let dead_end_neighbors = neighbors
+                .iter()
+                .copied()
+                .filter(|neighbor| self.get_cell(*neighbor).unwrap().links().len() == 1)
+                .collect::<Vec<CellHandle>>();
+
+            let neighbor = if !dead_end_neighbors.is_empty() {
+                *sample(&dead_end_neighbors, rng)
+            } else if !neighbors.is_empty() {
+                *sample(&neighbors, rng)
+            } else {
+                continue;
+            };
+
+            self.link_cells(handle, neighbor);
+        }
+    }
+
     /// Gets a reference to the given cell if it exists
     pub fn get(&self, row: usize, col: usize) -> Option<&Cell> {
-        let cell = match self {
-            Self::Orthogonal(grid) => grid.grid.get(row)?.get(col)?,
-            Self::Polar(grid) => grid.grid.get(row)?.get(col)?,
-        };
-
-        if let Some(cell) = cell {
-            return Some(cell);
+        match self {
+            Self::Orthogonal(grid) => grid.get(row, col),
+            Self::Polar(grid) => grid.get(row, col),
+            Self::Hex(grid) => grid.get(row, col),
+            Self::Triangle(grid) => grid.get(row, col),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.get(row, col),
+            Self::Layered(grid) => grid.get(row, col),
+            Self::Cube(grid) => grid.get(row, col),
+            Self::Sphere(grid) => grid.get(row, col),
         }
-        None
     }
 
     /// Gets a mutable reference to the given cell if it exists
     pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
-        let cell = match self {
-            Self::Orthogonal(grid) => grid.grid.get_mut(row)?.get_mut(col)?,
-            Self::Polar(grid) => grid.grid.get_mut(row)?.get_mut(col)?,
-        };
+        match self {
+            Self::Orthogonal(grid) => grid.get_mut(row, col),
+            Self::Polar(grid) => grid.get_mut(row, col),
+            Self::Hex(grid) => grid.get_mut(row, col),
+            Self::Triangle(grid) => grid.get_mut(row, col),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.get_mut(row, col),
+            Self::Layered(grid) => grid.get_mut(row, col),
+            Self::Cube(grid) => grid.get_mut(row, col),
+            Self::Sphere(grid) => grid.get_mut(row, col),
+        }
+    }
+
+    /// Gets a reference to the cell with the given handle if it exists
+    /// This includes weave grid under cells
+    pub fn get_cell(&self, handle: CellHandle) -> Option<&Cell> {
+        match self {
+            Self::Weave(grid) => grid.get_cell(handle),
+            _ => self.get(handle.row, handle.col),
+        }
+    }
 
-        if let Some(cell) = cell {
-            return Some(cell);
+    /// Gets a mutable reference to the cell with the given handle if it exists
+    /// This includes weave grid under cells
+    pub fn get_cell_mut(&mut self, handle: CellHandle) -> Option<&mut Cell> {
+        match self {
+            Self::Weave(grid) => grid.get_cell_mut(handle),
+            _ => self.get_mut(handle.row, handle.col),
         }
-        None
+    }
+
+    /// Gets the neighbors of the given cell
+    /// Weave grids also include cells that can be reached by tunneling under a neighbor
+    pub fn neighbors(&self, handle: CellHandle) -> Vec<CellHandle> {
+        let cell = self.get_cell(handle).unwrap();
+
+        let mut neighbors = cell.neighbors();
+        if let Self::Weave(grid) = self {
+            neighbors.extend(grid.tunnel_neighbors(cell));
+        }
+
+        neighbors
+    }
+
+    /// Gets a random neighbor of the given cell
+    /// Weave grids may tunnel under a neighbor
+    pub fn get_random_neighbor(&self, handle: CellHandle, rng: &mut dyn RngCore) -> CellHandle {
+        let neighbors = self.neighbors(handle);
+        *sample(&neighbors, rng)
     }
 
     fn get_row_mut(&mut self, row: usize) -> Option<&mut Vec<Option<Cell>>> {
         match self {
             Self::Orthogonal(grid) => grid.grid.get_mut(row),
             Self::Polar(grid) => grid.grid.get_mut(row),
+            Self::Hex(grid) => grid.grid.get_mut(row),
+            Self::Triangle(grid) => grid.grid.get_mut(row),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.grid.get_mut(row),
+            Self::Layered(grid) => grid.grid.get_mut(row),
+            Self::Cube(grid) => grid.grid.get_mut(row),
+            Self::Sphere(grid) => grid.grid.get_mut(row),
         }
     }
 
     /// Returns a random enabled cell
-    fn get_random_cell(&self) -> CellHandle {
-        if let Some(mask) = self.mask() {
-            mask.get_random().into()
-        } else {
-            let mut rng = rand::thread_rng();
-            (
-                rng.gen_range(0..self.rows()),
-                rng.gen_range(0..self.columns()),
-            )
-                .into()
+    fn get_random_cell(&self, rng: &mut dyn RngCore) -> CellHandle {
+        // orphaned cells are disabled without updating the mask,
+        // so keep trying until an enabled cell comes up
+        loop {
+            let cell = self.get_random_masked_cell(rng);
+            if self.get(cell.row, cell.col).is_some() {
+                return cell;
+            }
+        }
+    }
+
+    /// Returns a random cell enabled by the grid mask
+    fn get_random_masked_cell(&self, rng: &mut dyn RngCore) -> CellHandle {
+        match self {
+            Self::Orthogonal(grid) => get_random_cell(grid.rows, grid.cols, &grid.mask, rng),
+            Self::Polar(_) | Self::Sphere(_) => {
+                // rings have different cell counts
+                // so sample from all of the enabled cells
+                let cells = self.handles_iter().collect::<Vec<CellHandle>>();
+                *sample(&cells, rng)
+            }
+            Self::Hex(grid) => get_random_cell(grid.rows, grid.cols, &grid.mask, rng),
+            Self::Triangle(grid) => get_random_cell(grid.rows, grid.cols, &grid.mask, rng),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => {
+                get_random_cell(grid.rows, grid.cols, &grid.mask, rng)
+            }
+            Self::Layered(grid) => grid.get_random_cell(rng),
+            Self::Cube(grid) => grid.get_random_cell(rng),
         }
     }
 
     /// Returns the first enabled cell
     fn get_first_enabled(&self) -> Option<CellHandle> {
-        Some(if let Some(mask) = self.mask() {
-            mask.get_first_enabled()?.into()
-        } else {
-            CellHandle::new(0, 0)
-        })
+        self.handles_iter().next()
+    }
+
+    /// Returns the enabled cell nearest the center of the grid
+    /// Masking or culling may have disabled the cell at the center itself
+    pub(crate) fn center(&self) -> CellHandle {
+        let center = match self {
+            Self::Orthogonal(grid) => CellHandle::new(grid.rows / 2, grid.cols / 2),
+            Self::Polar(_) => CellHandle::new(0, 0),
+            Self::Hex(grid) => CellHandle::new(grid.rows / 2, grid.cols / 2),
+            Self::Triangle(grid) => CellHandle::new(grid.rows / 2, grid.cols / 2),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => {
+                CellHandle::new(grid.rows / 2, grid.cols / 2)
+            }
+            Self::Layered(grid) => CellHandle::new(grid.rows / 2, grid.cols / 2),
+            Self::Cube(grid) => CellHandle::new(grid.size / 2, grid.size / 2),
+            Self::Sphere(_) => CellHandle::new(0, 0),
+        };
+
+        if self.get_cell(center).is_some() {
+            return center;
+        }
+
+        self.handles_iter()
+            .min_by_key(|cell| {
+                let (rows, cols) = (cell.row.abs_diff(center.row), cell.col.abs_diff(center.col));
+                rows * rows + cols * cols
+            })
+            .unwrap_or(center)
     }
 
     /// Gets a reference to a random enabled cell
-    pub fn get_random(&self) -> &Cell {
-        let cell = self.get_random_cell();
+    pub fn get_random(&self, rng: &mut dyn RngCore) -> &Cell {
+        let cell = self.get_random_cell(rng);
         self.get(cell.row, cell.col).unwrap()
     }
 
     /// Gets a mutable reference to a random enabled cell
-    pub fn get_random_mut(&mut self) -> &mut Cell {
-        let cell = self.get_random_cell();
+    pub fn get_random_mut(&mut self, rng: &mut dyn RngCore) -> &mut Cell {
+        let cell = self.get_random_cell(rng);
         self.get_mut(cell.row, cell.col).unwrap()
     }
 
     /// Orphans a cell
+    /// Orphaned cells are disabled the same as masked cells
     pub fn orphan(&mut self, row: usize, col: usize) {
-        // remove this cell from its neighbors first
+        // unlink this cell first
+        if let Some(cell) = self.get(row, col).filter(|cell| !cell.is_orphaned()) {
+            let handle = cell.handle();
+            let links = cell.links().copied().collect::<Vec<CellHandle>>();
+            for link in links {
+                self.unlink_cells(handle, link);
+            }
+        }
+
+        // then remove this cell from its neighbors
         // TODO: having to clone here kinda sucks
         // is there a better way we could handle this?
         if let Some(cell) = self.get(row, col) {
@@ -196,6 +793,71 @@ impl Grid {
         if let Some(cell) = self.get_mut(row, col) {
             cell.orphan();
         }
+
+        // and finally disable it
+        if let Some(cell) = self.get_row_mut(row).and_then(|cells| cells.get_mut(col)) {
+            *cell = None;
+        }
+    }
+
+    /// Repeatedly orphans dead ends to shrink the maze to a sparse set of passages
+    /// Passages are never culled down to less than two cells
+    /// and dead ends leading into weave grid crossings are left in place
+    pub fn cull(&mut self, limit: CullLimit, rng: &mut dyn RngCore) {
+        let (iterations, target) = match limit {
+            CullLimit::Iterations(iterations) => (iterations, 0),
+            CullLimit::Remaining(fraction) => {
+                let cells = self.enabled_count() as f64;
+                (
+                    usize::MAX,
+                    (fraction.clamp(0.0, 1.0) * cells).ceil() as usize,
+                )
+            }
+        };
+
+        let mut remaining = self.enabled_count();
+        for _ in 0..iterations {
+            let mut dead_ends = self
+                .get_dead_ends()
+                .iter()
+                .filter(|cell| self.can_cull(cell))
+                .map(|cell| cell.handle())
+                .collect::<Vec<CellHandle>>();
+            if dead_ends.is_empty() || remaining <= target {
+                break;
+            }
+
+            // only some of the dead ends are needed to reach the target
+            dead_ends.shuffle(rng);
+            dead_ends.truncate(remaining - target);
+
+            for handle in dead_ends {
+                // culling an earlier dead end may have changed this one
+                let cell = self.get_cell(handle).unwrap();
+                if !self.can_cull(cell) {
+                    continue;
+                }
+
+                self.orphan(handle.row, handle.col);
+                remaining -= 1;
+            }
+        }
+    }
+
+    /// Returns true if the cell is a dead end that can be culled
+    fn can_cull(&self, cell: &Cell) -> bool {
+        let mut links = cell.links();
+        match (links.next(), links.next()) {
+            (Some(link), None) => {
+                let crossing = matches!(self, Self::Weave(_))
+                    && self
+                        .get_cell(CellHandle::new_under(link.row, link.col))
+                        .is_some();
+
+                !link.under && !crossing && self.get_cell(*link).unwrap().links().len() > 1
+            }
+            _ => false,
+        }
     }
 
     /// Returns an iterator over the grid rows
@@ -203,6 +865,12 @@ impl Grid {
         match self {
             Self::Orthogonal(grid) => grid.grid.iter(),
             Self::Polar(grid) => grid.grid.iter(),
+            Self::Hex(grid) => grid.grid.iter(),
+            Self::Triangle(grid) => grid.grid.iter(),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.grid.iter(),
+            Self::Layered(grid) => grid.grid.iter(),
+            Self::Cube(grid) => grid.grid.iter(),
+            Self::Sphere(grid) => grid.grid.iter(),
         }
     }
 
@@ -211,6 +879,12 @@ impl Grid {
         match self {
             Self::Orthogonal(grid) => grid.grid.iter_mut(),
             Self::Polar(grid) => grid.grid.iter_mut(),
+            Self::Hex(grid) => grid.grid.iter_mut(),
+            Self::Triangle(grid) => grid.grid.iter_mut(),
+            Self::Weave(WeaveGrid { ortho: grid, .. }) => grid.grid.iter_mut(),
+            Self::Layered(grid) => grid.grid.iter_mut(),
+            Self::Cube(grid) => grid.grid.iter_mut(),
+            Self::Sphere(grid) => grid.grid.iter_mut(),
         }
     }
 
@@ -231,13 +905,21 @@ impl Grid {
 
     /// Links two cells bidirectionally
     /// This creates a path between the cells
+    /// Weave grids link cells two apart by tunneling under the cell between them
     pub(crate) fn link_cells(&mut self, a: CellHandle, b: CellHandle) {
-        if let Some(a) = self.get_mut(a.row, a.col) {
+        if let Self::Weave(grid) = self {
+            if let Some(over) = WeaveGrid::get_tunnel_over(a, b) {
+                grid.tunnel_under(over);
+                return;
+            }
+        }
+
+        if let Some(a) = self.get_cell_mut(a) {
             assert!(!a.is_orphaned());
             a.link(b);
         }
 
-        if let Some(b) = self.get_mut(b.row, b.col) {
+        if let Some(b) = self.get_cell_mut(b) {
             assert!(!b.is_orphaned());
             b.link(a);
         }
@@ -249,15 +931,29 @@ impl Grid {
         }
     }
 
+    /// Links every cell to all of its neighbors
+    /// This removes every wall in the grid
+    pub fn link_all(&mut self) {
+        let links = self
+            .iter()
+            .flat_map(|cell| {
+                let handle = cell.handle();
+                cell.neighbors()
+                    .into_iter()
+                    .map(move |neighbor| (handle, neighbor))
+            })
+            .collect::<Vec<(CellHandle, CellHandle)>>();
+        self.link_cells_multi(links);
+    }
+
     /// Unlinks two cells bidirectionally
     /// This removes the path between the cells
-    #[allow(unused)]
     pub(crate) fn unlink_cells(&mut self, a: CellHandle, b: CellHandle) {
-        if let Some(a) = self.get_mut(a.row, a.col) {
+        if let Some(a) = self.get_cell_mut(a) {
             a.unlink(b);
         }
 
-        if let Some(b) = self.get_mut(b.row, b.col) {
+        if let Some(b) = self.get_cell_mut(b) {
             b.unlink(a);
         }
     }
@@ -278,75 +974,134 @@ impl Grid {
     pub(crate) fn render_ascii_solver(&self, solver: Option<&impl Solver>) -> String {
         match self {
             Self::Orthogonal(grid) => grid.render_ascii(solver),
-            Self::Polar(grid) => grid.render_ascii(solver),
+            _ => self.render_terminal(solver),
         }
     }
 
     pub(crate) fn empty_cell_contents(&self) -> (usize, String) {
-        let digits = (self.size() as f64).log(36.0).ceil() as usize;
-        (digits, str::repeat(" ", digits))
+        empty_cell_contents(self.size())
     }
 
-    pub(crate) fn render_solver(
-        &self,
-        mut cell_size: usize,
-        solver: Option<&impl Solver>,
-        color: bool,
-    ) -> ((usize, usize), Vec<u8>) {
+    /// Returns the center of the cell in an image drawn with the given options
+    pub(crate) fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
         match self {
-            Self::Orthogonal(grid) => grid.render(cell_size, solver, color),
-            Self::Polar(grid) => grid.render(cell_size, solver, color),
+            Self::Orthogonal(grid) => grid.cell_center(cell, options),
+            Self::Polar(grid) => grid.cell_center(cell, options),
+            Self::Hex(grid) => grid.cell_center(cell, options),
+            Self::Triangle(grid) => grid.cell_center(cell, options),
+            Self::Weave(grid) => grid.cell_center(cell, options),
+            Self::Layered(grid) => grid.cell_center(cell, options),
+            Self::Cube(grid) => grid.cell_center(cell, options),
+            Self::Sphere(grid) => grid.cell_center(cell, options),
         }
     }
 
-    fn save_png(
+    pub(crate) fn draw_solver(
         &self,
-        path: impl AsRef<Path>,
-        cell_size: usize,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
         solver: Option<&impl Solver>,
         color: bool,
-    ) -> io::Result<()> {
-        let ((image_width, image_height), data) = self.render_solver(cell_size, solver, color);
-
-        let file = fs::File::create(path)?;
-        let writer = io::BufWriter::new(file);
-        let mut encoder = png::Encoder::new(writer, image_width as u32, image_height as u32);
-        encoder.set_color(png::ColorType::Rgba);
-        encoder.set_depth(png::BitDepth::Eight);
-        let mut writer = encoder.write_header()?;
+    ) {
+        // anything that isn't a passage is left as the background
+        if options.background_color.a > 0 {
+            let (width, height) = self.image_size(options);
+            renderer.fill_rect(
+                (0.0, 0.0),
+                (width as f64, height as f64),
+                options.background_color,
+            );
+        }
 
-        debug!("data size: {}", data.len());
-        writer.write_image_data(&data)?;
+        match self {
+            Self::Orthogonal(grid) => grid.draw(renderer, options, solver, color),
+            Self::Polar(grid) => grid.draw(renderer, options, solver, color),
+            Self::Hex(grid) => grid.draw(renderer, options, solver, color),
+            Self::Triangle(grid) => grid.draw(renderer, options, solver, color),
+            Self::Weave(grid) => grid.draw(renderer, options, solver, color),
+            Self::Layered(grid) => grid.draw(renderer, options, solver, color),
+            Self::Cube(grid) => grid.draw(renderer, options, solver, color),
+            Self::Sphere(grid) => grid.draw(renderer, options, solver, color),
+        }
 
-        Ok(())
+        // the solved route goes over everything else
+        if options.solution {
+            if let Some(solver) = solver {
+                self.draw_solution(renderer, options, &solver.path());
+            }
+        }
     }
 
-    pub(crate) fn save_png_solver(
+    /// Draws the solved route as a line through the centers of its cells
+    /// with markers at the start and goal
+    fn draw_solution(
         &self,
-        path: impl AsRef<Path>,
-        cell_size: usize,
-        solver: Option<&impl Solver>,
-    ) -> io::Result<()> {
-        let path = path.as_ref();
-
-        // save in greyscale
-        self.save_png(path, cell_size, solver, false)?;
-
-        // get the file name but with _color appended
-        let mut color_filename = path.file_stem().unwrap().to_os_string();
-        color_filename.push("-colored");
-
-        // build the color file path
-        let mut color_path = path.to_owned();
-        color_path.set_file_name(&color_filename);
-        if let Some(extension) = path.extension() {
-            color_path.set_extension(extension);
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        path: &[CellHandle],
+    ) {
+        let cells = path
+            .iter()
+            .filter_map(|handle| self.get_cell(*handle))
+            .collect::<Vec<&Cell>>();
+
+        for step in cells.windows(2) {
+            let (from, to) = (step[0], step[1]);
+            match self {
+                Self::Orthogonal(grid) => grid.draw_solution_step(renderer, options, from, to),
+                Self::Polar(grid) => grid.draw_solution_step(renderer, options, from, to),
+                _ => {
+                    // cells that aren't drawn next to each other
+                    // (on different levels or faces) leave a gap in the route
+                    let (a, b) = (
+                        self.cell_center(from, options),
+                        self.cell_center(to, options),
+                    );
+                    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+                    if (dx * dx + dy * dy).sqrt() <= 2.0 * options.cell_size as f64 {
+                        renderer.line(a, b, options.solution_width(), options.solution_color);
+                    }
+                }
+            }
+        }
+
+        if let (Some(start), Some(goal)) = (cells.first(), cells.last()) {
+            for (cell, color) in [(start, options.start_color), (goal, options.goal_color)] {
+                renderer.fill_sector(
+                    self.cell_center(cell, options),
+                    (0.0, options.marker_radius()),
+                    (0.0, 2.0 * PI),
+                    color,
+                );
+            }
         }
+    }
 
-        // save in color
-        self.save_png(color_path, cell_size, solver, true)?;
+    /// Renders the grid as text by drawing it to the terminal
+    /// with the solver contents of each cell at its center
+    fn render_terminal(&self, solver: Option<&impl Solver>) -> String {
+        // cells are sized to fit their contents
+        // and characters are about twice as tall as they are wide
+        let (digits, _) = self.empty_cell_contents();
+        let options = RenderOptions::default()
+            .with_cell_size((digits + 3) * 2)
+            .with_margin(0);
+
+        let mut terminal = TerminalRenderer::new(self.image_size(&options), (1.0, 2.0));
+        self.draw_solver(&mut terminal, &options, solver, false);
+
+        if let Some(solver) = solver {
+            for cell in self.iter() {
+                let contents = solver.cell_contents(cell.row(), cell.col());
+                terminal.text(
+                    self.cell_center(cell, &options),
+                    contents.trim(),
+                    options.wall_color,
+                );
+            }
+        }
 
-        Ok(())
+        terminal.finish()
     }
 }
 
@@ -355,12 +1110,182 @@ impl Renderable for Grid {
         self.render_ascii_solver(None::<&crate::solvers::Djikstra>)
     }
 
-    fn render(&self, cell_size: usize, color: bool) -> ((usize, usize), Vec<u8>) {
-        self.render_solver(cell_size, None::<&crate::solvers::Djikstra>, color)
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        match self {
+            Self::Orthogonal(grid) => grid.image_size(options),
+            Self::Polar(grid) => grid.image_size(options),
+            Self::Hex(grid) => grid.image_size(options),
+            Self::Triangle(grid) => grid.image_size(options),
+            Self::Weave(grid) => grid.image_size(options),
+            Self::Layered(grid) => grid.image_size(options),
+            Self::Cube(grid) => grid.image_size(options),
+            Self::Sphere(grid) => grid.image_size(options),
+        }
+    }
+
+    fn draw(&self, renderer: &mut dyn Renderer, options: &RenderOptions, color: bool) {
+        self.draw_solver(renderer, options, None::<&crate::solvers::Djikstra>, color)
+    }
+}
+
+/// Returns a random enabled cell from a rectangular grid
+fn get_random_cell(
+    rows: usize,
+    cols: usize,
+    mask: &Option<Mask>,
+    rng: &mut dyn RngCore,
+) -> CellHandle {
+    if let Some(mask) = mask {
+        mask.get_random(rng).into()
+    } else {
+        (rng.gen_range(0..rows), rng.gen_range(0..cols)).into()
+    }
+}
+
+/// Returns the number of digits needed to render cell contents
+/// and an empty cell body of that width
+pub(crate) fn empty_cell_contents(size: usize) -> (usize, String) {
+    let digits = (size as f64).log(36.0).ceil() as usize;
+    (digits, str::repeat(" ", digits))
+}
+
+/// Returns the color to fill a cell with
+/// Cells are only colored by the solver if color is set
+fn cell_color(
+    cell: &Cell,
+    solver: Option<&impl Solver>,
+    options: &RenderOptions,
+    color: bool,
+) -> Color {
+    match solver {
+        Some(solver) if color => solver.cell_background(cell.row(), cell.col()),
+        _ => options.path_color,
+    }
+}
+
+/// A side of a square cell
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Side {
+    /// Linked to the neighbor on that side
+    Open,
+
+    /// A wall drawn by this cell
+    Wall,
+
+    /// A wall drawn by the neighbor on that side
+    /// (inset cells are walled in on every side that isn't open)
+    Shared,
+}
+
+/// Fills a square cell between its top left and bottom right corners
+/// given its north, south, west and east sides
+/// Inset cells only fill their passages
+pub(crate) fn fill_square_cell(
+    renderer: &mut dyn Renderer,
+    (x1, y1): Point,
+    (x4, y4): Point,
+    [north, south, west, east]: [Side; 4],
+    inset: f64,
+    color: Color,
+) {
+    if inset <= 0.0 {
+        renderer.fill_rect((x1, y1), (x4, y4), color);
+        return;
+    }
+
+    let (x2, y2, x3, y3) = (x1 + inset, y1 + inset, x4 - inset, y4 - inset);
+    renderer.fill_rect((x2, y2), (x3, y3), color);
+
+    if north == Side::Open {
+        renderer.fill_rect((x2, y1), (x3, y2), color);
+    }
+
+    if south == Side::Open {
+        renderer.fill_rect((x2, y3), (x3, y4), color);
+    }
+
+    if west == Side::Open {
+        renderer.fill_rect((x1, y2), (x2, y3), color);
+    }
+
+    if east == Side::Open {
+        renderer.fill_rect((x3, y2), (x4, y3), color);
+    }
+}
+
+/// Draws the walls of a square cell between its top left and bottom right corners
+/// given its north, south, west and east sides
+/// Inset cells have passage walls running out to their open sides
+pub(crate) fn draw_square_cell(
+    renderer: &mut dyn Renderer,
+    (x1, y1): Point,
+    (x4, y4): Point,
+    [north, south, west, east]: [Side; 4],
+    inset: f64,
+    options: &RenderOptions,
+) {
+    let (width, wall) = (options.wall_width(), options.wall_color);
+
+    if inset <= 0.0 {
+        if north == Side::Wall {
+            renderer.line((x1, y1), (x4, y1), width, wall);
+        }
+
+        if south == Side::Wall {
+            renderer.line((x1, y4), (x4, y4), width, wall);
+        }
+
+        if west == Side::Wall {
+            renderer.line((x1, y1), (x1, y4), width, wall);
+        }
+
+        if east == Side::Wall {
+            renderer.line((x4, y1), (x4, y4), width, wall);
+        }
+
+        return;
+    }
+
+    let (x2, y2, x3, y3) = (x1 + inset, y1 + inset, x4 - inset, y4 - inset);
+
+    if north == Side::Open {
+        renderer.line((x2, y1), (x2, y2), width, wall);
+        renderer.line((x3, y1), (x3, y2), width, wall);
+    } else {
+        renderer.line((x2, y2), (x3, y2), width, wall);
+    }
+
+    if south == Side::Open {
+        renderer.line((x2, y3), (x2, y4), width, wall);
+        renderer.line((x3, y3), (x3, y4), width, wall);
+    } else {
+        renderer.line((x2, y3), (x3, y3), width, wall);
+    }
+
+    if west == Side::Open {
+        renderer.line((x1, y2), (x2, y2), width, wall);
+        renderer.line((x1, y3), (x2, y3), width, wall);
+    } else {
+        renderer.line((x2, y2), (x2, y3), width, wall);
     }
 
-    fn save_png(&self, path: &Path, cell_size: usize) -> io::Result<()> {
-        self.save_png_solver(path, cell_size, None::<&crate::solvers::Djikstra>)
+    if east == Side::Open {
+        renderer.line((x3, y2), (x4, y2), width, wall);
+        renderer.line((x3, y3), (x4, y3), width, wall);
+    } else {
+        renderer.line((x3, y2), (x3, y3), width, wall);
+    }
+}
+
+/// Returns the side of a cell towards the given neighbor
+/// Sides without a neighbor are always walled,
+/// otherwise unlinked sides are left to the neighbor if it draws the shared wall
+fn square_side(cell: &Cell, neighbor: Option<CellHandle>, neighbor_draws: bool) -> Side {
+    match neighbor {
+        None => Side::Wall,
+        Some(neighbor) if cell.is_linked(neighbor) => Side::Open,
+        Some(_) if neighbor_draws => Side::Shared,
+        Some(_) => Side::Wall,
     }
 }
 
@@ -368,6 +1293,7 @@ impl Renderable for Grid {
 pub struct OrthogonalGrid {
     rows: usize,
     cols: usize,
+    wrap: Wrap,
 
     mask: Option<Mask>,
 
@@ -382,6 +1308,7 @@ impl OrthogonalGrid {
         Self {
             rows,
             cols,
+            wrap: Wrap::None,
             mask: None,
             grid: Vec::with_capacity(rows),
         }
@@ -392,16 +1319,33 @@ impl OrthogonalGrid {
         Self {
             rows,
             cols: mask.cols,
+            wrap: Wrap::None,
             mask: Some(mask),
             grid: Vec::with_capacity(rows),
         }
     }
 
+    fn size(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.grid.get(row)?.get(col)?.as_ref()
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.grid.get_mut(row)?.get_mut(col)?.as_mut()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.grid.iter().flatten().flatten()
+    }
+
     fn init_grid(&mut self) {
         for row in 0..self.rows {
             let mut cells = Vec::with_capacity(self.cols);
             for col in 0..self.cols {
-                let cell = if let Some(mask) = self.mask {
+                let cell = if let Some(mask) = &self.mask {
                     if mask.get(row, col) {
                         Some(Cell::new_ortho(row, col))
                     } else {
@@ -421,19 +1365,42 @@ impl OrthogonalGrid {
             for col in 0..self.cols {
                 let north = if row > 0 {
                     self.get(row - 1, col).map(|cell| cell.handle())
+                } else if self.wrap.wraps_rows() {
+                    self.get(self.rows - 1, col).map(|cell| cell.handle())
+                } else {
+                    None
+                };
+
+                let south = if row < self.rows - 1 {
+                    self.get(row + 1, col).map(|cell| cell.handle())
+                } else if self.wrap.wraps_rows() {
+                    self.get(0, col).map(|cell| cell.handle())
                 } else {
                     None
                 };
 
-                let south = self.get(row + 1, col).map(|cell| cell.handle());
+                // the Möbius seam joins each row to its mirrored row
+                let seam_row = if self.wrap == Wrap::Mobius {
+                    self.rows - 1 - row
+                } else {
+                    row
+                };
 
                 let west = if col > 0 {
                     self.get(row, col - 1).map(|cell| cell.handle())
+                } else if self.wrap.wraps_columns() {
+                    self.get(seam_row, self.cols - 1).map(|cell| cell.handle())
                 } else {
                     None
                 };
 
-                let east = self.get(row, col + 1).map(|cell| cell.handle());
+                let east = if col < self.cols - 1 {
+                    self.get(row, col + 1).map(|cell| cell.handle())
+                } else if self.wrap.wraps_columns() {
+                    self.get(seam_row, 0).map(|cell| cell.handle())
+                } else {
+                    None
+                };
 
                 let cell = self.get_mut(row, col);
                 if let Some(cell) = cell {
@@ -444,9 +1411,7 @@ impl OrthogonalGrid {
                             cell.west = west;
                             cell.east = east;
                         }
-                        Cell::Polar(cell) => {
-                            // TODO:
-                        }
+                        _ => panic!("Invalid cell type"),
                     }
                 }
             }
@@ -454,19 +1419,24 @@ impl OrthogonalGrid {
     }
 
     fn render_ascii(&self, solver: Option<&impl Solver>) -> String {
-        let (digits, empty) = self.empty_cell_contents();
+        let (digits, empty) = empty_cell_contents(self.size());
         let mut output = format!(
             "+{}\n",
             format!("-{}-+", str::repeat("-", digits)).repeat(self.cols)
         );
 
-        for row in self.rows_iter() {
+        for row in &self.grid {
             let mut top = String::from("|");
             let mut bottom = String::from("+");
 
             for cell in row {
                 // TODO: this could be cleaner
                 let (body, east_boundary, south_boundary) = if let Some(cell) = cell {
+                    let ortho = match cell {
+                        Cell::Orthogonal(ortho) => ortho,
+                        _ => panic!("Invalid cell type"),
+                    };
+
                     let body = format!(
                         " {} ",
                         solver
@@ -474,7 +1444,7 @@ impl OrthogonalGrid {
                             .unwrap_or_else(|| empty.clone())
                     );
 
-                    let east_boundary = if let Some(east) = cell.east {
+                    let east_boundary = if let Some(east) = ortho.east {
                         if cell.is_linked(east) {
                             " "
                         } else {
@@ -484,7 +1454,7 @@ impl OrthogonalGrid {
                         "|"
                     };
 
-                    let south_boundary = if let Some(south) = cell.south {
+                    let south_boundary = if let Some(south) = ortho.south {
                         if cell.is_linked(south) {
                             format!(" {} ", str::repeat(" ", digits))
                         } else {
@@ -519,121 +1489,202 @@ impl OrthogonalGrid {
         output
     }
 
-    fn render_cell(
-        &self,
-        cell: &Cell,
-        cell_size: usize,
-        image_dimensions: (usize, usize),
-        wall: Color,
-        mut data: impl AsMut<[u8]>,
-    ) {
-        if let Cell::Orthogonal(ortho) = cell {
-            let x1 = 1 + (cell.col() * cell_size);
-            let y1 = 1 + (cell.row() * cell_size);
-            let x2 = (cell.col() + 1) * cell_size;
-            let y2 = (cell.row() + 1) * cell_size;
+    /// Returns the top left and bottom right corners of the cell
+    /// Tiled edges aren't padded since they continue on the other side of the image
+    fn cell_corners(&self, cell: &Cell, options: &RenderOptions) -> (Point, Point) {
+        let size = options.cell_size as f64;
+        let padding = options.padding();
+        let x = if self.wrap.tiles_columns() {
+            0.0
+        } else {
+            padding
+        } + cell.col() as f64 * size;
+        let y = if self.wrap.tiles_rows() { 0.0 } else { padding } + cell.row() as f64 * size;
 
-            if ortho.north.is_none() {
-                crate::util::line(&mut data, image_dimensions, x1, y1, x2, y1, wall);
-            }
+        ((x, y), (x + size, y + size))
+    }
 
-            if ortho.west.is_none() {
-                crate::util::line(&mut data, image_dimensions, x1, y1, x1, y2, wall);
+    /// Returns the north, south, west and east sides of the cell
+    fn cell_sides(&self, cell: &Cell) -> [Side; 4] {
+        match cell {
+            Cell::Orthogonal(ortho) => {
+                // the untiled Möbius seam is drawn on both sides of the image
+                let seam = cell.col() == 0 && !self.wrap.tiles_columns();
+
+                [
+                    square_side(cell, ortho.north, true),
+                    square_side(cell, ortho.south, false),
+                    square_side(cell, ortho.west, !seam),
+                    square_side(cell, ortho.east, false),
+                ]
             }
+            _ => panic!("Invalid cell type"),
+        }
+    }
 
-            if let Some(east) = ortho.east {
-                if !cell.is_linked(east) {
-                    crate::util::line(&mut data, image_dimensions, x2, y1, x2, y2, wall);
-                }
-            } else {
-                crate::util::line(&mut data, image_dimensions, x2, y1, x2, y2, wall);
-            }
+    fn draw_cell(&self, renderer: &mut dyn Renderer, cell: &Cell, options: &RenderOptions) {
+        let ((x1, y1), (x2, y2)) = self.cell_corners(cell, options);
+        let sides = self.cell_sides(cell);
+        let inset = options.cell_inset(options.cell_size as f64);
 
-            if let Some(south) = ortho.south {
-                if !cell.is_linked(south) {
-                    crate::util::line(&mut data, image_dimensions, x1, y2, x2, y2, wall);
-                }
-            } else {
-                crate::util::line(&mut data, image_dimensions, x1, y2, x2, y2, wall);
+        // cells on tiled edges are drawn again on the other side of the image
+        // so that walls on the edges are split between both sides
+        let (width, height) = (
+            (self.cols * options.cell_size) as f64,
+            (self.rows * options.cell_size) as f64,
+        );
+        let x_offsets: &[f64] = if self.wrap.tiles_columns() && cell.col() + 1 == self.cols {
+            &[0.0, -width]
+        } else {
+            &[0.0]
+        };
+        let y_offsets: &[f64] = if self.wrap.tiles_rows() && cell.row() + 1 == self.rows {
+            &[0.0, -height]
+        } else {
+            &[0.0]
+        };
+
+        for x in x_offsets {
+            for y in y_offsets {
+                draw_square_cell(
+                    renderer,
+                    (x1 + x, y1 + y),
+                    (x2 + x, y2 + y),
+                    sides,
+                    inset,
+                    options,
+                );
             }
         }
     }
 
-    fn render(
-        &self,
-        mut cell_size: usize,
-        solver: Option<&impl Solver>,
-        color: bool,
-    ) -> ((usize, usize), Vec<u8>) {
-        let wall = Color::new(0, 0, 0, 255);
-
-        // iamge width / height in pixels
-        // (plus 2 for the edge walls)
-        let (image_width, image_height) = {
-            let width = self.cols * cell_size;
-            let height = self.rows * cell_size;
-            (width + 2, height + 2)
-        };
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        let width = self.cols * options.cell_size;
+        let height = self.rows * options.cell_size;
+        let padded = options.padded_size((width as f64, height as f64));
+        (
+            if self.wrap.tiles_columns() {
+                width
+            } else {
+                padded.0
+            },
+            if self.wrap.tiles_rows() {
+                height
+            } else {
+                padded.1
+            },
+        )
+    }
 
-        // size in bytes (4 bytes per-pixel)
-        let image_size = image_width * image_height * 4;
+    fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        let ((x1, y1), (x2, y2)) = self.cell_corners(cell, options);
+        ((x1 + x2) / 2.0, (y1 + y2) / 2.0)
+    }
 
-        // init image to the default color
-        let mut data = vec![0; image_size];
+    /// Draws a step of the solved route between linked cells
+    /// Steps across a wrapped edge leave each cell out the edge of the image
+    fn draw_solution_step(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        from: &Cell,
+        to: &Cell,
+    ) {
+        let (a, b) = (
+            self.cell_center(from, options),
+            self.cell_center(to, options),
+        );
+        let (width, color) = (options.solution_width(), options.solution_color);
 
-        // color cells using the solver
-        for cell in self {
-            let cell_handle = cell.handle();
+        let cell_size = options.cell_size as f64;
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        if dx.abs() <= cell_size && dy.abs() <= cell_size {
+            renderer.line(a, b, width, color);
+            return;
+        }
 
-            let background = if color {
-                solver
-                    .map(|solver| solver.cell_background(cell_handle.row, cell_handle.col))
-                    .unwrap_or_else(|| Color::WHITE)
-            } else {
-                Color::WHITE
-            };
+        // the step looks like it goes the long way across the image,
+        // so each half goes the other way
+        let half = if dx.abs() > cell_size {
+            (-dx.signum() * cell_size / 2.0, 0.0)
+        } else {
+            (0.0, -dy.signum() * cell_size / 2.0)
+        };
+        renderer.line(a, (a.0 + half.0, a.1 + half.1), width, color);
+        renderer.line(b, (b.0 - half.0, b.1 - half.1), width, color);
+    }
 
-            let x1 = cell.col() * cell_size;
-            let y1 = cell.row() * cell_size;
-            let x2 = (cell.col() + 1) * cell_size;
-            let y2 = (cell.row() + 1) * cell_size;
+    fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        solver: Option<&impl Solver>,
+        color: bool,
+    ) {
+        let inset = options.cell_inset(options.cell_size as f64);
 
-            quad(&mut data, image_width, x1, y1, x2, y2, background);
+        // color cells using the solver
+        for cell in self.iter() {
+            let (from, to) = self.cell_corners(cell, options);
+            fill_square_cell(
+                renderer,
+                from,
+                to,
+                self.cell_sides(cell),
+                inset,
+                cell_color(cell, solver, options, color),
+            );
         }
 
         // draw the cell walls
-        for cell in self {
-            self.render_cell(
-                cell,
-                cell_size,
-                (image_width, image_height),
-                wall,
-                &mut data,
-            );
+        for cell in self.iter() {
+            self.draw_cell(renderer, cell, options);
         }
+    }
+}
+
+/// Returns the number of cells in each ring of a polar grid
+/// The center is a single cell and each ring after that subdivides its cells
+/// to keep them roughly as wide as they are tall
+fn ring_counts(rings: usize, row_height: f64, radius: impl Fn(usize) -> f64) -> Vec<usize> {
+    let mut counts: Vec<usize> = Vec::with_capacity(rings);
+    for row in 0..rings {
+        let count = if row == 0 {
+            1
+        } else {
+            let circumference = 2.0 * PI * radius(row);
+
+            let previous_count = counts[row - 1];
+            let estimated_cell_width = circumference / previous_count as f64;
+            let ratio = (estimated_cell_width / row_height).round() as usize;
 
-        ((image_width, image_height), data)
+            previous_count * ratio
+        };
+        counts.push(count);
     }
+
+    counts
 }
 
 #[derive(Debug, Clone)]
 pub struct PolarGrid {
     rows: usize,
+
+    // the number of cells in the outermost ring
     cols: usize,
 
     mask: Option<Mask>,
 
-    // vector of vector of cells so we can easily iterate over rows
-    // TODO: this would be better, however, if it was a single vector
-    // and a custom "row iterator" was used instead
+    // vector of rings of cells, from the center outward
+    // each ring may have a different number of cells
     grid: Vec<Vec<Option<Cell>>>,
 }
 
 impl PolarGrid {
-    fn new(rows: usize, cols: usize) -> Self {
+    fn new(rows: usize) -> Self {
         Self {
             rows,
-            cols,
+            cols: 0,
             mask: None,
             grid: Vec::with_capacity(rows),
         }
@@ -643,172 +1694,1910 @@ impl PolarGrid {
         let rows = mask.rows;
         Self {
             rows,
-            cols: mask.cols,
+            cols: 0,
             mask: Some(mask),
             grid: Vec::with_capacity(rows),
         }
     }
 
+    fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.grid.get(row)?.get(col)?.as_ref()
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.grid.get_mut(row)?.get_mut(col)?.as_mut()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.grid.iter().flatten().flatten()
+    }
+
+    fn is_enabled(&self, row: usize, col: usize, count: usize) -> bool {
+        if let Some(mask) = &self.mask {
+            // spread the mask columns evenly around the ring
+            mask.get(row, col * mask.cols / count)
+        } else {
+            true
+        }
+    }
+
     fn init_grid(&mut self) {
-        // TODO:
+        let row_height = 1.0 / self.rows as f64;
+        let counts = ring_counts(self.rows, row_height, |row| row as f64 * row_height);
+
+        for (row, count) in counts.into_iter().enumerate() {
+            let mut cells = Vec::with_capacity(count);
+            for col in 0..count {
+                let cell = if self.is_enabled(row, col, count) {
+                    Some(Cell::new_polar(row, col))
+                } else {
+                    None
+                };
+                cells.push(cell);
+            }
+            self.grid.push(cells);
+        }
+
+        self.cols = self.grid[self.rows - 1].len();
     }
 
     fn init_cells(&mut self) {
-        // TODO:
-    }
+        // the center cell only has outward neighbors
+        // which get wired up by the first ring
+        for row in 1..self.rows {
+            let count = self.grid[row].len();
+
+            // number of cells in this ring for each cell in the inner ring
+            let ratio = count / self.grid[row - 1].len();
+
+            for col in 0..count {
+                let cw = self.get(row, (col + 1) % count).map(|cell| cell.handle());
+                let ccw = self
+                    .get(row, (col + count - 1) % count)
+                    .map(|cell| cell.handle());
+                let inward = self.get(row - 1, col / ratio).map(|cell| cell.handle());
+
+                match self.get_mut(row, col) {
+                    Some(Cell::Polar(cell)) => {
+                        cell.cw = cw;
+                        cell.ccw = ccw;
+                        cell.inward = inward;
+                    }
+                    Some(_) => panic!("Invalid cell type"),
+                    None => continue,
+                }
 
-    fn render_ascii(&self, solver: Option<&impl Solver>) -> String {
-        "Cannot render polar grid".to_string()
+                if let Some(inward) = inward {
+                    match self.get_mut(inward.row, inward.col) {
+                        Some(Cell::Polar(cell)) => cell.outward.push(CellHandle::new(row, col)),
+                        _ => panic!("Invalid cell type"),
+                    }
+                }
+            }
+        }
     }
 
-    fn render_cell(
+    fn draw_cell(
         &self,
+        renderer: &mut dyn Renderer,
         cell: &Cell,
-        cell_size: usize,
-        image_dimensions: (usize, usize),
-        image_center: (f64, f64),
-        wall: Color,
-        mut data: impl AsMut<[u8]>,
+        cell_size: f64,
+        image_center: Point,
+        options: &RenderOptions,
     ) {
-        // cell angle
-        let theta = (2.0 * std::f64::consts::PI) / self.grid[cell.row()].len() as f64;
-
-        // inner / outer wall distance from center
-        let inner_radius = (cell.row() * cell_size) as f64;
-        let outer_radius = ((cell.row() + 1) * cell_size) as f64;
+        let (width, wall) = (options.wall_width(), options.wall_color);
+
+        if let Cell::Polar(polar) = cell {
+            let count = self.grid[cell.row()].len();
+
+            // cell angle
+            let theta = (2.0 * PI) / count as f64;
+
+            // inner / outer wall distance from center
+            let inner_radius = cell.row() as f64 * cell_size;
+            let outer_radius = (cell.row() + 1) as f64 * cell_size;
+
+            // cell wall angles
+            let theta_ccw = cell.col() as f64 * theta;
+            let theta_cw = (cell.col() + 1) as f64 * theta;
+
+            // the center cell has no inward or radial walls
+            if cell.row() > 0 {
+                if !polar.inward.is_some_and(|inward| cell.is_linked(inward)) {
+                    renderer.arc(
+                        image_center,
+                        inner_radius,
+                        (theta_ccw, theta_cw),
+                        width,
+                        wall,
+                    );
+                }
 
-        // cell wall angles
-        let theta_ccw = cell.col() as f64 * theta;
-        let theta_cw = (cell.col() + 1) as f64 * theta;
+                if !polar.cw.is_some_and(|cw| cell.is_linked(cw)) {
+                    renderer.line(
+                        point(image_center, inner_radius, theta_cw),
+                        point(image_center, outer_radius, theta_cw),
+                        width,
+                        wall,
+                    );
+                }
 
-        // cell walls
-        let ax = (image_center.0 + (inner_radius * theta_ccw.cos())) as usize;
-        let ay = (image_center.1 + (inner_radius * theta_ccw.sin())) as usize;
-        let _bx = (image_center.0 + (outer_radius * theta_ccw.cos())) as usize;
-        let _by = (image_center.1 + (outer_radius * theta_ccw.sin())) as usize;
-        let cx = (image_center.0 + (inner_radius * theta_cw.cos())) as usize;
-        let cy = (image_center.1 + (inner_radius * theta_cw.sin())) as usize;
-        let dx = (image_center.0 + (outer_radius * theta_cw.cos())) as usize;
-        let dy = (image_center.1 + (outer_radius * theta_cw.sin())) as usize;
+                // linked ccw walls are handled by the ccw neighbor
+                // so this only needs to cover disabled neighbors
+                if polar.ccw.is_none() {
+                    renderer.line(
+                        point(image_center, inner_radius, theta_ccw),
+                        point(image_center, outer_radius, theta_ccw),
+                        width,
+                        wall,
+                    );
+                }
+            }
 
-        // TODO:
-        /*if let Some(north) = cell.north {
-            if !cell.is_linked(north) {
-                crate::util::line(&mut data, image_dimensions, ax, ay, cx, cy, wall);
+            // outward walls border either the edge of the grid or disabled cells
+            if cell.row() + 1 >= self.rows {
+                renderer.arc(
+                    image_center,
+                    outer_radius,
+                    (theta_ccw, theta_cw),
+                    width,
+                    wall,
+                );
+            } else {
+                let ratio = self.grid[cell.row() + 1].len() / count;
+                let outward_theta = theta / ratio as f64;
+
+                for index in 0..ratio {
+                    let col = cell.col() * ratio + index;
+                    if self.get(cell.row() + 1, col).is_none() {
+                        let theta_ccw = col as f64 * outward_theta;
+                        renderer.arc(
+                            image_center,
+                            outer_radius,
+                            (theta_ccw, theta_ccw + outward_theta),
+                            width,
+                            wall,
+                        );
+                    }
+                }
             }
         }
+    }
 
-        if let Some(east) = cell.east {
-            if !cell.is_linked(east) {
-                crate::util::line(&mut data, image_dimensions, cx, cy, dx, dy, wall);
-            }
-        }*/
+    /// Returns the cell size polar grids are drawn with
+    /// They need to be scaled down to match (roughly) the orthogonal grid image size
+    fn scaled_cell_size(cell_size: usize) -> f64 {
+        (cell_size as f64 / 2.0).ceil()
     }
 
-    fn render(
-        &self,
-        mut cell_size: usize,
-        solver: Option<&impl Solver>,
-        color: bool,
-    ) -> ((usize, usize), Vec<u8>) {
-        // polar grids need to be scaled down
-        // to match (roughly) the orthogonal grid image size
-        cell_size = (cell_size as f64 / 2.0).ceil() as usize;
-
-        let wall = Color::new(0, 0, 0, 255);
-
-        // iamge width / height in pixels
-        // (plus 2 for the edge walls)
-        let (image_width, image_height) = {
-            let diameter = 2 * self.grid.len() * cell_size;
-            (diameter + 2, diameter + 2)
-        };
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        let diameter = 2.0 * self.rows as f64 * Self::scaled_cell_size(options.cell_size);
+        options.padded_size((diameter, diameter))
+    }
 
-        // size in bytes (4 bytes per-pixel)
-        let image_size = image_width * image_height * 4;
+    fn image_center(&self, options: &RenderOptions) -> Point {
+        let radius = self.rows as f64 * Self::scaled_cell_size(options.cell_size);
+        let padding = options.padding();
+        (padding + radius, padding + radius)
+    }
 
-        let image_center = (image_width as f64 / 2.0, image_height as f64 / 2.0);
+    fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        let image_center = self.image_center(options);
+        if cell.row() == 0 {
+            return image_center;
+        }
 
-        // init image to the default color
-        let mut data = vec![255; image_size];
+        let count = self.grid[cell.row()].len();
+        let radius = (cell.row() as f64 + 0.5) * Self::scaled_cell_size(options.cell_size);
+        let theta = (cell.col() as f64 + 0.5) * (2.0 * PI) / count as f64;
+        point(image_center, radius, theta)
+    }
 
-        // draw the cell walls
-        for cell in self {
-            self.render_cell(
-                cell,
-                cell_size,
-                (image_width, image_height),
-                image_center,
-                wall,
-                &mut data,
+    /// Draws a step of the solved route between linked cells
+    /// Steps around a ring follow it the short way around,
+    /// and steps between rings are straight
+    fn draw_solution_step(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        from: &Cell,
+        to: &Cell,
+    ) {
+        let (width, color) = (options.solution_width(), options.solution_color);
+
+        if from.row() != to.row() || from.row() == 0 {
+            renderer.line(
+                self.cell_center(from, options),
+                self.cell_center(to, options),
+                width,
+                color,
             );
+            return;
         }
 
-        circle(
-            &mut data,
-            (image_width, image_height),
-            (image_center.0 as usize, image_center.1 as usize),
-            self.grid.len() * cell_size,
-            wall,
-        );
+        let count = self.grid[from.row()].len();
+        let radius = (from.row() as f64 + 0.5) * Self::scaled_cell_size(options.cell_size);
+        let theta = (2.0 * PI) / count as f64;
 
-        ((image_width, image_height), data)
+        let a = (from.col() as f64 + 0.5) * theta;
+        let mut b = (to.col() as f64 + 0.5) * theta;
+        if b - a > PI {
+            b -= 2.0 * PI;
+        } else if a - b > PI {
+            b += 2.0 * PI;
+        }
+        renderer.arc(self.image_center(options), radius, (a, b), width, color);
     }
-}
 
-/// Cell-based grid iterator
-pub struct Iter<'a> {
-    grid: &'a Grid,
+    fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        solver: Option<&impl Solver>,
+        color: bool,
+    ) {
+        let image_center = self.image_center(options);
+        let cell_size = Self::scaled_cell_size(options.cell_size);
 
-    row: usize,
-    col: usize,
-}
+        // color cells using the solver
+        for cell in self.iter() {
+            let count = self.grid[cell.row()].len();
+            let theta = (2.0 * PI) / count as f64;
 
-impl<'a> Iter<'a> {
-    fn new(grid: &'a Grid) -> Self {
-        Self {
-            grid,
-            row: 0,
-            col: 0,
+            renderer.fill_sector(
+                image_center,
+                (
+                    cell.row() as f64 * cell_size,
+                    (cell.row() + 1) as f64 * cell_size,
+                ),
+                (cell.col() as f64 * theta, (cell.col() + 1) as f64 * theta),
+                cell_color(cell, solver, options, color),
+            );
+        }
+
+        // draw the cell walls
+        for cell in self.iter() {
+            self.draw_cell(renderer, cell, cell_size, image_center, options);
         }
     }
 }
 
-impl<'a> Iterator for Iter<'a> {
-    type Item = &'a Cell;
+#[derive(Debug, Clone)]
+pub struct HexGrid {
+    rows: usize,
+    cols: usize,
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let ret = loop {
-            let index = self.grid.index(self.row, self.col);
-            if index >= self.grid.size() {
-                break None;
-            }
+    mask: Option<Mask>,
 
-            let ret = self.grid.get(self.row, self.col);
+    // vector of vector of cells so we can easily iterate over rows
+    // odd columns are shifted down half a cell
+    grid: Vec<Vec<Option<Cell>>>,
+}
 
-            let mut next_row = self.row;
-            let mut next_col = self.col + 1;
-            if next_col >= self.grid.columns() {
-                next_row += 1;
-                next_col = 0;
-            }
+impl HexGrid {
+    fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            mask: None,
+            grid: Vec::with_capacity(rows),
+        }
+    }
 
-            self.row = next_row;
-            self.col = next_col;
+    fn from_mask(mask: Mask) -> Self {
+        let rows = mask.rows;
+        Self {
+            rows,
+            cols: mask.cols,
+            mask: Some(mask),
+            grid: Vec::with_capacity(rows),
+        }
+    }
 
-            if ret.is_none() {
-                continue;
-            }
+    fn size(&self) -> usize {
+        self.rows * self.cols
+    }
 
-            break ret;
-        };
+    fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.grid.get(row)?.get(col)?.as_ref()
+    }
 
-        ret
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.grid.get_mut(row)?.get_mut(col)?.as_mut()
     }
-}
 
-impl<'a> IntoIterator for &'a Grid {
-    type Item = &'a Cell;
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.grid.iter().flatten().flatten()
+    }
+
+    fn init_grid(&mut self) {
+        for row in 0..self.rows {
+            let mut cells = Vec::with_capacity(self.cols);
+            for col in 0..self.cols {
+                let cell = if let Some(mask) = &self.mask {
+                    if mask.get(row, col) {
+                        Some(Cell::new_hex(row, col))
+                    } else {
+                        None
+                    }
+                } else {
+                    Some(Cell::new_hex(row, col))
+                };
+                cells.push(cell);
+            }
+            self.grid.push(cells);
+        }
+    }
+
+    fn init_cells(&mut self) {
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                // odd columns sit lower than even columns
+                // so the diagonal neighbors depend on the column
+                let (north_diagonal, south_diagonal) = if col.is_multiple_of(2) {
+                    (row.checked_sub(1), Some(row))
+                } else {
+                    (Some(row), Some(row + 1))
+                };
+
+                let handle = |row: Option<usize>, col: Option<usize>| {
+                    self.get(row?, col?).map(|cell| cell.handle())
+                };
+
+                let north = handle(row.checked_sub(1), Some(col));
+                let south = handle(Some(row + 1), Some(col));
+                let northwest = handle(north_diagonal, col.checked_sub(1));
+                let southwest = handle(south_diagonal, col.checked_sub(1));
+                let northeast = handle(north_diagonal, Some(col + 1));
+                let southeast = handle(south_diagonal, Some(col + 1));
+
+                let cell = self.get_mut(row, col);
+                if let Some(cell) = cell {
+                    match cell {
+                        Cell::Hex(cell) => {
+                            cell.north = north;
+                            cell.south = south;
+                            cell.northeast = northeast;
+                            cell.northwest = northwest;
+                            cell.southeast = southeast;
+                            cell.southwest = southwest;
+                        }
+                        _ => panic!("Invalid cell type"),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the x (far west, near west, near east, far east)
+    /// and y (north, middle, south) coordinates of the cell corners
+    fn cell_corners(&self, cell: &Cell, options: &RenderOptions) -> ([f64; 4], [f64; 3]) {
+        let size = Self::scaled_cell_size(options.cell_size);
+        let padding = options.padding();
+
+        // distance from the center to the corners
+        let a_size = size / 2.0;
+        let b_size = size * 3.0_f64.sqrt() / 2.0;
+
+        // cell center
+        let cx = padding + size + 3.0 * cell.col() as f64 * a_size;
+        let mut cy = padding + b_size + cell.row() as f64 * b_size * 2.0;
+        if !cell.col().is_multiple_of(2) {
+            cy += b_size;
+        }
+
+        (
+            [cx - size, cx - a_size, cx + a_size, cx + size],
+            [cy - b_size, cy, cy + b_size],
+        )
+    }
+
+    fn draw_cell(&self, renderer: &mut dyn Renderer, cell: &Cell, options: &RenderOptions) {
+        let (width, wall) = (options.wall_width(), options.wall_color);
+
+        if let Cell::Hex(hex) = cell {
+            let ([x_fw, x_nw, x_ne, x_fe], [y_n, y_m, y_s]) = self.cell_corners(cell, options);
+
+            // linked western / northern walls are handled by those neighbors
+            // so these only need to cover the edge of the grid
+            if hex.southwest.is_none() {
+                renderer.line((x_fw, y_m), (x_nw, y_s), width, wall);
+            }
+
+            if hex.northwest.is_none() {
+                renderer.line((x_fw, y_m), (x_nw, y_n), width, wall);
+            }
+
+            if hex.north.is_none() {
+                renderer.line((x_nw, y_n), (x_ne, y_n), width, wall);
+            }
+
+            if !hex
+                .northeast
+                .is_some_and(|northeast| cell.is_linked(northeast))
+            {
+                renderer.line((x_ne, y_n), (x_fe, y_m), width, wall);
+            }
+
+            if !hex
+                .southeast
+                .is_some_and(|southeast| cell.is_linked(southeast))
+            {
+                renderer.line((x_fe, y_m), (x_ne, y_s), width, wall);
+            }
+
+            if !hex.south.is_some_and(|south| cell.is_linked(south)) {
+                renderer.line((x_ne, y_s), (x_nw, y_s), width, wall);
+            }
+        }
+    }
+
+    /// Returns the size hex cells are drawn with
+    /// They're sized from their center to a corner
+    /// so they need to be scaled down
+    /// to match (roughly) the orthogonal grid image size
+    fn scaled_cell_size(cell_size: usize) -> f64 {
+        cell_size as f64 / 2.0
+    }
+
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        let size = Self::scaled_cell_size(options.cell_size);
+        let a_size = size / 2.0;
+        let b_size = size * 3.0_f64.sqrt() / 2.0;
+
+        let width = 3.0 * a_size * self.cols as f64 + a_size;
+        let height = b_size * 2.0 * self.rows as f64 + b_size;
+        options.padded_size((width, height))
+    }
+
+    fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        let ([x_fw, _, _, x_fe], [_, y_m, _]) = self.cell_corners(cell, options);
+        ((x_fw + x_fe) / 2.0, y_m)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        solver: Option<&impl Solver>,
+        color: bool,
+    ) {
+        // color cells using the solver
+        for cell in self.iter() {
+            let ([x_fw, x_nw, x_ne, x_fe], [y_n, y_m, y_s]) = self.cell_corners(cell, options);
+            renderer.fill_polygon(
+                &[
+                    (x_fw, y_m),
+                    (x_nw, y_n),
+                    (x_ne, y_n),
+                    (x_fe, y_m),
+                    (x_ne, y_s),
+                    (x_nw, y_s),
+                ],
+                cell_color(cell, solver, options, color),
+            );
+        }
+
+        // draw the cell walls
+        for cell in self.iter() {
+            self.draw_cell(renderer, cell, options);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TriangleGrid {
+    rows: usize,
+    cols: usize,
+
+    mask: Option<Mask>,
+
+    // vector of vector of cells so we can easily iterate over rows
+    // cells alternate between upright and inverted
+    grid: Vec<Vec<Option<Cell>>>,
+}
+
+impl TriangleGrid {
+    fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            mask: None,
+            grid: Vec::with_capacity(rows),
+        }
+    }
+
+    fn from_mask(mask: Mask) -> Self {
+        let rows = mask.rows;
+        Self {
+            rows,
+            cols: mask.cols,
+            mask: Some(mask),
+            grid: Vec::with_capacity(rows),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.grid.get(row)?.get(col)?.as_ref()
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.grid.get_mut(row)?.get_mut(col)?.as_mut()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.grid.iter().flatten().flatten()
+    }
+
+    fn init_grid(&mut self) {
+        for row in 0..self.rows {
+            let mut cells = Vec::with_capacity(self.cols);
+            for col in 0..self.cols {
+                let cell = if let Some(mask) = &self.mask {
+                    if mask.get(row, col) {
+                        Some(Cell::new_triangle(row, col))
+                    } else {
+                        None
+                    }
+                } else {
+                    Some(Cell::new_triangle(row, col))
+                };
+                cells.push(cell);
+            }
+            self.grid.push(cells);
+        }
+    }
+
+    fn init_cells(&mut self) {
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let west = if col > 0 {
+                    self.get(row, col - 1).map(|cell| cell.handle())
+                } else {
+                    None
+                };
+
+                let east = self.get(row, col + 1).map(|cell| cell.handle());
+
+                let cell = self.get_mut(row, col);
+                if let Some(cell) = cell {
+                    match cell {
+                        Cell::Triangle(cell) => {
+                            cell.west = west;
+                            cell.east = east;
+                        }
+                        _ => panic!("Invalid cell type"),
+                    }
+                }
+
+                // upright cells share their base with the inverted cell below them
+                // so only one of north / south is ever set
+                let upright = (row + col).is_multiple_of(2);
+
+                let north = if !upright && row > 0 {
+                    self.get(row - 1, col).map(|cell| cell.handle())
+                } else {
+                    None
+                };
+
+                let south = if upright {
+                    self.get(row + 1, col).map(|cell| cell.handle())
+                } else {
+                    None
+                };
+
+                let cell = self.get_mut(row, col);
+                if let Some(cell) = cell {
+                    match cell {
+                        Cell::Triangle(cell) => {
+                            cell.north = north;
+                            cell.south = south;
+                        }
+                        _ => panic!("Invalid cell type"),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the x (west, middle, east)
+    /// and y (apex, base) coordinates of the cell corners
+    fn cell_corners(&self, cell: &Cell, options: &RenderOptions) -> ([f64; 3], [f64; 2]) {
+        // triangle cells are sized by their side length
+        let size = options.cell_size as f64;
+        let padding = options.padding();
+
+        let half_width = size / 2.0;
+        let height = size * 3.0_f64.sqrt() / 2.0;
+        let half_height = height / 2.0;
+
+        // cell center
+        let cx = padding + half_width + cell.col() as f64 * half_width;
+        let cy = padding + half_height + cell.row() as f64 * height;
+
+        let upright = (cell.row() + cell.col()).is_multiple_of(2);
+        let (apex_y, base_y) = if upright {
+            (cy - half_height, cy + half_height)
+        } else {
+            (cy + half_height, cy - half_height)
+        };
+
+        ([cx - half_width, cx, cx + half_width], [apex_y, base_y])
+    }
+
+    fn draw_cell(&self, renderer: &mut dyn Renderer, cell: &Cell, options: &RenderOptions) {
+        let (width, wall) = (options.wall_width(), options.wall_color);
+
+        if let Cell::Triangle(triangle) = cell {
+            let ([west_x, mid_x, east_x], [apex_y, base_y]) = self.cell_corners(cell, options);
+
+            // linked western walls are handled by the western neighbor
+            // so this only needs to cover the edge of the grid
+            if triangle.west.is_none() {
+                renderer.line((west_x, base_y), (mid_x, apex_y), width, wall);
+            }
+
+            if !triangle.east.is_some_and(|east| cell.is_linked(east)) {
+                renderer.line((east_x, base_y), (mid_x, apex_y), width, wall);
+            }
+
+            // the base is shared between an upright cell and the inverted cell below it,
+            // so inverted cells draw it unless linked and upright cells only at the edge
+            let base = if triangle.upright() {
+                triangle.south.is_none()
+            } else {
+                !triangle.north.is_some_and(|north| cell.is_linked(north))
+            };
+
+            if base {
+                renderer.line((east_x, base_y), (west_x, base_y), width, wall);
+            }
+        }
+    }
+
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        // triangle cells are sized by their side length
+        let size = options.cell_size as f64;
+        let height = size * 3.0_f64.sqrt() / 2.0;
+
+        let width = size * (self.cols + 1) as f64 / 2.0;
+        let height = height * self.rows as f64;
+        options.padded_size((width, height))
+    }
+
+    fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        // the centroid is a third of the way up from the base
+        let ([_, mid_x, _], [apex_y, base_y]) = self.cell_corners(cell, options);
+        (mid_x, base_y + (apex_y - base_y) / 3.0)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        solver: Option<&impl Solver>,
+        color: bool,
+    ) {
+        // color cells using the solver
+        for cell in self.iter() {
+            let ([west_x, mid_x, east_x], [apex_y, base_y]) = self.cell_corners(cell, options);
+            renderer.fill_polygon(
+                &[(west_x, base_y), (mid_x, apex_y), (east_x, base_y)],
+                cell_color(cell, solver, options, color),
+            );
+        }
+
+        // draw the cell walls
+        for cell in self.iter() {
+            self.draw_cell(renderer, cell, options);
+        }
+    }
+}
+
+/// Orthogonal grid that allows passages to tunnel under other passages
+#[derive(Debug, Clone)]
+pub struct WeaveGrid {
+    ortho: OrthogonalGrid,
+
+    // cells tunneling under the cell at the same row / col
+    under_cells: BTreeMap<(usize, usize), Cell>,
+}
+
+impl WeaveGrid {
+    // inset of the passage walls from the cell walls
+    // when the render options don't set one
+    // (as a fraction of the cell size)
+    const INSET: f64 = 0.15;
+
+    fn new(ortho: OrthogonalGrid) -> Self {
+        Self {
+            ortho,
+            under_cells: BTreeMap::new(),
+        }
+    }
+
+    fn get_cell(&self, handle: CellHandle) -> Option<&Cell> {
+        if handle.under {
+            self.under_cells.get(&(handle.row, handle.col))
+        } else {
+            self.ortho.get(handle.row, handle.col)
+        }
+    }
+
+    fn get_cell_mut(&mut self, handle: CellHandle) -> Option<&mut Cell> {
+        if handle.under {
+            self.under_cells.get_mut(&(handle.row, handle.col))
+        } else {
+            self.ortho.get_mut(handle.row, handle.col)
+        }
+    }
+
+    fn get_ortho_cell(&self, handle: Option<CellHandle>) -> Option<&OrthogonalCell> {
+        match self.get_cell(handle?)? {
+            Cell::Orthogonal(cell) => Some(cell),
+            _ => panic!("Invalid cell type"),
+        }
+    }
+
+    /// Returns the over cell between two cells
+    /// if they are two apart in a straight line
+    fn get_tunnel_over(a: CellHandle, b: CellHandle) -> Option<CellHandle> {
+        if a.under || b.under {
+            return None;
+        }
+
+        if a.row == b.row && a.col.abs_diff(b.col) == 2 {
+            Some(CellHandle::new(a.row, a.col.min(b.col) + 1))
+        } else if a.col == b.col && a.row.abs_diff(b.row) == 2 {
+            Some(CellHandle::new(a.row.min(b.row) + 1, a.col))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cells that can be reached by tunneling under a neighbor
+    /// Tunnels can only go under straight passages perpendicular to them
+    fn tunnel_neighbors(&self, cell: &Cell) -> Vec<CellHandle> {
+        let mut neighbors = Vec::new();
+
+        let cell = match cell {
+            Cell::Orthogonal(cell) if !cell.under => cell,
+            _ => return neighbors,
+        };
+
+        // under cells can't be tunneled under again
+        let over = |neighbor: Option<CellHandle>| {
+            self.get_ortho_cell(neighbor.filter(|neighbor| !neighbor.under))
+        };
+
+        if let Some(north) = over(cell.north) {
+            if north.is_horizontal_passage() {
+                neighbors.extend(north.north);
+            }
+        }
+
+        if let Some(south) = over(cell.south) {
+            if south.is_horizontal_passage() {
+                neighbors.extend(south.south);
+            }
+        }
+
+        if let Some(east) = over(cell.east) {
+            if east.is_vertical_passage() {
+                neighbors.extend(east.east);
+            }
+        }
+
+        if let Some(west) = over(cell.west) {
+            if west.is_vertical_passage() {
+                neighbors.extend(west.west);
+            }
+        }
+
+        neighbors.retain(|neighbor| !neighbor.under);
+        neighbors
+    }
+
+    /// Adds an under cell beneath the given over cell
+    /// linking the neighbors on either side of it
+    fn tunnel_under(&mut self, over: CellHandle) {
+        assert!(!self.under_cells.contains_key(&(over.row, over.col)));
+
+        let (horizontal, north, south, east, west) = {
+            let over = self.get_ortho_cell(Some(over)).unwrap();
+            (
+                over.is_horizontal_passage(),
+                over.north,
+                over.south,
+                over.east,
+                over.west,
+            )
+        };
+
+        let handle = CellHandle::new_under(over.row, over.col);
+        let mut under = Cell::new_under(over.row, over.col);
+
+        // tunnels run perpendicular to the passage above them
+        let (a, b) = if let Cell::Orthogonal(cell) = &mut under {
+            if horizontal {
+                cell.north = north;
+                cell.south = south;
+                (north.unwrap(), south.unwrap())
+            } else {
+                cell.east = east;
+                cell.west = west;
+                (west.unwrap(), east.unwrap())
+            }
+        } else {
+            unreachable!();
+        };
+
+        under.link(a);
+        under.link(b);
+        self.under_cells.insert((over.row, over.col), under);
+
+        // the cells on either side now neighbor the under cell
+        for neighbor in [a, b] {
+            let cell = self.get_cell_mut(neighbor).unwrap();
+            if let Cell::Orthogonal(cell) = cell {
+                if horizontal {
+                    if neighbor == north.unwrap() {
+                        cell.south = Some(handle);
+                    } else {
+                        cell.north = Some(handle);
+                    }
+                } else if neighbor == west.unwrap() {
+                    cell.east = Some(handle);
+                } else {
+                    cell.west = Some(handle);
+                }
+            }
+
+            cell.link(handle);
+        }
+
+        // and no longer neighbor the cell over it
+        if let Some(Cell::Orthogonal(cell)) = self.get_cell_mut(over) {
+            if horizontal {
+                cell.north = None;
+                cell.south = None;
+            } else {
+                cell.east = None;
+                cell.west = None;
+            }
+        }
+    }
+
+    /// Returns the distance cells are inset by
+    /// Weave grids are always drawn inset so that the tunnels can be seen
+    fn cell_inset(options: &RenderOptions) -> f64 {
+        let size = options.cell_size as f64;
+        if options.inset > 0.0 {
+            options.cell_inset(size)
+        } else {
+            (size * Self::INSET).floor()
+        }
+    }
+
+    /// Returns the north, south, west and east sides of the cell
+    fn cell_sides(&self, cell: &Cell) -> [Side; 4] {
+        match cell {
+            Cell::Orthogonal(ortho) => {
+                [ortho.north, ortho.south, ortho.west, ortho.east].map(|neighbor| {
+                    if neighbor.is_some_and(|neighbor| cell.is_linked(neighbor)) {
+                        Side::Open
+                    } else {
+                        Side::Wall
+                    }
+                })
+            }
+            _ => panic!("Invalid cell type"),
+        }
+    }
+
+    fn draw_cell(&self, renderer: &mut dyn Renderer, cell: &Cell, options: &RenderOptions) {
+        if let Cell::Orthogonal(ortho) = cell {
+            let ((x1, y1), (x4, y4)) = self.ortho.cell_corners(cell, options);
+            let sides = self.cell_sides(cell);
+            let inset = Self::cell_inset(options);
+
+            if !ortho.under {
+                draw_square_cell(renderer, (x1, y1), (x4, y4), sides, inset, options);
+                return;
+            }
+
+            // under cells only draw their passage walls outside the over cell
+            let (width, wall) = (options.wall_width(), options.wall_color);
+            let (x2, y2, x3, y3) = (x1 + inset, y1 + inset, x4 - inset, y4 - inset);
+            let [north, south, west, east] = sides;
+
+            if north == Side::Open {
+                renderer.line((x2, y1), (x2, y2), width, wall);
+                renderer.line((x3, y1), (x3, y2), width, wall);
+            }
+
+            if south == Side::Open {
+                renderer.line((x2, y3), (x2, y4), width, wall);
+                renderer.line((x3, y3), (x3, y4), width, wall);
+            }
+
+            if west == Side::Open {
+                renderer.line((x1, y2), (x2, y2), width, wall);
+                renderer.line((x1, y3), (x2, y3), width, wall);
+            }
+
+            if east == Side::Open {
+                renderer.line((x3, y2), (x4, y2), width, wall);
+                renderer.line((x3, y3), (x4, y3), width, wall);
+            }
+        }
+    }
+
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        self.ortho.image_size(options)
+    }
+
+    fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        self.ortho.cell_center(cell, options)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        solver: Option<&impl Solver>,
+        color: bool,
+    ) {
+        let inset = Self::cell_inset(options);
+
+        // color cells using the solver
+        // tunnels first so the passages over them cover their middles
+        for cell in self.under_cells.values().chain(self.ortho.iter()) {
+            let (from, to) = self.ortho.cell_corners(cell, options);
+            fill_square_cell(
+                renderer,
+                from,
+                to,
+                self.cell_sides(cell),
+                inset,
+                cell_color(cell, solver, options, color),
+            );
+        }
+
+        // draw the cell walls
+        // over cells first and then the tunnels under them
+        for cell in self.ortho.iter().chain(self.under_cells.values()) {
+            self.draw_cell(renderer, cell, options);
+        }
+    }
+}
+
+/// Stacked orthogonal grids with up / down neighbors between levels
+#[derive(Debug, Clone)]
+pub struct LayeredGrid {
+    levels: usize,
+    rows: usize,
+    cols: usize,
+
+    // the same mask is applied to every level
+    mask: Option<Mask>,
+
+    // each level is a consecutive block of rows
+    // so cell handles stay unique across levels
+    grid: Vec<Vec<Option<Cell>>>,
+}
+
+impl LayeredGrid {
+    const DEFAULT_LEVELS: usize = 3;
+
+    fn new(levels: usize, rows: usize, cols: usize) -> Self {
+        Self {
+            levels,
+            rows,
+            cols,
+            mask: None,
+            grid: Vec::with_capacity(levels * rows),
+        }
+    }
+
+    fn from_mask(levels: usize, mask: Mask) -> Self {
+        let rows = mask.rows;
+        Self {
+            levels,
+            rows,
+            cols: mask.cols,
+            mask: Some(mask),
+            grid: Vec::with_capacity(levels * rows),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.levels * self.rows * self.cols
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.grid.get(row)?.get(col)?.as_ref()
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.grid.get_mut(row)?.get_mut(col)?.as_mut()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.grid.iter().flatten().flatten()
+    }
+
+    fn get_random_cell(&self, rng: &mut dyn RngCore) -> CellHandle {
+        let level = rng.gen_range(0..self.levels);
+        let cell = get_random_cell(self.rows, self.cols, &self.mask, rng);
+
+        CellHandle::new((level * self.rows) + cell.row, cell.col)
+    }
+
+    fn init_grid(&mut self) {
+        for level in 0..self.levels {
+            for row in 0..self.rows {
+                let mut cells = Vec::with_capacity(self.cols);
+                for col in 0..self.cols {
+                    let enabled = self.mask.as_ref().is_none_or(|mask| mask.get(row, col));
+
+                    cells.push(if enabled {
+                        Some(Cell::new_layered((level * self.rows) + row, col, level))
+                    } else {
+                        None
+                    });
+                }
+                self.grid.push(cells);
+            }
+        }
+    }
+
+    fn init_cells(&mut self) {
+        for level in 0..self.levels {
+            for level_row in 0..self.rows {
+                let row = (level * self.rows) + level_row;
+                for col in 0..self.cols {
+                    let north = if level_row > 0 {
+                        self.get(row - 1, col).map(|cell| cell.handle())
+                    } else {
+                        None
+                    };
+
+                    let south = if level_row < self.rows - 1 {
+                        self.get(row + 1, col).map(|cell| cell.handle())
+                    } else {
+                        None
+                    };
+
+                    let west = if col > 0 {
+                        self.get(row, col - 1).map(|cell| cell.handle())
+                    } else {
+                        None
+                    };
+
+                    let east = self.get(row, col + 1).map(|cell| cell.handle());
+
+                    let up = if level < self.levels - 1 {
+                        self.get(row + self.rows, col).map(|cell| cell.handle())
+                    } else {
+                        None
+                    };
+
+                    let down = if level > 0 {
+                        self.get(row - self.rows, col).map(|cell| cell.handle())
+                    } else {
+                        None
+                    };
+
+                    let cell = self.get_mut(row, col);
+                    if let Some(cell) = cell {
+                        match cell {
+                            Cell::Layered(cell) => {
+                                cell.north = north;
+                                cell.south = south;
+                                cell.west = west;
+                                cell.east = east;
+                                cell.up = up;
+                                cell.down = down;
+                            }
+                            _ => panic!("Invalid cell type"),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the top left corner of the cell in the image
+    /// Levels are laid out left to right with a cell sized gap between them
+    fn cell_origin(&self, cell: &LayeredCell, options: &RenderOptions) -> Point {
+        let level_offset = cell.level * (self.cols + 1) * options.cell_size;
+        let row = cell.row - (cell.level * self.rows);
+        let padding = options.padding();
+
+        (
+            padding + (level_offset + (cell.col * options.cell_size)) as f64,
+            padding + (row * options.cell_size) as f64,
+        )
+    }
+
+    /// Returns the north, south, west and east sides of the cell
+    fn cell_sides(&self, cell: &Cell) -> [Side; 4] {
+        match cell {
+            Cell::Layered(layered) => [
+                square_side(cell, layered.north, true),
+                square_side(cell, layered.south, false),
+                square_side(cell, layered.west, true),
+                square_side(cell, layered.east, false),
+            ],
+            _ => panic!("Invalid cell type"),
+        }
+    }
+
+    fn draw_cell(&self, renderer: &mut dyn Renderer, cell: &Cell, options: &RenderOptions) {
+        if let Cell::Layered(layered) = cell {
+            let (x, y) = self.cell_origin(layered, options);
+            let size = options.cell_size as f64;
+
+            draw_square_cell(
+                renderer,
+                (x, y),
+                (x + size, y + size),
+                self.cell_sides(cell),
+                options.cell_inset(size),
+                options,
+            );
+
+            // staircases are marked with arrows
+            // pointing up on the right and down on the left
+            let wall = options.wall_color;
+
+            if layered.up.is_some_and(|up| cell.is_linked(up)) {
+                renderer.fill_polygon(
+                    &[
+                        (x + (size * 0.75), y + (size * 0.2)),
+                        (x + (size * 0.9), y + (size * 0.45)),
+                        (x + (size * 0.6), y + (size * 0.45)),
+                    ],
+                    wall,
+                );
+            }
+
+            if layered.down.is_some_and(|down| cell.is_linked(down)) {
+                renderer.fill_polygon(
+                    &[
+                        (x + (size * 0.1), y + (size * 0.55)),
+                        (x + (size * 0.4), y + (size * 0.55)),
+                        (x + (size * 0.25), y + (size * 0.8)),
+                    ],
+                    wall,
+                );
+            }
+        }
+    }
+
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        let width = ((self.levels * (self.cols + 1)) - 1) * options.cell_size;
+        let height = self.rows * options.cell_size;
+        options.padded_size((width as f64, height as f64))
+    }
+
+    fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        match cell {
+            Cell::Layered(layered) => {
+                let (x, y) = self.cell_origin(layered, options);
+                let half = options.cell_size as f64 / 2.0;
+                (x + half, y + half)
+            }
+            _ => panic!("Invalid cell type"),
+        }
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        solver: Option<&impl Solver>,
+        color: bool,
+    ) {
+        let size = options.cell_size as f64;
+
+        // color cells using the solver
+        for cell in self.iter() {
+            if let Cell::Layered(layered) = cell {
+                let (x, y) = self.cell_origin(layered, options);
+                fill_square_cell(
+                    renderer,
+                    (x, y),
+                    (x + size, y + size),
+                    self.cell_sides(cell),
+                    options.cell_inset(size),
+                    cell_color(cell, solver, options, color),
+                );
+            }
+        }
+
+        // draw the cell walls
+        for cell in self.iter() {
+            self.draw_cell(renderer, cell, options);
+        }
+    }
+}
+
+/// How a cube face sits on the cube and in its unfolded net
+struct CubeFace {
+    // north-west corner of the face
+    // (in units of the cube size)
+    origin: [isize; 3],
+
+    // directions of increasing column and row on the face
+    east: [isize; 3],
+    south: [isize; 3],
+
+    // direction out of the cube
+    normal: [isize; 3],
+
+    // face position in the unfolded net
+    net: (usize, usize),
+}
+
+// faces in the order they're stored, unfolding into a cross:
+//
+//      top
+// left front right back
+//      bottom
+const CUBE_FACES: [CubeFace; 6] = [
+    // left
+    CubeFace {
+        origin: [0, 0, 1],
+        east: [0, 0, -1],
+        south: [0, 1, 0],
+        normal: [-1, 0, 0],
+        net: (0, 1),
+    },
+    // front
+    CubeFace {
+        origin: [0, 0, 0],
+        east: [1, 0, 0],
+        south: [0, 1, 0],
+        normal: [0, 0, -1],
+        net: (1, 1),
+    },
+    // right
+    CubeFace {
+        origin: [1, 0, 0],
+        east: [0, 0, 1],
+        south: [0, 1, 0],
+        normal: [1, 0, 0],
+        net: (2, 1),
+    },
+    // back
+    CubeFace {
+        origin: [1, 0, 1],
+        east: [-1, 0, 0],
+        south: [0, 1, 0],
+        normal: [0, 0, 1],
+        net: (3, 1),
+    },
+    // top
+    CubeFace {
+        origin: [0, 0, 1],
+        east: [1, 0, 0],
+        south: [0, 0, -1],
+        normal: [0, -1, 0],
+        net: (1, 0),
+    },
+    // bottom
+    CubeFace {
+        origin: [0, 1, 0],
+        east: [1, 0, 0],
+        south: [0, 0, 1],
+        normal: [0, 1, 0],
+        net: (1, 2),
+    },
+];
+
+/// Orthogonal grids covering the six faces of a cube
+#[derive(Debug, Clone)]
+pub struct CubeGrid {
+    // faces are size x size cells
+    size: usize,
+
+    // the same mask is applied to every face
+    mask: Option<Mask>,
+
+    // each face is a consecutive block of rows
+    // so cell handles stay unique across faces
+    grid: Vec<Vec<Option<Cell>>>,
+}
+
+impl CubeGrid {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            mask: None,
+            grid: Vec::with_capacity(CUBE_FACES.len() * size),
+        }
+    }
+
+    fn from_mask(mask: Mask) -> Self {
+        let size = mask.rows;
+        Self {
+            size,
+            mask: Some(mask),
+            grid: Vec::with_capacity(CUBE_FACES.len() * size),
+        }
+    }
+
+    fn cell_count(&self) -> usize {
+        CUBE_FACES.len() * self.size * self.size
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.grid.get(row)?.get(col)?.as_ref()
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.grid.get_mut(row)?.get_mut(col)?.as_mut()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.grid.iter().flatten().flatten()
+    }
+
+    fn get_random_cell(&self, rng: &mut dyn RngCore) -> CellHandle {
+        let face = rng.gen_range(0..CUBE_FACES.len());
+        let cell = get_random_cell(self.size, self.size, &self.mask, rng);
+
+        CellHandle::new((face * self.size) + cell.row, cell.col)
+    }
+
+    /// Returns the cell one step from the given face row / col
+    /// in the given row / col direction, folding over the cube edges
+    fn step(&self, face: usize, row: usize, col: usize, direction: (isize, isize)) -> CellHandle {
+        let size = self.size as isize;
+        let (row, col) = (row as isize, col as isize);
+
+        let (next_row, next_col) = (row + direction.0, col + direction.1);
+        if (0..size).contains(&next_row) && (0..size).contains(&next_col) {
+            return CellHandle::new((face * self.size) + next_row as usize, next_col as usize);
+        }
+
+        // step to the edge of the face and then half a cell into the cube
+        // which lands on the center of the cell on the neighboring face
+        // (this uses doubled coordinates so cell centers are whole numbers)
+        let from = &CUBE_FACES[face];
+        let point: [isize; 3] = std::array::from_fn(|axis| {
+            (2 * size * from.origin[axis])
+                + ((2 * col + 1 + direction.1) * from.east[axis])
+                + ((2 * row + 1 + direction.0) * from.south[axis])
+                - from.normal[axis]
+        });
+
+        let (face, to) = CUBE_FACES
+            .iter()
+            .enumerate()
+            .find(|(_, to)| {
+                (0..3).any(|axis| match to.normal[axis] {
+                    1 => point[axis] == 2 * size,
+                    -1 => point[axis] == 0,
+                    _ => false,
+                })
+            })
+            .unwrap();
+
+        let dot = |direction: [isize; 3]| {
+            (0..3)
+                .map(|axis| (point[axis] - (2 * size * to.origin[axis])) * direction[axis])
+                .sum::<isize>()
+        };
+
+        CellHandle::new(
+            (face * self.size) + ((dot(to.south) - 1) / 2) as usize,
+            ((dot(to.east) - 1) / 2) as usize,
+        )
+    }
+
+    fn init_grid(&mut self) {
+        for face in 0..CUBE_FACES.len() {
+            for row in 0..self.size {
+                let mut cells = Vec::with_capacity(self.size);
+                for col in 0..self.size {
+                    let enabled = self.mask.as_ref().is_none_or(|mask| mask.get(row, col));
+
+                    cells.push(if enabled {
+                        Some(Cell::new_ortho((face * self.size) + row, col))
+                    } else {
+                        None
+                    });
+                }
+                self.grid.push(cells);
+            }
+        }
+    }
+
+    fn init_cells(&mut self) {
+        for face in 0..CUBE_FACES.len() {
+            for face_row in 0..self.size {
+                let row = (face * self.size) + face_row;
+                for col in 0..self.size {
+                    let neighbor = |direction| {
+                        let handle = self.step(face, face_row, col, direction);
+                        self.get(handle.row, handle.col).map(|cell| cell.handle())
+                    };
+
+                    let north = neighbor((-1, 0));
+                    let south = neighbor((1, 0));
+                    let east = neighbor((0, 1));
+                    let west = neighbor((0, -1));
+
+                    let cell = self.get_mut(row, col);
+                    if let Some(cell) = cell {
+                        match cell {
+                            Cell::Orthogonal(cell) => {
+                                cell.north = north;
+                                cell.south = south;
+                                cell.west = west;
+                                cell.east = east;
+                            }
+                            _ => panic!("Invalid cell type"),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the top left corner of the cell in the unfolded net
+    fn cell_origin(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        let face = cell.row() / self.size;
+        let row = cell.row() % self.size;
+        let (net_x, net_y) = CUBE_FACES[face].net;
+        let padding = options.padding();
+
+        (
+            padding + (((net_x * self.size) + cell.col()) * options.cell_size) as f64,
+            padding + (((net_y * self.size) + row) * options.cell_size) as f64,
+        )
+    }
+
+    /// Returns the north, south, west and east sides of the cell
+    fn cell_sides(&self, cell: &Cell) -> [Side; 4] {
+        match cell {
+            Cell::Orthogonal(ortho) => {
+                // face edges are drawn by both faces
+                // since they aren't next to each other in the net
+                let row = cell.row() % self.size;
+
+                [
+                    square_side(cell, ortho.north, row > 0),
+                    square_side(cell, ortho.south, false),
+                    square_side(cell, ortho.west, cell.col() > 0),
+                    square_side(cell, ortho.east, false),
+                ]
+            }
+            _ => panic!("Invalid cell type"),
+        }
+    }
+
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        // the net is 4 faces wide and 3 faces tall
+        let width = 4 * self.size * options.cell_size;
+        let height = 3 * self.size * options.cell_size;
+        options.padded_size((width as f64, height as f64))
+    }
+
+    fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        let (x, y) = self.cell_origin(cell, options);
+        let half = options.cell_size as f64 / 2.0;
+        (x + half, y + half)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        solver: Option<&impl Solver>,
+        color: bool,
+    ) {
+        let size = options.cell_size as f64;
+        let inset = options.cell_inset(size);
+
+        // color cells using the solver
+        for cell in self.iter() {
+            let (x, y) = self.cell_origin(cell, options);
+            fill_square_cell(
+                renderer,
+                (x, y),
+                (x + size, y + size),
+                self.cell_sides(cell),
+                inset,
+                cell_color(cell, solver, options, color),
+            );
+        }
+
+        // draw the cell walls
+        for cell in self.iter() {
+            let (x, y) = self.cell_origin(cell, options);
+            draw_square_cell(
+                renderer,
+                (x, y),
+                (x + size, y + size),
+                self.cell_sides(cell),
+                inset,
+                options,
+            );
+        }
+    }
+}
+
+/// Polar hemisphere grids joined at their equators
+#[derive(Debug, Clone)]
+pub struct SphereGrid {
+    // rings per hemisphere
+    rows: usize,
+
+    // cells around the equator
+    cols: usize,
+
+    // mask rows are applied to the rings of both hemispheres
+    mask: Option<Mask>,
+
+    // rows run from the north pole to the south pole
+    // so the two equator rings are next to each other
+    grid: Vec<Vec<Option<Cell>>>,
+}
+
+impl SphereGrid {
+    fn new(rows: usize) -> Self {
+        Self {
+            rows,
+            cols: 0,
+            mask: None,
+            grid: Vec::with_capacity(2 * rows),
+        }
+    }
+
+    fn from_mask(mask: Mask) -> Self {
+        let rows = mask.rows;
+        Self {
+            rows,
+            cols: 0,
+            mask: Some(mask),
+            grid: Vec::with_capacity(2 * rows),
+        }
+    }
+
+    fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.grid.get(row)?.get(col)?.as_ref()
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Cell> {
+        self.grid.get_mut(row)?.get_mut(col)?.as_mut()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.grid.iter().flatten().flatten()
+    }
+
+    /// Returns the hemisphere (0 north, 1 south) and ring of the given row
+    fn ring(&self, row: usize) -> (usize, usize) {
+        if row < self.rows {
+            (0, row)
+        } else {
+            (1, (2 * self.rows) - 1 - row)
+        }
+    }
+
+    /// Returns the row of the given hemisphere ring
+    fn ring_row(&self, hemisphere: usize, ring: usize) -> usize {
+        if hemisphere == 0 {
+            ring
+        } else {
+            (2 * self.rows) - 1 - ring
+        }
+    }
+
+    fn is_enabled(&self, ring: usize, col: usize, count: usize) -> bool {
+        if let Some(mask) = &self.mask {
+            // spread the mask columns evenly around the ring
+            mask.get(ring, col * mask.cols / count)
+        } else {
+            true
+        }
+    }
+
+    fn init_grid(&mut self) {
+        // rings shrink towards the poles with the sine of their latitude
+        let row_height = PI / (2 * self.rows) as f64;
+        let counts = ring_counts(self.rows, row_height, |ring| {
+            (ring as f64 * row_height).sin()
+        });
+
+        for row in 0..2 * self.rows {
+            let (_, ring) = self.ring(row);
+            let count = counts[ring];
+
+            let mut cells = Vec::with_capacity(count);
+            for col in 0..count {
+                let cell = if self.is_enabled(ring, col, count) {
+                    Some(Cell::new_polar(row, col))
+                } else {
+                    None
+                };
+                cells.push(cell);
+            }
+            self.grid.push(cells);
+        }
+
+        self.cols = self.grid[self.rows - 1].len();
+    }
+
+    fn init_cells(&mut self) {
+        for row in 0..2 * self.rows {
+            // the poles only have outward neighbors
+            // which get wired up by the first ring
+            let (hemisphere, ring) = self.ring(row);
+            if ring == 0 {
+                continue;
+            }
+
+            let inward_row = self.ring_row(hemisphere, ring - 1);
+            let count = self.grid[row].len();
+
+            // number of cells in this ring for each cell in the inner ring
+            let ratio = count / self.grid[inward_row].len();
+
+            for col in 0..count {
+                let cw = self.get(row, (col + 1) % count).map(|cell| cell.handle());
+                let ccw = self
+                    .get(row, (col + count - 1) % count)
+                    .map(|cell| cell.handle());
+                let inward = self.get(inward_row, col / ratio).map(|cell| cell.handle());
+
+                match self.get_mut(row, col) {
+                    Some(Cell::Polar(cell)) => {
+                        cell.cw = cw;
+                        cell.ccw = ccw;
+                        cell.inward = inward;
+                    }
+                    Some(_) => panic!("Invalid cell type"),
+                    None => continue,
+                }
+
+                if let Some(inward) = inward {
+                    match self.get_mut(inward.row, inward.col) {
+                        Some(Cell::Polar(cell)) => cell.outward.push(CellHandle::new(row, col)),
+                        _ => panic!("Invalid cell type"),
+                    }
+                }
+            }
+        }
+
+        // the equators are outward neighbors of each other
+        let (north, south) = (self.rows - 1, self.rows);
+        for col in 0..self.cols {
+            if self.get(north, col).is_none() || self.get(south, col).is_none() {
+                continue;
+            }
+
+            for (row, other) in [(north, south), (south, north)] {
+                match self.get_mut(row, col) {
+                    Some(Cell::Polar(cell)) => cell.outward.push(CellHandle::new(other, col)),
+                    _ => panic!("Invalid cell type"),
+                }
+            }
+        }
+    }
+
+    /// Returns the center of the given hemisphere in the image
+    /// The hemispheres are laid out side by side, north on the left
+    fn hemisphere_center(&self, hemisphere: usize, options: &RenderOptions) -> Point {
+        let cell_size = Self::scaled_cell_size(options.cell_size);
+        let diameter = (2 * self.rows * cell_size) as f64;
+        let radius = diameter / 2.0;
+        let padding = options.padding();
+
+        (
+            padding + radius + (hemisphere as f64 * (diameter + cell_size as f64)),
+            padding + radius,
+        )
+    }
+
+    fn draw_cell(&self, renderer: &mut dyn Renderer, cell: &Cell, options: &RenderOptions) {
+        let cell_size = Self::scaled_cell_size(options.cell_size);
+        let (width, wall) = (options.wall_width(), options.wall_color);
+
+        if let Cell::Polar(polar) = cell {
+            let (hemisphere, ring) = self.ring(cell.row());
+            let image_center = self.hemisphere_center(hemisphere, options);
+            let count = self.grid[cell.row()].len();
+
+            // cell angle
+            let theta = (2.0 * PI) / count as f64;
+
+            // inner / outer wall distance from center
+            let inner_radius = (ring * cell_size) as f64;
+            let outer_radius = ((ring + 1) * cell_size) as f64;
+
+            // cell wall angles
+            let theta_ccw = cell.col() as f64 * theta;
+            let theta_cw = (cell.col() + 1) as f64 * theta;
+
+            // the poles have no inward or radial walls
+            if ring > 0 {
+                if !polar.inward.is_some_and(|inward| cell.is_linked(inward)) {
+                    renderer.arc(
+                        image_center,
+                        inner_radius,
+                        (theta_ccw, theta_cw),
+                        width,
+                        wall,
+                    );
+                }
+
+                if !polar.cw.is_some_and(|cw| cell.is_linked(cw)) {
+                    renderer.line(
+                        point(image_center, inner_radius, theta_cw),
+                        point(image_center, outer_radius, theta_cw),
+                        width,
+                        wall,
+                    );
+                }
+
+                // linked ccw walls are handled by the ccw neighbor
+                // so this only needs to cover disabled neighbors
+                if polar.ccw.is_none() {
+                    renderer.line(
+                        point(image_center, inner_radius, theta_ccw),
+                        point(image_center, outer_radius, theta_ccw),
+                        width,
+                        wall,
+                    );
+                }
+            }
+
+            // equator walls are drawn on both hemispheres
+            // and the other outward walls border disabled cells
+            if ring + 1 >= self.rows {
+                if !polar.outward.iter().any(|outward| cell.is_linked(*outward)) {
+                    renderer.arc(
+                        image_center,
+                        outer_radius,
+                        (theta_ccw, theta_cw),
+                        width,
+                        wall,
+                    );
+                }
+            } else {
+                let outward_row = self.ring_row(hemisphere, ring + 1);
+                let ratio = self.grid[outward_row].len() / count;
+                let outward_theta = theta / ratio as f64;
+
+                for index in 0..ratio {
+                    let col = cell.col() * ratio + index;
+                    if self.get(outward_row, col).is_none() {
+                        let theta_ccw = col as f64 * outward_theta;
+                        renderer.arc(
+                            image_center,
+                            outer_radius,
+                            (theta_ccw, theta_ccw + outward_theta),
+                            width,
+                            wall,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the cell size sphere grids are drawn with
+    /// They're scaled down like polar grids
+    fn scaled_cell_size(cell_size: usize) -> usize {
+        (cell_size as f64 / 2.0).ceil() as usize
+    }
+
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        let cell_size = Self::scaled_cell_size(options.cell_size);
+
+        // two hemispheres with a cell sized gap between them
+        let diameter = 2 * self.rows * cell_size;
+        options.padded_size((((2 * diameter) + cell_size) as f64, diameter as f64))
+    }
+
+    fn cell_center(&self, cell: &Cell, options: &RenderOptions) -> Point {
+        let cell_size = Self::scaled_cell_size(options.cell_size);
+
+        let (hemisphere, ring) = self.ring(cell.row());
+        let image_center = self.hemisphere_center(hemisphere, options);
+        if ring == 0 {
+            return image_center;
+        }
+
+        let count = self.grid[cell.row()].len();
+        let radius = (ring as f64 + 0.5) * cell_size as f64;
+        let theta = (cell.col() as f64 + 0.5) * (2.0 * PI) / count as f64;
+        point(image_center, radius, theta)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        options: &RenderOptions,
+        solver: Option<&impl Solver>,
+        color: bool,
+    ) {
+        let cell_size = Self::scaled_cell_size(options.cell_size);
+
+        // color cells using the solver
+        for cell in self.iter() {
+            let (hemisphere, ring) = self.ring(cell.row());
+            let count = self.grid[cell.row()].len();
+            let theta = (2.0 * PI) / count as f64;
+
+            renderer.fill_sector(
+                self.hemisphere_center(hemisphere, options),
+                ((ring * cell_size) as f64, ((ring + 1) * cell_size) as f64),
+                (cell.col() as f64 * theta, (cell.col() + 1) as f64 * theta),
+                cell_color(cell, solver, options, color),
+            );
+        }
+
+        // draw the cell walls
+        for cell in self.iter() {
+            self.draw_cell(renderer, cell, options);
+        }
+    }
+}
+
+/// Cell-based grid iterator
+pub struct Iter<'a> {
+    grid: &'a Grid,
+
+    row: usize,
+    col: usize,
+}
+
+impl<'a> Iter<'a> {
+    fn new(grid: &'a Grid) -> Self {
+        Self {
+            grid,
+            row: 0,
+            col: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Cell;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.row < self.grid.row_count() {
+            let (row, col) = (self.row, self.col);
+
+            // rows may not all be the same length
+            self.col += 1;
+            if self.col >= self.grid.row_len(row) {
+                self.row += 1;
+                self.col = 0;
+            }
+
+            let ret = self.grid.get(row, col);
+            if ret.is_some() {
+                return ret;
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> IntoIterator for &'a Grid {
+    type Item = &'a Cell;
     type IntoIter = Iter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -833,36 +3622,26 @@ impl<'a> HandlesIter<'a> {
     }
 }
 
-impl<'a> Iterator for HandlesIter<'a> {
+impl Iterator for HandlesIter<'_> {
     type Item = CellHandle;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let ret = loop {
-            let index = self.grid.index(self.row, self.col);
-            if index >= self.grid.size() {
-                break None;
-            }
-
-            let ret = self.grid.get(self.row, self.col);
-
-            let mut next_row = self.row;
-            let mut next_col = self.col + 1;
-            if next_col >= self.grid.columns() {
-                next_row += 1;
-                next_col = 0;
+        while self.row < self.grid.row_count() {
+            let (row, col) = (self.row, self.col);
+
+            // rows may not all be the same length
+            self.col += 1;
+            if self.col >= self.grid.row_len(row) {
+                self.row += 1;
+                self.col = 0;
             }
 
-            self.row = next_row;
-            self.col = next_col;
-
-            if ret.is_none() {
-                continue;
+            if let Some(cell) = self.grid.get(row, col) {
+                return Some(cell.handle());
             }
+        }
 
-            break ret.map(|cell| cell.handle());
-        };
-
-        ret
+        None
     }
 }
 
@@ -888,43 +3667,31 @@ impl<'a> Iterator for IterMut<'a> {
     type Item = &'a mut Cell;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let ret = loop {
-            let index = self.grid.index(self.row, self.col);
-            if index >= self.grid.size() {
-                break None;
+        while self.row < self.grid.row_count() {
+            let (row, col) = (self.row, self.col);
+
+            // rows may not all be the same length
+            self.col += 1;
+            if self.col >= self.grid.row_len(row) {
+                self.row += 1;
+                self.col = 0;
             }
 
-            //let ret = self.grid.get_mut(self.row, self.col);
+            //let ret = self.grid.get_mut(row, col);
             // TODO: can we rework anything to remove this unsafe?
             let ret = unsafe {
-                if self.row >= self.grid.rows() || self.col >= self.grid.columns() {
-                    return None;
-                }
-
-                let cols = self.grid.get_row_mut(self.row).unwrap();
+                let cols = self.grid.get_row_mut(row).unwrap();
                 let ptr = cols.as_mut_ptr();
 
-                (&mut *ptr.add(self.col)).as_mut()
+                (*ptr.add(col)).as_mut()
             };
 
-            let mut next_row = self.row;
-            let mut next_col = self.col + 1;
-            if next_col >= self.grid.columns() {
-                next_row += 1;
-                next_col = 0;
-            }
-
-            self.row = next_row;
-            self.col = next_col;
-
-            if ret.is_none() {
-                continue;
+            if ret.is_some() {
+                return ret;
             }
+        }
 
-            break ret;
-        };
-
-        ret
+        None
     }
 }
 
@@ -936,3 +3703,516 @@ impl<'a> IntoIterator for &'a mut Grid {
         self.iter_mut()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::generators::{Generator, RecursiveBacktracker};
+    use crate::solvers::Djikstra;
+    use crate::util::seeded_rng;
+
+    /// Records the solution route and the markers drawn in the given colors
+    struct SolutionRecorder {
+        options: RenderOptions,
+        steps: Vec<(Point, Point)>,
+        markers: Vec<(Point, Color)>,
+    }
+
+    impl Renderer for SolutionRecorder {
+        fn line(&mut self, from: Point, to: Point, _width: f64, color: Color) {
+            if color == self.options.solution_color {
+                self.steps.push((from, to));
+            }
+        }
+
+        fn arc(
+            &mut self,
+            center: Point,
+            radius: f64,
+            angles: (f64, f64),
+            _width: f64,
+            color: Color,
+        ) {
+            if color == self.options.solution_color {
+                self.steps.push((
+                    point(center, radius, angles.0),
+                    point(center, radius, angles.1),
+                ));
+            }
+        }
+
+        fn fill_polygon(&mut self, _points: &[Point], _color: Color) {}
+
+        fn text(&mut self, _position: Point, _text: &str, _color: Color) {}
+
+        fn fill_sector(
+            &mut self,
+            center: Point,
+            radii: (f64, f64),
+            _angles: (f64, f64),
+            color: Color,
+        ) {
+            if radii == (0.0, self.options.marker_radius()) {
+                self.markers.push((center, color));
+            }
+        }
+    }
+
+    /// Rounds a point so that the same point computed two ways compares equal
+    fn rounded((x, y): Point) -> (i64, i64) {
+        ((x * 100.0).round() as i64, (y * 100.0).round() as i64)
+    }
+
+    /// Returns a step between two points regardless of its direction
+    fn step(a: Point, b: Point) -> ((i64, i64), (i64, i64)) {
+        let (a, b) = (rounded(a), rounded(b));
+        (a.min(b), a.max(b))
+    }
+
+    #[test]
+    fn checked_sizes_have_no_lone_cells() {
+        for grid_type in [
+            GridType::Orthogonal,
+            GridType::Polar,
+            GridType::Hex,
+            GridType::Triangle,
+            GridType::Weave,
+            GridType::Cylinder,
+            GridType::Torus,
+            GridType::Mobius,
+            GridType::Layered(1),
+            GridType::Layered(2),
+            GridType::Cube,
+            GridType::Sphere,
+        ] {
+            for (rows, cols) in (0..=4).flat_map(|rows| (0..=4).map(move |cols| (rows, cols))) {
+                if grid_type.check_size(rows, cols).is_err() {
+                    continue;
+                }
+
+                let grid = RecursiveBacktracker.generate(rows, cols, grid_type, &mut seeded_rng(1));
+                assert!(
+                    grid.iter().all(|cell| cell.links().len() > 0),
+                    "a {}x{} {} grid has a lone cell",
+                    rows,
+                    cols,
+                    grid_type
+                );
+            }
+        }
+
+        assert!(GridType::Polar.check_size(1, 20).is_err());
+        assert!(GridType::Orthogonal.check_size(1, 1).is_err());
+        assert!(GridType::Orthogonal.check_size(1, 2).is_ok());
+        assert!(GridType::Layered(2).check_size(1, 1).is_ok());
+        assert!(GridType::Triangle.check_size(5, 1).is_err());
+        assert!(GridType::Triangle.check_size(1, 2).is_ok());
+        assert!(GridType::Torus.check_size(2, 2).is_err());
+        assert!(GridType::Torus.check_size(3, 2).is_err());
+        assert!(GridType::Mobius.check_size(1, 3).is_ok());
+    }
+
+    #[test]
+    fn full_braid_leaves_no_dead_ends() {
+        for grid_type in [GridType::Orthogonal, GridType::Polar, GridType::Hex] {
+            for seed in [1, 2, 3] {
+                let mut rng = seeded_rng(seed);
+                let mut grid = RecursiveBacktracker.generate(12, 12, grid_type, &mut rng);
+                assert!(!grid.get_dead_ends().is_empty());
+
+                grid.braid(1.0, &mut rng);
+                assert!(
+                    grid.get_dead_ends().is_empty(),
+                    "braiding left dead ends on a {} grid (seed={})",
+                    grid_type,
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cull_stops_at_the_target() {
+        for grid_type in [GridType::Orthogonal, GridType::Polar, GridType::Hex] {
+            for fraction in [0.0, 0.1, 0.5, 0.9] {
+                let mut rng = seeded_rng(1);
+                let mut grid = RecursiveBacktracker.generate(12, 12, grid_type, &mut rng);
+                let target = (fraction * grid.enabled_count() as f64).ceil() as usize;
+
+                grid.cull(CullLimit::Remaining(fraction), &mut rng);
+
+                let remaining = grid.enabled_count();
+                assert!(
+                    remaining >= 2,
+                    "culled a {} grid to {}",
+                    grid_type,
+                    remaining
+                );
+                if fraction > 0.0 {
+                    assert_eq!(
+                        remaining, target,
+                        "culled a {} grid past the target",
+                        grid_type
+                    );
+                }
+
+                // culling only removes dead ends, so every cell left is still reachable
+                let start = grid.get_first_enabled().unwrap();
+                let reachable = crate::distances(&grid, start).cells_by_distance().len();
+                assert_eq!(reachable, remaining, "culling split a {} grid", grid_type);
+            }
+        }
+    }
+
+    #[test]
+    fn solution_follows_the_solved_path() {
+        for grid_type in [GridType::Orthogonal, GridType::Polar, GridType::Hex] {
+            let grid = RecursiveBacktracker.generate(10, 10, grid_type, &mut seeded_rng(1));
+            let (root, goal) = grid.longest_path();
+            let solver = Djikstra::new(grid, root.0, root.1);
+            solver.solve(goal.0, goal.1);
+
+            let path = solver.path();
+            assert!(path.len() > 2);
+
+            let options = RenderOptions::default().with_solution(true);
+            let mut recorder = SolutionRecorder {
+                options,
+                steps: Vec::new(),
+                markers: Vec::new(),
+            };
+            solver.draw(&mut recorder, &options, false);
+
+            let grid = solver.grid();
+            let center =
+                |handle: &CellHandle| grid.cell_center(grid.get_cell(*handle).unwrap(), &options);
+            let expected = path
+                .windows(2)
+                .map(|cells| step(center(&cells[0]), center(&cells[1])))
+                .collect::<HashSet<_>>();
+            let drawn = recorder
+                .steps
+                .iter()
+                .map(|(a, b)| step(*a, *b))
+                .collect::<HashSet<_>>();
+
+            assert_eq!(
+                recorder.steps.len(),
+                path.len() - 1,
+                "on a {} grid",
+                grid_type
+            );
+            assert_eq!(drawn, expected, "on a {} grid", grid_type);
+            assert_eq!(
+                recorder.markers,
+                [
+                    (center(path.first().unwrap()), options.start_color),
+                    (center(path.last().unwrap()), options.goal_color),
+                ]
+            );
+
+            // nothing is drawn over the maze unless it's asked for
+            let options = RenderOptions::default();
+            let mut recorder = SolutionRecorder {
+                options,
+                steps: Vec::new(),
+                markers: Vec::new(),
+            };
+            solver.draw(&mut recorder, &options, false);
+            assert!(recorder.steps.is_empty() && recorder.markers.is_empty());
+        }
+
+        // steps across a seam would be drawn across the whole image,
+        // wrapped grids split them in half at the edges
+        // and the rest leave a gap
+        let options = RenderOptions::default().with_solution(true);
+        let cell_size = options.cell_size as f64;
+        for (grid_type, size, max_length) in [
+            (GridType::Torus, 10, cell_size),
+            (GridType::Cube, 4, 2.0 * cell_size),
+        ] {
+            let grid = RecursiveBacktracker.generate(size, size, grid_type, &mut seeded_rng(2));
+            let (root, goal) = grid.longest_path();
+            let solver = Djikstra::new(grid, root.0, root.1);
+            solver.solve(goal.0, goal.1);
+
+            let mut recorder = SolutionRecorder {
+                options,
+                steps: Vec::new(),
+                markers: Vec::new(),
+            };
+            solver.draw(&mut recorder, &options, false);
+
+            let grid = solver.grid();
+            let centers = solver
+                .path()
+                .iter()
+                .map(|handle| grid.cell_center(grid.get_cell(*handle).unwrap(), &options))
+                .collect::<Vec<Point>>();
+            let length = |(a, b): (Point, Point)| (b.0 - a.0).hypot(b.1 - a.1);
+            let seams = centers
+                .windows(2)
+                .filter(|step| length((step[0], step[1])) > max_length)
+                .count();
+            assert!(seams > 0, "no seams crossed on a {} grid", grid_type);
+
+            let expected = if grid_type == GridType::Torus {
+                centers.len() - 1 + seams
+            } else {
+                centers.len() - 1 - seams
+            };
+            assert_eq!(recorder.steps.len(), expected, "on a {} grid", grid_type);
+
+            let centers = centers.into_iter().map(rounded).collect::<HashSet<_>>();
+            for &(a, b) in &recorder.steps {
+                assert!(centers.contains(&rounded(a)), "on a {} grid", grid_type);
+                assert!(
+                    length((a, b)) <= max_length,
+                    "drew a step across a {} grid",
+                    grid_type
+                );
+            }
+        }
+    }
+
+    /// Asserts that every neighbor of every cell is a different enabled cell
+    /// that lists the cell back as one of its own neighbors
+    fn assert_neighbors_agree(grid: &Grid) {
+        let handles = grid
+            .handles_iter()
+            .chain(match grid {
+                Grid::Weave(weave) => weave.under_cells.values().map(Cell::handle).collect(),
+                _ => vec![],
+            })
+            .collect::<Vec<CellHandle>>();
+
+        for handle in handles {
+            let neighbors = grid.neighbors(handle);
+            assert_eq!(
+                neighbors.iter().collect::<HashSet<_>>().len(),
+                neighbors.len(),
+                "{:?} has duplicate neighbors on a {} grid",
+                handle,
+                grid.grid_type()
+            );
+
+            for neighbor in neighbors {
+                assert_ne!(neighbor, handle, "{:?} neighbors itself", handle);
+                assert!(
+                    grid.get_cell(neighbor).is_some() && grid.neighbors(neighbor).contains(&handle),
+                    "{:?} doesn't list {:?} back on a {} grid",
+                    neighbor,
+                    handle,
+                    grid.grid_type()
+                );
+            }
+        }
+    }
+
+    fn neighbors(grid: &Grid, row: usize, col: usize) -> HashSet<CellHandle> {
+        grid.neighbors(CellHandle::new(row, col))
+            .into_iter()
+            .collect()
+    }
+
+    fn handles(cells: &[(usize, usize)]) -> HashSet<CellHandle> {
+        cells
+            .iter()
+            .map(|&(row, col)| CellHandle::new(row, col))
+            .collect()
+    }
+
+    #[test]
+    fn polar_rings_nest() {
+        for rows in 2..=8 {
+            let grid = Grid::new_polar(rows);
+            assert_neighbors_agree(&grid);
+
+            // every cell outside the center has exactly one cell inward of it
+            for cell in grid.iter().filter(|cell| cell.row() > 0) {
+                let inward = grid
+                    .neighbors(cell.handle())
+                    .iter()
+                    .filter(|neighbor| neighbor.row == cell.row() - 1)
+                    .count();
+                assert_eq!(inward, 1, "{:?} in a {} ring grid", cell.handle(), rows);
+            }
+        }
+    }
+
+    #[test]
+    fn hex_columns_zig_zag() {
+        for (rows, cols) in [(1, 2), (2, 1), (4, 4), (5, 7)] {
+            assert_neighbors_agree(&Grid::new_hex(rows, cols));
+        }
+
+        // odd columns sit half a cell lower than even columns
+        let grid = Grid::new_hex(4, 4);
+        assert_eq!(
+            neighbors(&grid, 1, 1),
+            handles(&[(0, 1), (2, 1), (1, 0), (2, 0), (1, 2), (2, 2)])
+        );
+        assert_eq!(
+            neighbors(&grid, 1, 2),
+            handles(&[(0, 2), (2, 2), (0, 1), (1, 1), (0, 3), (1, 3)])
+        );
+    }
+
+    #[test]
+    fn triangles_share_bases() {
+        for (rows, cols) in [(1, 2), (2, 2), (4, 5), (5, 4)] {
+            let grid = Grid::new_triangle(rows, cols);
+            assert_neighbors_agree(&grid);
+
+            // upright cells share their base with the inverted cell below them
+            for cell in grid.iter() {
+                let (row, col) = (cell.row(), cell.col());
+                let upright = (row + col).is_multiple_of(2);
+                let base = if upright {
+                    (row + 1 < rows).then(|| (row + 1, col))
+                } else {
+                    row.checked_sub(1).map(|row| (row, col))
+                };
+
+                let mut expected = vec![];
+                expected.extend(base);
+                expected.extend(col.checked_sub(1).map(|col| (row, col)));
+                expected.extend((col + 1 < cols).then(|| (row, col + 1)));
+                assert_eq!(neighbors(&grid, row, col), handles(&expected));
+            }
+        }
+    }
+
+    #[test]
+    fn weave_tunnels_pass_under_straight_passages() {
+        let mut tunnels = 0;
+        for seed in 1..=5 {
+            let grid =
+                RecursiveBacktracker.generate(10, 10, GridType::Weave, &mut seeded_rng(seed));
+            assert_neighbors_agree(&grid);
+
+            let Grid::Weave(weave) = &grid else {
+                unreachable!()
+            };
+            for (&(row, col), under) in &weave.under_cells {
+                tunnels += 1;
+
+                // the tunnel runs straight through under the cell above it
+                let over = match grid.get(row, col) {
+                    Some(Cell::Orthogonal(cell)) => cell,
+                    _ => panic!("({}, {}) has nothing over it", row, col),
+                };
+                let links = under.links().copied().collect::<Vec<CellHandle>>();
+                assert_eq!(links.len(), 2);
+                if links[0].row == row {
+                    assert!(over.is_vertical_passage());
+                    assert_eq!(links[0].col.abs_diff(links[1].col), 2);
+                } else {
+                    assert!(over.is_horizontal_passage());
+                    assert_eq!(links[0].row.abs_diff(links[1].row), 2);
+                }
+            }
+        }
+        assert!(tunnels > 0);
+    }
+
+    #[test]
+    fn wrapped_edges_meet() {
+        for (rows, cols) in [(1, 3), (3, 3), (4, 5), (5, 3)] {
+            for wrap in [Wrap::Cylinder, Wrap::Torus, Wrap::Mobius] {
+                if wrap == Wrap::Torus && rows < 3 {
+                    continue;
+                }
+
+                let grid = Grid::new_wrapped(rows, cols, wrap);
+                assert_neighbors_agree(&grid);
+
+                for row in 0..rows {
+                    // the Möbius seam joins each row to its mirrored row
+                    let seam_row = if wrap == Wrap::Mobius {
+                        rows - 1 - row
+                    } else {
+                        row
+                    };
+                    assert!(neighbors(&grid, row, 0).contains(&CellHandle::new(seam_row, cols - 1)));
+                }
+
+                for col in 0..cols {
+                    assert_eq!(
+                        neighbors(&grid, 0, col).contains(&CellHandle::new(rows - 1, col)),
+                        wrap == Wrap::Torus,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn layers_stack() {
+        for (levels, rows, cols) in [(2, 1, 1), (3, 4, 5)] {
+            let grid = Grid::new_layered(levels, rows, cols);
+            assert_neighbors_agree(&grid);
+
+            for cell in grid.iter() {
+                let (row, col) = (cell.row(), cell.col());
+                let level = row / rows;
+
+                let mut expected = vec![];
+                expected.extend((row % rows > 0).then(|| (row - 1, col)));
+                expected.extend((row % rows + 1 < rows).then(|| (row + 1, col)));
+                expected.extend(col.checked_sub(1).map(|col| (row, col)));
+                expected.extend((col + 1 < cols).then(|| (row, col + 1)));
+                expected.extend((level > 0).then(|| (row - rows, col)));
+                expected.extend((level + 1 < levels).then(|| (row + rows, col)));
+                assert_eq!(neighbors(&grid, row, col), handles(&expected));
+            }
+        }
+    }
+
+    #[test]
+    fn cube_faces_fold() {
+        for size in 1..=5 {
+            let grid = Grid::new_cube(size);
+            assert_eq!(grid.enabled_count(), 6 * size * size);
+            assert_neighbors_agree(&grid);
+
+            // every cell has four sides, even at the corners of the faces
+            for cell in grid.iter() {
+                assert_eq!(grid.neighbors(cell.handle()).len(), 4);
+            }
+
+            // corners touch two other faces as well as their own
+            // (single cell faces touch all four around them)
+            let faces = |handle: CellHandle| {
+                grid.neighbors(handle)
+                    .iter()
+                    .map(|neighbor| neighbor.row / size)
+                    .collect::<HashSet<usize>>()
+            };
+            for face in 0..6 {
+                let corner = CellHandle::new(face * size, 0);
+                let expected = if size == 1 { 4 } else { 3 };
+                assert_eq!(faces(corner).len(), expected, "size {}", size);
+            }
+        }
+    }
+
+    #[test]
+    fn sphere_equators_meet() {
+        for rows in 1..=6 {
+            let grid = Grid::new_sphere(rows);
+            assert_neighbors_agree(&grid);
+
+            let Grid::Sphere(sphere) = &grid else {
+                unreachable!()
+            };
+            let (north, south) = (rows - 1, rows);
+            assert_eq!(sphere.grid[north].len(), sphere.grid[south].len());
+            for col in 0..sphere.cols {
+                assert!(neighbors(&grid, north, col).contains(&CellHandle::new(south, col)));
+            }
+        }
+    }
+}
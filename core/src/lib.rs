@@ -1,27 +1,76 @@
+pub mod animation;
 mod cell;
 mod distances;
 pub mod generators;
+mod gif;
 pub mod grid;
 pub mod mask;
+pub mod render;
 pub mod solvers;
+pub mod stream;
 mod util;
 
+use std::fs;
 use std::io;
 use std::path::Path;
 
+pub use cell::CellHandle;
 use cell::*;
 use distances::*;
 pub use grid::*;
 pub use mask::*;
+use render::{colored_path, ImageRenderer, RenderOptions, Renderer, SvgRenderer};
+pub use util::{random_seed, seeded_rng, MazeRng};
 
 /// Implement this trait to allow rendering a maze
 pub trait Renderable {
     /// Renders the renderable to the CLI
     fn render_ascii(&self) -> String;
 
+    /// Returns the size of the image drawn with the given options
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize);
+
+    /// Draws the renderable with the given renderer
+    /// Cells are colored in if color is set, otherwise they're filled with the path color
+    fn draw(&self, renderer: &mut dyn Renderer, options: &RenderOptions, color: bool);
+
     /// Renders the renderable to a buffer
-    fn render(&self, cell_size: usize, color: bool) -> ((usize, usize), Vec<u8>);
+    fn render(&self, options: &RenderOptions, color: bool) -> ((usize, usize), Vec<u8>) {
+        let mut image = ImageRenderer::new(self.image_size(options));
+        self.draw(&mut image, options, color);
+        image.finish()
+    }
 
     /// Saves the renderable as a PNG at the given path
-    fn save_png(&self, path: &Path, cell_size: usize) -> io::Result<()>;
+    /// along with a colored copy next to it
+    fn save_png(&self, path: &Path, options: &RenderOptions) -> io::Result<()> {
+        // save in greyscale
+        let (size, data) = self.render(options, false);
+        render::save_png(path, size, &data)?;
+
+        // save in color
+        let (size, data) = self.render(options, true);
+        render::save_png(colored_path(path), size, &data)?;
+
+        Ok(())
+    }
+
+    /// Renders the renderable to an SVG document
+    fn render_svg(&self, options: &RenderOptions, color: bool) -> String {
+        let mut svg = SvgRenderer::new(self.image_size(options));
+        self.draw(&mut svg, options, color);
+        svg.finish()
+    }
+
+    /// Saves the renderable as an SVG at the given path
+    /// along with a colored copy next to it
+    fn save_svg(&self, path: &Path, options: &RenderOptions) -> io::Result<()> {
+        // save in greyscale
+        fs::write(path, self.render_svg(options, false))?;
+
+        // save in color
+        fs::write(colored_path(path), self.render_svg(options, true))?;
+
+        Ok(())
+    }
 }
@@ -3,7 +3,7 @@ use std::io;
 use std::path::Path;
 
 use bit_vec::BitVec;
-use rand::Rng;
+use rand::{Rng, RngCore};
 use tracing::{debug, info};
 
 use crate::util::read_file_lines;
@@ -34,8 +34,8 @@ impl Mask {
     fn remove_comments(lines: Vec<String>) -> Vec<String> {
         lines
             .iter()
-            .cloned()
             .filter(|line| !line.starts_with('#'))
+            .cloned()
             .collect()
     }
 
@@ -46,22 +46,16 @@ impl Mask {
         let lines: Vec<String> = Self::remove_comments(
             read_file_lines(path)?
                 .iter()
-                .cloned()
                 .filter(|x| !x.is_empty())
+                .cloned()
                 .collect(),
         );
         if lines.is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Invalid mask - no rows",
-            ));
+            return Err(io::Error::other("Invalid mask - no rows"));
         }
 
         if !lines.iter().all(|x| x.len() == lines[0].len()) {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Invalid mask - column length mismatch",
-            ));
+            return Err(io::Error::other("Invalid mask - column length mismatch"));
         }
 
         let mut mask = Mask::new(lines.len(), lines[0].len());
@@ -88,17 +82,11 @@ impl Mask {
         let info = reader.next_frame(&mut buf)?;
 
         if info.color_type != png::ColorType::Rgba {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Invalid mask - must be rgba",
-            ));
+            return Err(io::Error::other("Invalid mask - must be rgba"));
         }
 
         if info.bit_depth != png::BitDepth::Eight {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Invalid mask - bit depth must be 8",
-            ));
+            return Err(io::Error::other("Invalid mask - bit depth must be 8"));
         }
 
         debug!("data size: {}", info.buffer_size());
@@ -146,11 +134,9 @@ impl Mask {
     }
 
     /// Returns a random enabled cell
-    pub fn get_random(&self) -> (usize, usize) {
+    pub fn get_random(&self, rng: &mut dyn RngCore) -> (usize, usize) {
         assert!(self.bits.any());
 
-        let mut rng = rand::thread_rng();
-
         // TODO: this could be smarter and avoid looping
         loop {
             let row = rng.gen_range(0..self.rows);
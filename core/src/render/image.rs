@@ -0,0 +1,243 @@
+use std::f64::consts::PI;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use tracing::debug;
+
+use super::{sector_bounds, Point, Renderer};
+use crate::util::{arc, line, plot, polygon, quad, Color};
+
+/// Renders to an RGBA image buffer
+/// Pixels that nothing is drawn on are left transparent
+/// and anything outside of the image is clipped
+#[derive(Debug, Clone)]
+pub struct ImageRenderer {
+    size: (usize, usize),
+    data: Vec<u8>,
+}
+
+impl ImageRenderer {
+    /// Creates a transparent image of the given size
+    pub fn new(size: (usize, usize)) -> Self {
+        Self {
+            size,
+            data: vec![0; size.0 * size.1 * 4],
+        }
+    }
+
+    /// Returns the image size in pixels
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// Returns the image size and its RGBA data
+    pub fn finish(self) -> ((usize, usize), Vec<u8>) {
+        (self.size, self.data)
+    }
+}
+
+impl Renderer for ImageRenderer {
+    /// Thin lines are drawn a pixel wide,
+    /// anything wider is filled in as a rectangle
+    fn line(&mut self, from: Point, to: Point, width: f64, color: Color) {
+        if width <= 1.0 {
+            line(
+                &mut self.data,
+                self.size,
+                (from.0.floor() as isize, from.1.floor() as isize),
+                (to.0.floor() as isize, to.1.floor() as isize),
+                color,
+            );
+            return;
+        }
+
+        // half the width along the line and across it
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        let (ax, ay) = if length > 0.0 {
+            (dx / length * width / 2.0, dy / length * width / 2.0)
+        } else {
+            (width / 2.0, 0.0)
+        };
+        let (cx, cy) = (-ay, ax);
+
+        polygon(
+            &mut self.data,
+            self.size,
+            &[
+                (from.0 - ax + cx, from.1 - ay + cy),
+                (to.0 + ax + cx, to.1 + ay + cy),
+                (to.0 + ax - cx, to.1 + ay - cy),
+                (from.0 - ax - cx, from.1 - ay - cy),
+            ],
+            color,
+        );
+    }
+
+    /// Thin arcs are drawn a pixel wide,
+    /// anything wider is filled in as a sector of a ring
+    fn arc(&mut self, center: Point, radius: f64, angles: (f64, f64), width: f64, color: Color) {
+        if width <= 1.0 {
+            arc(&mut self.data, self.size, center, radius, angles, color);
+            return;
+        }
+
+        // extend the ends by half the width to square them off
+        let (from, to) = (angles.0.min(angles.1), angles.0.max(angles.1));
+        let cap = width / 2.0 / radius.max(width / 2.0);
+        self.fill_sector(
+            center,
+            ((radius - width / 2.0).max(0.0), radius + width / 2.0),
+            (from - cap, to + cap),
+            color,
+        );
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        polygon(&mut self.data, self.size, points, color);
+    }
+
+    fn text(&mut self, position: Point, text: &str, color: Color) {
+        // glyphs are 3x5 pixels with a pixel between them
+        let width = (text.chars().count() * 4).saturating_sub(1) as f64;
+        let left = (position.0 - width / 2.0).round() as isize;
+        let top = (position.1 - 2.5).round() as isize;
+
+        for (index, c) in text.chars().enumerate() {
+            let bits = glyph(c);
+            for row in 0..5 {
+                for col in 0..3 {
+                    if bits & (1 << (14 - (row * 3 + col))) == 0 {
+                        continue;
+                    }
+
+                    let x = left + (index * 4 + col) as isize;
+                    let y = top + row as isize;
+                    if x >= 0 && y >= 0 && (x as usize) < self.size.0 && (y as usize) < self.size.1
+                    {
+                        plot(&mut self.data, self.size.0, x as usize, y as usize, color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fills every pixel from the first corner to the second, inclusive
+    fn fill_rect(&mut self, from: Point, to: Point, color: Color) {
+        let (min_x, max_x) = (from.0.min(to.0), from.0.max(to.0));
+        let (min_y, max_y) = (from.1.min(to.1), from.1.max(to.1));
+        if max_x < 0.0 || max_y < 0.0 || min_x >= self.size.0 as f64 || min_y >= self.size.1 as f64
+        {
+            return;
+        }
+
+        quad(
+            &mut self.data,
+            self.size.0,
+            min_x as usize,
+            min_y as usize,
+            (max_x as usize).min(self.size.0 - 1),
+            (max_y as usize).min(self.size.1 - 1),
+            color,
+        );
+    }
+
+    /// Fills the pixels whose center falls within the sector,
+    /// so neighboring sectors don't overlap or leave gaps
+    fn fill_sector(&mut self, center: Point, radii: (f64, f64), angles: (f64, f64), color: Color) {
+        let (from, to) = (angles.0.min(angles.1), angles.0.max(angles.1));
+
+        let (min, max) = sector_bounds(center, radii, angles);
+        let (min_x, min_y) = (min.0.max(0.0) as usize, min.1.max(0.0) as usize);
+        let max_x = (max.0.ceil().max(0.0) as usize).min(self.size.0 - 1);
+        let max_y = (max.1.ceil().max(0.0) as usize).min(self.size.1 - 1);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let dx = x as f64 + 0.5 - center.0;
+                let dy = y as f64 + 0.5 - center.1;
+
+                let radius = (dx * dx + dy * dy).sqrt();
+                if radius < radii.0 || radius >= radii.1 {
+                    continue;
+                }
+
+                // measured from the start of the sector so it can cross 0
+                let theta = (dy.atan2(dx) - from).rem_euclid(2.0 * PI);
+                if theta >= to - from {
+                    continue;
+                }
+
+                plot(&mut self.data, self.size.0, x, y, color);
+            }
+        }
+    }
+}
+
+/// Saves RGBA image data as a PNG at the given path
+pub(crate) fn save_png(
+    path: impl AsRef<Path>,
+    size: (usize, usize),
+    data: &[u8],
+) -> io::Result<()> {
+    let file = fs::File::create(path)?;
+    let writer = io::BufWriter::new(file);
+    let mut encoder = png::Encoder::new(writer, size.0 as u32, size.1 as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+
+    debug!("data size: {}", data.len());
+    writer.write_image_data(data)?;
+
+    Ok(())
+}
+
+/// Returns the 3x5 bitmap of a character, a row of 3 bits at a time from the top
+/// Letters are all drawn in upper case and anything unknown is left blank
+fn glyph(c: char) -> u16 {
+    match c.to_ascii_uppercase() {
+        '0' => 0b111_101_101_101_111,
+        '1' => 0b010_110_010_010_111,
+        '2' => 0b111_001_111_100_111,
+        '3' => 0b111_001_111_001_111,
+        '4' => 0b101_101_111_001_001,
+        '5' => 0b111_100_111_001_111,
+        '6' => 0b111_100_111_101_111,
+        '7' => 0b111_001_001_001_001,
+        '8' => 0b111_101_111_101_111,
+        '9' => 0b111_101_111_001_111,
+        'A' => 0b010_101_111_101_101,
+        'B' => 0b110_101_110_101_110,
+        'C' => 0b011_100_100_100_011,
+        'D' => 0b110_101_101_101_110,
+        'E' => 0b111_100_110_100_111,
+        'F' => 0b111_100_110_100_100,
+        'G' => 0b011_100_101_101_011,
+        'H' => 0b101_101_111_101_101,
+        'I' => 0b111_010_010_010_111,
+        'J' => 0b001_001_001_101_010,
+        'K' => 0b101_101_110_101_101,
+        'L' => 0b100_100_100_100_111,
+        'M' => 0b101_111_111_101_101,
+        'N' => 0b110_101_101_101_101,
+        'O' => 0b010_101_101_101_010,
+        'P' => 0b110_101_110_100_100,
+        'Q' => 0b010_101_101_110_011,
+        'R' => 0b110_101_110_101_101,
+        'S' => 0b011_100_010_001_110,
+        'T' => 0b111_010_010_010_010,
+        'U' => 0b101_101_101_101_111,
+        'V' => 0b101_101_101_101_010,
+        'W' => 0b101_101_111_111_101,
+        'X' => 0b101_101_010_101_101,
+        'Y' => 0b101_101_010_010_010,
+        'Z' => 0b111_001_010_100_111,
+        '-' => 0b000_000_111_000_000,
+        '.' => 0b000_000_000_000_010,
+        ':' => 0b000_010_000_010_000,
+        '?' => 0b110_001_010_000_010,
+        _ => 0,
+    }
+}
@@ -0,0 +1,312 @@
+//! Drawing backends for rendering mazes
+//! Each grid topology draws its geometry once using the primitives of the `Renderer` trait,
+//! so any backend can render any topology
+
+pub mod image;
+pub mod palette;
+pub mod svg;
+pub mod terminal;
+
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+
+pub use crate::util::Color;
+pub use image::*;
+pub use palette::*;
+pub use svg::*;
+pub use terminal::*;
+
+/// A point in image coordinates (x to the right, y down)
+pub type Point = (f64, f64);
+
+/// How mazes are drawn
+/// Sizes are in pixels (or SVG user units)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub(crate) cell_size: usize,
+    pub(crate) wall_thickness: usize,
+    pub(crate) inset: f64,
+    pub(crate) margin: usize,
+
+    pub(crate) wall_color: Color,
+    pub(crate) background_color: Color,
+    pub(crate) path_color: Color,
+
+    pub(crate) solution: bool,
+    pub(crate) solution_thickness: usize,
+    pub(crate) solution_color: Color,
+    pub(crate) start_color: Color,
+    pub(crate) goal_color: Color,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 25,
+            wall_thickness: 1,
+            inset: 0.0,
+            margin: 1,
+            wall_color: Color::new(0, 0, 0, 255),
+            background_color: Color::default(),
+            path_color: Color::WHITE,
+            solution: false,
+            solution_thickness: 3,
+            solution_color: Color::new(255, 128, 0, 255),
+            start_color: Color::new(32, 96, 224, 255),
+            goal_color: Color::new(224, 32, 32, 255),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Draws cells of the given size
+    /// Polar and hex cells are scaled to give roughly the same image size
+    pub fn with_cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+
+    /// Draws walls the given number of pixels thick
+    pub fn with_wall_thickness(mut self, wall_thickness: usize) -> Self {
+        self.wall_thickness = wall_thickness.max(1);
+        self
+    }
+
+    /// Insets each side of a cell by the given fraction of the cell size,
+    /// leaving passages narrower than the cells they run through
+    /// Only grids with square cells (orthogonal, weave, layered and cube) are drawn inset
+    pub fn with_inset(mut self, inset: f64) -> Self {
+        self.inset = inset.clamp(0.0, 0.4);
+        self
+    }
+
+    /// Leaves the given number of pixels around the outside walls
+    pub fn with_margin(mut self, margin: usize) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Draws walls in the given color
+    pub fn with_wall_color(mut self, wall_color: Color) -> Self {
+        self.wall_color = wall_color;
+        self
+    }
+
+    /// Fills everything that isn't a passage with the given color
+    /// (the margin, disabled cells and the space around inset passages)
+    pub fn with_background_color(mut self, background_color: Color) -> Self {
+        self.background_color = background_color;
+        self
+    }
+
+    /// Fills passages in the given color when they aren't colored by a solver
+    pub fn with_path_color(mut self, path_color: Color) -> Self {
+        self.path_color = path_color;
+        self
+    }
+
+    /// Draws the solved route over the maze as a line through the centers of its cells,
+    /// with markers at the start and goal
+    pub fn with_solution(mut self, solution: bool) -> Self {
+        self.solution = solution;
+        self
+    }
+
+    /// Draws the solved route the given number of pixels thick
+    pub fn with_solution_thickness(mut self, solution_thickness: usize) -> Self {
+        self.solution_thickness = solution_thickness.max(1);
+        self
+    }
+
+    /// Draws the solved route in the given color
+    pub fn with_solution_color(mut self, solution_color: Color) -> Self {
+        self.solution_color = solution_color;
+        self
+    }
+
+    /// Marks the start of the solved route in the given color
+    pub fn with_start_color(mut self, start_color: Color) -> Self {
+        self.start_color = start_color;
+        self
+    }
+
+    /// Marks the goal of the solved route in the given color
+    pub fn with_goal_color(mut self, goal_color: Color) -> Self {
+        self.goal_color = goal_color;
+        self
+    }
+
+    /// Returns the wall thickness as a stroke width
+    pub(crate) fn wall_width(&self) -> f64 {
+        self.wall_thickness as f64
+    }
+
+    /// Returns the solution thickness as a stroke width
+    pub(crate) fn solution_width(&self) -> f64 {
+        self.solution_thickness as f64
+    }
+
+    /// Returns the radius of the start and goal markers
+    pub(crate) fn marker_radius(&self) -> f64 {
+        (self.cell_size as f64 / 4.0).max(self.solution_width())
+    }
+
+    /// Returns the distance from the edge of the image to the middle of the outside walls
+    pub(crate) fn padding(&self) -> f64 {
+        self.margin as f64 + self.wall_width() / 2.0
+    }
+
+    /// Returns the size of an image with room for the padding around the given contents
+    pub(crate) fn padded_size(&self, (width, height): (f64, f64)) -> (usize, usize) {
+        let padding = 2.0 * self.padding();
+        (
+            (width + padding).ceil() as usize,
+            (height + padding).ceil() as usize,
+        )
+    }
+
+    /// Returns the distance cells of the given size are inset by
+    pub(crate) fn cell_inset(&self, cell_size: f64) -> f64 {
+        (cell_size * self.inset).floor()
+    }
+}
+
+/// Drawing primitives that mazes are rendered with
+/// Angles are in radians, clockwise from the positive x axis
+/// Lines and arcs are stroked with the given width and square ends
+pub trait Renderer {
+    /// Draws a straight line
+    fn line(&mut self, from: Point, to: Point, width: f64, color: Color);
+
+    /// Draws an arc around the center from the first angle to the second
+    fn arc(&mut self, center: Point, radius: f64, angles: (f64, f64), width: f64, color: Color);
+
+    /// Fills a convex polygon
+    fn fill_polygon(&mut self, points: &[Point], color: Color);
+
+    /// Draws text centered on the given point
+    fn text(&mut self, position: Point, text: &str, color: Color);
+
+    /// Fills the rectangle between two corners
+    fn fill_rect(&mut self, from: Point, to: Point, color: Color) {
+        self.fill_polygon(&[from, (to.0, from.1), to, (from.0, to.1)], color);
+    }
+
+    /// Fills the sector of a ring between two radii and two angles
+    /// By default the ring is split into convex pieces roughly a pixel wide
+    fn fill_sector(
+        &mut self,
+        center: Point,
+        (inner_radius, outer_radius): (f64, f64),
+        (from, to): (f64, f64),
+        color: Color,
+    ) {
+        let segments = ((outer_radius * (to - from).abs()).ceil() as usize).max(1);
+        let step = (to - from) / segments as f64;
+
+        for segment in 0..segments {
+            let (a, b) = (
+                from + step * segment as f64,
+                from + step * (segment + 1) as f64,
+            );
+            self.fill_polygon(
+                &[
+                    point(center, inner_radius, a),
+                    point(center, outer_radius, a),
+                    point(center, outer_radius, b),
+                    point(center, inner_radius, b),
+                ],
+                color,
+            );
+        }
+    }
+}
+
+/// Returns the point at the given radius and angle from the center
+pub fn point(center: Point, radius: f64, theta: f64) -> Point {
+    (
+        center.0 + radius * theta.cos(),
+        center.1 + radius * theta.sin(),
+    )
+}
+
+/// Returns the bounding box (min, max) of the sector of a ring
+pub(crate) fn sector_bounds(
+    center: Point,
+    radii: (f64, f64),
+    (from, to): (f64, f64),
+) -> (Point, Point) {
+    let (from, to) = (from.min(to), from.max(to));
+
+    let mut points = vec![
+        point(center, radii.0, from),
+        point(center, radii.0, to),
+        point(center, radii.1, from),
+        point(center, radii.1, to),
+    ];
+
+    // the outer edge bulges out wherever it crosses an axis
+    let mut quarter = (from / (PI / 2.0)).ceil();
+    while quarter * (PI / 2.0) < to {
+        points.push(point(center, radii.1, quarter * (PI / 2.0)));
+        quarter += 1.0;
+    }
+
+    let min = points.iter().fold((f64::MAX, f64::MAX), |min, p| {
+        (min.0.min(p.0), min.1.min(p.1))
+    });
+    let max = points.iter().fold((f64::MIN, f64::MIN), |max, p| {
+        (max.0.max(p.0), max.1.max(p.1))
+    });
+
+    (min, max)
+}
+
+/// Returns the path of the colored copy of a saved maze
+/// (maze.png is colored as maze-colored.png)
+pub(crate) fn colored_path(path: &Path) -> PathBuf {
+    // get the file name but with -colored appended
+    let mut color_filename = path.file_stem().unwrap().to_os_string();
+    color_filename.push("-colored");
+
+    // build the color file path
+    let mut color_path = path.to_owned();
+    color_path.set_file_name(&color_filename);
+    if let Some(extension) = path.extension() {
+        color_path.set_extension(extension);
+    }
+    color_path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_are_clamped() {
+        let options = RenderOptions::default()
+            .with_cell_size(0)
+            .with_wall_thickness(0)
+            .with_inset(-0.5);
+        assert_eq!(options.cell_size, 1);
+        assert_eq!(options.wall_thickness, 1);
+        assert_eq!(options.inset, 0.0);
+
+        let options = RenderOptions::default().with_inset(0.9);
+        assert_eq!(options.inset, 0.4);
+        assert_eq!(options.cell_inset(25.0), 10.0);
+
+        let options = RenderOptions::default().with_inset(0.25);
+        assert_eq!(options.inset, 0.25);
+        assert_eq!(options.cell_inset(10.0), 2.0);
+    }
+
+    #[test]
+    fn padding_leaves_room_for_the_walls() {
+        let options = RenderOptions::default()
+            .with_wall_thickness(4)
+            .with_margin(3);
+        assert_eq!(options.padding(), 5.0);
+        assert_eq!(options.padded_size((100.0, 50.5)), (110, 61));
+    }
+}
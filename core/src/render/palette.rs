@@ -0,0 +1,285 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::util::Color;
+
+/// The default heatmap hue
+const GREEN: Color = Color::new(0, 128, 0, 255);
+
+/// Stops of a perceptually even purple / blue / green / yellow gradient
+pub const VIRIDIS: [Color; 5] = [
+    Color::new(68, 1, 84, 255),
+    Color::new(59, 82, 139, 255),
+    Color::new(33, 145, 140, 255),
+    Color::new(94, 201, 98, 255),
+    Color::new(253, 231, 37, 255),
+];
+
+/// Stops of a perceptually even black / purple / orange / pale yellow gradient
+pub const MAGMA: [Color; 5] = [
+    Color::new(0, 0, 4, 255),
+    Color::new(81, 18, 124, 255),
+    Color::new(183, 55, 121, 255),
+    Color::new(252, 137, 97, 255),
+    Color::new(252, 253, 191, 255),
+];
+
+/// Stops of a gradient around the color wheel that ends where it starts,
+/// so it can be repeated without any seams
+pub const RAINBOW: [Color; 7] = [
+    Color::new(255, 64, 64, 255),
+    Color::new(255, 224, 64, 255),
+    Color::new(64, 255, 64, 255),
+    Color::new(64, 224, 255, 255),
+    Color::new(64, 64, 255, 255),
+    Color::new(224, 64, 255, 255),
+    Color::new(255, 64, 64, 255),
+];
+
+/// Colors for heatmaps of the distance from an origin cell
+#[derive(Debug, Clone, PartialEq)]
+pub enum Palette {
+    /// Fades from white at the origin to the given color at the farthest cells
+    Hue(Color),
+
+    /// Blends through evenly spaced color stops from the origin to the farthest cells
+    Gradient(Vec<Color>),
+
+    /// Steps another palette into the given number of solid bands
+    Banded(Box<Palette>, usize),
+
+    /// Repeats another palette every given number of cells out from the origin
+    Cyclic(Box<Palette>, usize),
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::Hue(GREEN)
+    }
+}
+
+impl Palette {
+    pub fn viridis() -> Self {
+        Self::Gradient(VIRIDIS.to_vec())
+    }
+
+    pub fn magma() -> Self {
+        Self::Gradient(MAGMA.to_vec())
+    }
+
+    pub fn rainbow() -> Self {
+        Self::Gradient(RAINBOW.to_vec())
+    }
+
+    /// Returns the color of a cell at the given distance from the origin
+    /// where the farthest cells are at the max distance
+    pub fn color(&self, distance: usize, max_distance: usize) -> Color {
+        let t = if max_distance > 0 {
+            distance.min(max_distance) as f64 / max_distance as f64
+        } else {
+            0.0
+        };
+
+        match self {
+            Self::Hue(color) => blend(Color::WHITE, *color, t),
+            Self::Gradient(stops) => match stops.len() {
+                0 => Color::WHITE,
+                1 => stops[0],
+                count => {
+                    let position = t * (count - 1) as f64;
+                    let index = (position.floor() as usize).min(count - 2);
+                    blend(stops[index], stops[index + 1], position - index as f64)
+                }
+            },
+            Self::Banded(palette, bands) => {
+                let bands = (*bands).max(1);
+                let band = (distance * bands / (max_distance + 1)).min(bands - 1);
+                palette.color(band, bands - 1)
+            }
+            Self::Cyclic(palette, period) => {
+                let period = (*period).max(1);
+                palette.color(distance % period, period)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hue(color) if *color == GREEN => write!(f, "green"),
+            Self::Hue(color) => write!(f, "hue:{}", color),
+            Self::Gradient(stops) if stops[..] == VIRIDIS => write!(f, "viridis"),
+            Self::Gradient(stops) if stops[..] == MAGMA => write!(f, "magma"),
+            Self::Gradient(stops) if stops[..] == RAINBOW => write!(f, "rainbow"),
+            Self::Gradient(stops) => write!(
+                f,
+                "gradient:{}",
+                stops
+                    .iter()
+                    .map(Color::to_string)
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            Self::Banded(palette, bands) => write!(f, "bands:{}:{}", bands, palette),
+            Self::Cyclic(palette, period) => write!(f, "cyclic:{}:{}", period, palette),
+        }
+    }
+}
+
+/// Parses the name of a palette, hue:color, gradient:color,color,...,
+/// bands:count[:palette] (viridis by default) or cyclic:period[:palette] (rainbow by default)
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // bands and cycles wrap the palette after their count
+        let wrapped = |s: &str, default: Palette| -> Result<(usize, Palette), String> {
+            let (count, palette) = match s.split_once(':') {
+                Some((count, palette)) => (count, palette.parse()?),
+                None => (s, default),
+            };
+
+            match count.parse() {
+                Ok(count) if count > 0 => Ok((count, palette)),
+                _ => Err(format!("Invalid palette count: {}", count)),
+            }
+        };
+
+        match s {
+            "green" => Ok(Self::default()),
+            "viridis" => Ok(Self::viridis()),
+            "magma" => Ok(Self::magma()),
+            "rainbow" => Ok(Self::rainbow()),
+            _ => match s.split_once(':') {
+                Some(("hue", color)) => Ok(Self::Hue(color.parse()?)),
+                Some(("gradient", stops)) => Ok(Self::Gradient(
+                    stops
+                        .split(',')
+                        .map(str::parse)
+                        .collect::<Result<Vec<Color>, String>>()?,
+                )),
+                Some(("bands", bands)) => {
+                    let (bands, palette) = wrapped(bands, Self::viridis())?;
+                    Ok(Self::Banded(Box::new(palette), bands))
+                }
+                Some(("cyclic", period)) => {
+                    let (period, palette) = wrapped(period, Self::rainbow())?;
+                    Ok(Self::Cyclic(Box::new(palette), period))
+                }
+                _ => Err(format!("Invalid palette: {}", s)),
+            },
+        }
+    }
+}
+
+/// Blends from one color to another
+fn blend(from: Color, to: Color, t: f64) -> Color {
+    let channel = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * t).round() as u8;
+
+    Color::new(
+        channel(from.r, to.r),
+        channel(from.g, to.g),
+        channel(from.b, to.b),
+        channel(from.a, to.a),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = Color::new(0, 0, 0, 255);
+
+    fn grey() -> Palette {
+        Palette::Gradient(vec![BLACK, Color::WHITE])
+    }
+
+    #[test]
+    fn hue_fades_from_white() {
+        let palette = Palette::default();
+        assert_eq!(palette.color(0, 10), Color::WHITE);
+        assert_eq!(palette.color(10, 10), GREEN);
+        assert_eq!(palette.color(5, 10), Color::new(128, 192, 128, 255));
+
+        // nothing is farther than the farthest cell
+        assert_eq!(palette.color(20, 10), GREEN);
+        assert_eq!(palette.color(0, 0), Color::WHITE);
+    }
+
+    #[test]
+    fn gradient_blends_between_stops() {
+        let palette = Palette::viridis();
+        assert_eq!(palette.color(0, 8), VIRIDIS[0]);
+        assert_eq!(palette.color(2, 8), VIRIDIS[1]);
+        assert_eq!(palette.color(6, 8), VIRIDIS[3]);
+        assert_eq!(palette.color(8, 8), VIRIDIS[4]);
+
+        assert_eq!(grey().color(1, 4), Color::new(64, 64, 64, 255));
+        assert_eq!(grey().color(3, 4), Color::new(191, 191, 191, 255));
+
+        assert_eq!(Palette::Gradient(vec![]).color(1, 2), Color::WHITE);
+        assert_eq!(Palette::Gradient(vec![BLACK]).color(1, 2), BLACK);
+    }
+
+    #[test]
+    fn bands_are_solid() {
+        let palette = Palette::Banded(Box::new(grey()), 3);
+
+        let colors = (0..=8)
+            .map(|distance| palette.color(distance, 8))
+            .collect::<Vec<Color>>();
+        let middle = Color::new(128, 128, 128, 255);
+        assert_eq!(
+            colors,
+            [[BLACK; 3].as_slice(), &[middle; 3], &[Color::WHITE; 3]].concat()
+        );
+    }
+
+    #[test]
+    fn cycles_repeat() {
+        let palette = Palette::Cyclic(Box::new(grey()), 4);
+        for distance in 0..4 {
+            assert_eq!(
+                palette.color(distance, 100),
+                palette.color(distance + 4, 100)
+            );
+            assert_eq!(palette.color(distance, 100), grey().color(distance, 4));
+        }
+
+        // rainbow cycles go back to where they started
+        let palette = Palette::Cyclic(Box::new(Palette::rainbow()), 6);
+        assert_eq!(palette.color(0, 100), RAINBOW[0]);
+        assert_eq!(palette.color(6, 100), RAINBOW[6]);
+    }
+
+    #[test]
+    fn palettes_round_trip() {
+        for s in [
+            "green",
+            "viridis",
+            "magma",
+            "rainbow",
+            "hue:#336699",
+            "gradient:#000000,#ffffff",
+            "bands:4:viridis",
+            "cyclic:12:bands:3:magma",
+        ] {
+            let palette = s.parse::<Palette>().unwrap();
+            assert_eq!(palette.to_string(), s);
+        }
+
+        assert_eq!(
+            "bands:5".parse(),
+            Ok(Palette::Banded(Box::new(Palette::viridis()), 5))
+        );
+        assert_eq!(
+            "cyclic:7".parse(),
+            Ok(Palette::Cyclic(Box::new(Palette::rainbow()), 7))
+        );
+
+        assert!("bands:0".parse::<Palette>().is_err());
+        assert!("gradient:#000000,nope".parse::<Palette>().is_err());
+        assert!("plaid".parse::<Palette>().is_err());
+    }
+}
@@ -0,0 +1,279 @@
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+use super::{point, Point, Renderer};
+use crate::util::Color;
+
+/// Renders to an SVG document
+/// Consecutive lines and arcs of the same color and width are collected into a single path
+#[derive(Debug, Clone)]
+pub struct SvgRenderer {
+    size: (usize, usize),
+    body: String,
+
+    // the path being collected and its stroke color and width
+    stroke: Option<(Color, f64, String)>,
+}
+
+impl SvgRenderer {
+    /// Creates an empty document of the given size
+    pub fn new(size: (usize, usize)) -> Self {
+        Self {
+            size,
+            body: String::new(),
+            stroke: None,
+        }
+    }
+
+    /// Returns the document size
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// Returns the finished document
+    pub fn finish(mut self) -> String {
+        self.end_stroke();
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            self.size.0, self.size.1
+        )
+        .unwrap();
+        svg.push_str(&self.body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Returns the path to continue with a line or arc of the given color and width
+    fn stroke(&mut self, color: Color, width: f64) -> &mut String {
+        if self
+            .stroke
+            .as_ref()
+            .is_some_and(|(stroke, stroke_width, _)| *stroke != color || *stroke_width != width)
+        {
+            self.end_stroke();
+        }
+
+        &mut self
+            .stroke
+            .get_or_insert_with(|| (color, width, String::new()))
+            .2
+    }
+
+    /// Writes out the path being collected
+    fn end_stroke(&mut self) {
+        if let Some((color, width, path)) = self.stroke.take() {
+            writeln!(
+                self.body,
+                r#"<path d="{}" fill="none" stroke="{}"{} stroke-width="{}" stroke-linecap="square"/>"#,
+                path,
+                hex(color),
+                opacity("stroke", color),
+                round(width)
+            )
+            .unwrap();
+        }
+    }
+
+    /// Writes out a filled element, which goes over anything collected so far
+    fn fill(&mut self, element: std::fmt::Arguments, color: Color) {
+        self.end_stroke();
+
+        writeln!(
+            self.body,
+            r#"<{} fill="{}"{}/>"#,
+            element,
+            hex(color),
+            opacity("fill", color)
+        )
+        .unwrap();
+    }
+}
+
+impl Renderer for SvgRenderer {
+    fn line(&mut self, from: Point, to: Point, width: f64, color: Color) {
+        write!(
+            self.stroke(color, width),
+            "M{} {}L{} {}",
+            round(from.0),
+            round(from.1),
+            round(to.0),
+            round(to.1)
+        )
+        .unwrap();
+    }
+
+    fn arc(&mut self, center: Point, radius: f64, angles: (f64, f64), width: f64, color: Color) {
+        let start = point(center, radius, angles.0);
+        let path = self.stroke(color, width);
+        write!(path, "M{} {}", round(start.0), round(start.1)).unwrap();
+        arc_to(path, center, radius, angles);
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        let points = points
+            .iter()
+            .map(|p| format!("{},{}", round(p.0), round(p.1)))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        self.fill(format_args!(r#"polygon points="{}""#, points), color);
+    }
+
+    fn text(&mut self, position: Point, text: &str, color: Color) {
+        self.end_stroke();
+
+        let text = text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+
+        writeln!(
+            self.body,
+            r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="central" fill="{}"{}>{}</text>"#,
+            round(position.0),
+            round(position.1),
+            hex(color),
+            opacity("fill", color),
+            text
+        )
+        .unwrap();
+    }
+
+    fn fill_rect(&mut self, from: Point, to: Point, color: Color) {
+        self.fill(
+            format_args!(
+                r#"rect x="{}" y="{}" width="{}" height="{}""#,
+                round(from.0.min(to.0)),
+                round(from.1.min(to.1)),
+                round((to.0 - from.0).abs()),
+                round((to.1 - from.1).abs())
+            ),
+            color,
+        );
+    }
+
+    /// Fills the sector with real arcs
+    /// A sector with no inner radius is a pie slice, or a circle if it goes all the way around
+    fn fill_sector(
+        &mut self,
+        center: Point,
+        (inner_radius, outer_radius): (f64, f64),
+        (from, to): (f64, f64),
+        color: Color,
+    ) {
+        let mut path = String::new();
+        let start = point(center, outer_radius, from);
+        write!(path, "M{} {}", round(start.0), round(start.1)).unwrap();
+        arc_to(&mut path, center, outer_radius, (from, to));
+
+        if inner_radius > 0.0 {
+            let end = point(center, inner_radius, to);
+            write!(path, "L{} {}", round(end.0), round(end.1)).unwrap();
+            arc_to(&mut path, center, inner_radius, (to, from));
+        } else {
+            write!(path, "L{} {}", round(center.0), round(center.1)).unwrap();
+        }
+
+        self.fill(format_args!(r#"path d="{}Z""#, path), color);
+    }
+}
+
+/// Continues a path along an arc
+/// Arcs more than half way around are split in two
+/// since a single SVG arc can't tell which way to go around
+fn arc_to(path: &mut String, center: Point, radius: f64, (from, to): (f64, f64)) {
+    if (to - from).abs() > PI {
+        let middle = (from + to) / 2.0;
+        arc_to(path, center, radius, (from, middle));
+        arc_to(path, center, radius, (middle, to));
+        return;
+    }
+
+    let end = point(center, radius, to);
+    let sweep = if to > from { 1 } else { 0 };
+    write!(
+        path,
+        "A{0} {0} 0 0 {1} {2} {3}",
+        round(radius),
+        sweep,
+        round(end.0),
+        round(end.1)
+    )
+    .unwrap();
+}
+
+/// Rounds a coordinate to keep the document small
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// Returns the opacity attribute for translucent colors
+fn opacity(attribute: &str, color: Color) -> String {
+    if color.a == 255 {
+        String::new()
+    } else {
+        format!(
+            r#" {}-opacity="{}""#,
+            attribute,
+            round(color.a as f64 / 255.0)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::generators::{Generator, RecursiveBacktracker};
+    use crate::render::RenderOptions;
+    use crate::solvers::{Djikstra, Solver};
+    use crate::util::seeded_rng;
+    use crate::{GridType, Renderable};
+
+    fn solved(grid_type: GridType) -> Djikstra {
+        let grid = RecursiveBacktracker.generate(8, 8, grid_type, &mut seeded_rng(1));
+        let (root, goal) = grid.longest_path();
+        let solver = Djikstra::new(grid, root.0, root.1);
+        solver.solve(goal.0, goal.1);
+        solver
+    }
+
+    #[test]
+    fn polar_walls_are_arcs() {
+        let svg = solved(GridType::Polar).render_svg(&RenderOptions::default(), false);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.lines().any(|line| line.starts_with(r#"<path d="M"#)
+            && line.contains('A')
+            && line.contains(r#"fill="none""#)));
+    }
+
+    #[test]
+    fn solver_colors_are_filled() {
+        let solver = solved(GridType::Orthogonal);
+
+        let plain = solver.render_svg(&RenderOptions::default(), false);
+        let colored = solver.render_svg(&RenderOptions::default(), true);
+
+        // every cell is filled, in the path color or with its distance color
+        let fills = |svg: &str| {
+            svg.lines()
+                .filter(|line| line.starts_with("<rect "))
+                .map(|line| line.split("fill=").nth(1).unwrap().to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(fills(&plain).len(), 8 * 8);
+        assert_eq!(fills(&colored).len(), 8 * 8);
+        assert!(fills(&plain)
+            .iter()
+            .all(|fill| fill.starts_with(r##""#ffffff""##)));
+        assert!(fills(&colored).iter().collect::<HashSet<_>>().len() > 8);
+    }
+}
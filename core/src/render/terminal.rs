@@ -0,0 +1,188 @@
+use super::{point, Point, Renderer};
+use crate::util::Color;
+
+/// Renders to lines of text for printing in a terminal
+/// Each character covers a block of the image, and lines are drawn
+/// with `-`, `|`, `/` and `\` depending on their slope, joining with `+`
+/// Fills are blank, or `#` if they're dark, and line widths are ignored
+#[derive(Debug, Clone)]
+pub struct TerminalRenderer {
+    // image pixels per character
+    scale: (f64, f64),
+
+    lines: Vec<Vec<char>>,
+}
+
+impl TerminalRenderer {
+    /// Creates a blank canvas covering an image of the given size
+    /// with each character covering the given number of pixels
+    pub fn new(image_size: (usize, usize), scale: (f64, f64)) -> Self {
+        let cols = (image_size.0 as f64 / scale.0).ceil() as usize + 1;
+        let rows = (image_size.1 as f64 / scale.1).ceil() as usize + 1;
+
+        Self {
+            scale,
+            lines: vec![vec![' '; cols]; rows],
+        }
+    }
+
+    /// Returns the rendered text, without any trailing whitespace
+    pub fn finish(self) -> String {
+        let mut output = self
+            .lines
+            .iter()
+            .map(|line| line.iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<String>>();
+
+        while output.last().is_some_and(String::is_empty) {
+            output.pop();
+        }
+
+        let mut output = output.join("\n");
+        output.push('\n');
+        output
+    }
+
+    /// Returns the character position of an image point
+    fn position(&self, p: Point) -> (isize, isize) {
+        (
+            (p.0 / self.scale.0).round() as isize,
+            (p.1 / self.scale.1).round() as isize,
+        )
+    }
+
+    /// Returns the character at the given position, if it's on the canvas
+    fn get_mut(&mut self, (x, y): (isize, isize)) -> Option<&mut char> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        self.lines
+            .get_mut(y as usize)
+            .and_then(|line| line.get_mut(x as usize))
+    }
+
+    /// Draws part of a line
+    fn put(&mut self, position: (isize, isize), c: char) {
+        if let Some(existing) = self.get_mut(position) {
+            // crossing lines join
+            *existing = if is_line(*existing) && is_line(c) && *existing != c {
+                '+'
+            } else {
+                c
+            };
+        }
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn line(&mut self, from: Point, to: Point, _width: f64, _color: Color) {
+        // the slope is measured in characters since they aren't square
+        let dx = (to.0 - from.0) / self.scale.0;
+        let dy = (to.1 - from.1) / self.scale.1;
+        let c = if dy.abs() < dx.abs() / 2.0 {
+            '-'
+        } else if dx.abs() < dy.abs() / 2.0 {
+            '|'
+        } else if (dx > 0.0) == (dy > 0.0) {
+            '\\'
+        } else {
+            '/'
+        };
+
+        // https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm
+        let (mut x1, mut y1) = self.position(from);
+        let (x2, y2) = self.position(to);
+
+        let dx = (x2 - x1).abs();
+        let sx = if x1 < x2 { 1 } else { -1 };
+        let dy = -((y2 - y1).abs());
+        let sy = if y1 < y2 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.put((x1, y1), c);
+
+            if x1 == x2 && y1 == y2 {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x1 += sx;
+            }
+
+            if e2 <= dx {
+                err += dx;
+                y1 += sy;
+            }
+        }
+    }
+
+    fn arc(&mut self, center: Point, radius: f64, angles: (f64, f64), width: f64, color: Color) {
+        // approximate the arc with segments roughly a character long
+        let length = radius * (angles.1 - angles.0).abs() / self.scale.0.min(self.scale.1);
+        let segments = (length.ceil() as usize).max(1);
+        let step = (angles.1 - angles.0) / segments as f64;
+
+        for segment in 0..segments {
+            let from = point(center, radius, angles.0 + step * segment as f64);
+            let to = point(center, radius, angles.0 + step * (segment + 1) as f64);
+            self.line(from, to, width, color);
+        }
+    }
+
+    /// Fills the characters whose center is inside the polygon
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        let c = if is_dark(color) { '#' } else { ' ' };
+
+        let (min_x, min_y) = self.position(points.iter().fold((f64::MAX, f64::MAX), |min, p| {
+            (min.0.min(p.0), min.1.min(p.1))
+        }));
+        let (max_x, max_y) = self.position(points.iter().fold((f64::MIN, f64::MIN), |max, p| {
+            (max.0.max(p.0), max.1.max(p.1))
+        }));
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let (px, py) = (x as f64 * self.scale.0, y as f64 * self.scale.1);
+
+                // the character is inside if it's on the same side of every edge
+                let mut positive = false;
+                let mut negative = false;
+                for (index, a) in points.iter().enumerate() {
+                    let b = points[(index + 1) % points.len()];
+
+                    let cross = (b.0 - a.0) * (py - a.1) - (b.1 - a.1) * (px - a.0);
+                    positive |= cross > 0.0;
+                    negative |= cross < 0.0;
+                }
+
+                if !(positive && negative) {
+                    self.put((x, y), c);
+                }
+            }
+        }
+    }
+
+    fn text(&mut self, position: Point, text: &str, _color: Color) {
+        let (x, y) = self.position(position);
+        let left = x - (text.chars().count() as isize) / 2;
+
+        for (index, c) in text.chars().enumerate() {
+            if let Some(existing) = self.get_mut((left + index as isize, y)) {
+                *existing = c;
+            }
+        }
+    }
+}
+
+fn is_line(c: char) -> bool {
+    matches!(c, '-' | '|' | '/' | '\\' | '+')
+}
+
+fn is_dark(color: Color) -> bool {
+    let luminance = 0.299 * color.r as f64 + 0.587 * color.g as f64 + 0.114 * color.b as f64;
+    color.a >= 128 && luminance < 128.0
+}
@@ -1,12 +1,59 @@
 use std::cell::RefCell;
-use std::io;
-use std::path::Path;
+use std::fmt;
+use std::str::FromStr;
 
+use crate::render::{Palette, RenderOptions, Renderer};
 use crate::util::Color;
 use crate::{CellHandle, Distances, Grid, Renderable};
 
 use super::Solver;
 
+/// The cell that heatmap distances are measured from
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum DistanceOrigin {
+    /// The center of the grid
+    #[default]
+    Center,
+
+    /// The cell the solver starts from
+    Start,
+
+    /// The goal the maze is solved to
+    Goal,
+
+    /// The cell at the given row and column,
+    /// or the start if there isn't an enabled cell there
+    Cell(usize, usize),
+}
+
+impl fmt::Display for DistanceOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Center => write!(f, "center"),
+            Self::Start => write!(f, "start"),
+            Self::Goal => write!(f, "goal"),
+            Self::Cell(row, col) => write!(f, "{},{}", row, col),
+        }
+    }
+}
+
+/// Parses center, start, goal or row,col
+impl FromStr for DistanceOrigin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "center" => Ok(Self::Center),
+            "start" => Ok(Self::Start),
+            "goal" => Ok(Self::Goal),
+            _ => s
+                .split_once(',')
+                .and_then(|(row, col)| Some(Self::Cell(row.parse().ok()?, col.parse().ok()?)))
+                .ok_or_else(|| format!("Invalid distance origin: {}", s)),
+        }
+    }
+}
+
 /// Simple Djikstra's algorithm solver
 #[derive(Debug)]
 pub struct Djikstra {
@@ -14,8 +61,10 @@ pub struct Djikstra {
     root: CellHandle,
 
     // used for cell background coloring
-    distances_from_center: RefCell<Option<Distances>>,
-    max_distance_from_center: RefCell<usize>,
+    palette: Palette,
+    origin: DistanceOrigin,
+    distances_from_origin: RefCell<Option<Distances>>,
+    max_distance_from_origin: RefCell<usize>,
 
     // solved path through the maze
     path: RefCell<Option<Distances>>,
@@ -27,12 +76,26 @@ impl Djikstra {
         Self {
             grid,
             root: CellHandle::new(root_row, root_column),
-            distances_from_center: RefCell::new(None),
-            max_distance_from_center: RefCell::new(0),
+            palette: Palette::default(),
+            origin: DistanceOrigin::default(),
+            distances_from_origin: RefCell::new(None),
+            max_distance_from_origin: RefCell::new(0),
             path: RefCell::new(None),
         }
     }
 
+    /// Colors cell backgrounds with the given palette
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Colors cell backgrounds by their distance from the given origin
+    pub fn with_origin(mut self, origin: DistanceOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
     fn cell_contents_from_distances(&self, distances: &Distances, cell: CellHandle) -> String {
         let (digits, empty) = self.grid.empty_cell_contents();
 
@@ -56,7 +119,7 @@ impl Djikstra {
         breadcrumbs.set_distance(current, current_distance);
 
         while current != self.root {
-            let cell = self.grid.get(current.row, current.col).unwrap();
+            let cell = self.grid.get_cell(current).unwrap();
 
             for neighbor in cell.links() {
                 let neighbor_distance = distances.get_distance(neighbor).unwrap_or_default();
@@ -93,42 +156,54 @@ impl Solver for Djikstra {
         }
     }
 
+    fn path(&self) -> Vec<CellHandle> {
+        self.path
+            .borrow()
+            .as_ref()
+            .map(Distances::cells_by_distance)
+            .unwrap_or_default()
+    }
+
     fn cell_background(&self, row: usize, col: usize) -> Color {
         let cell = CellHandle::new(row, col);
 
         let distance = self
-            .distances_from_center
+            .distances_from_origin
             .borrow()
             .as_ref()
             .unwrap()
             .get_distance(&cell)
             .unwrap_or_default();
-        let max_distance = *self.max_distance_from_center.borrow();
-
-        let intensity = (max_distance - distance) as f32 / max_distance as f32;
-        let dark = (255.0 * intensity).round() as u8;
-        let bright = 128 + (127.0 * intensity).round() as u8;
+        let max_distance = *self.max_distance_from_origin.borrow();
 
-        Color::new(dark, bright, dark, 255)
+        self.palette.color(distance, max_distance)
     }
 
     fn solve(&self, goal_row: usize, goal_col: usize) {
         // compute the shortest path
+        let goal = CellHandle::new(goal_row, goal_col);
         let distances = crate::distances(&self.grid, self.root);
-        *self.path.borrow_mut() =
-            Some(self.path_to(CellHandle::new(goal_row, goal_col), &distances));
+        *self.path.borrow_mut() = Some(self.path_to(goal, &distances));
 
-        // compute distances from the center
+        // compute distances from the origin
         // for cell background coloring
-        let distances = crate::distances(
-            &self.grid,
-            CellHandle::new(self.grid.rows() / 2, self.grid.columns() / 2),
-        );
+        let origin = match self.origin {
+            DistanceOrigin::Center => self.grid.center(),
+            DistanceOrigin::Start => self.root,
+            DistanceOrigin::Goal => goal,
+            DistanceOrigin::Cell(row, col) => self
+                .grid
+                .get(row, col)
+                .filter(|cell| !cell.is_orphaned())
+                .map(|cell| cell.handle())
+                .unwrap_or(self.root),
+        };
+        let distances = crate::distances(&self.grid, origin);
 
         let (_, max_distance) = distances.max_distance();
-        *self.max_distance_from_center.borrow_mut() = max_distance;
+        *self.max_distance_from_origin.borrow_mut() = max_distance;
 
-        *self.distances_from_center.borrow_mut() = Some(distances);
+        *self.distances_from_origin.borrow_mut() = Some(distances);
     }
 }
 
@@ -137,11 +212,120 @@ impl Renderable for Djikstra {
         self.grid.render_ascii_solver(Some(self))
     }
 
-    fn render(&self, cell_size: usize, color: bool) -> ((usize, usize), Vec<u8>) {
-        self.grid.render_solver(cell_size, Some(self), color)
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        self.grid.image_size(options)
     }
 
-    fn save_png(&self, path: &Path, cell_size: usize) -> io::Result<()> {
-        self.grid.save_png_solver(path, cell_size, Some(self))
+    fn draw(&self, renderer: &mut dyn Renderer, options: &RenderOptions, color: bool) {
+        self.grid.draw_solver(renderer, options, Some(self), color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{Caves, Generator, Kruskals, RecursiveBacktracker};
+    use crate::util::seeded_rng;
+    use crate::{CullLimit, GridType};
+
+    fn solve(grid: Grid, origin: DistanceOrigin) -> Djikstra {
+        let (root, goal) = grid.longest_path();
+        let solver = Djikstra::new(grid, root.0, root.1).with_origin(origin);
+        solver.solve(goal.0, goal.1);
+
+        let path = solver.path();
+        assert_eq!(path.first(), Some(&CellHandle::new(root.0, root.1)));
+        assert_eq!(path.last(), Some(&CellHandle::new(goal.0, goal.1)));
+        solver
+    }
+
+    #[test]
+    fn solves_culled_weave_mazes() {
+        for seed in [1, 2, 3] {
+            for fraction in [0.1, 0.5] {
+                let mut rng = seeded_rng(seed);
+                let mut grid = Kruskals::default().with_random_crossings(30).generate(
+                    12,
+                    12,
+                    GridType::Weave,
+                    &mut rng,
+                );
+                let crossings = grid
+                    .iter()
+                    .map(|cell| CellHandle::new_under(cell.row(), cell.col()))
+                    .filter(|&under| grid.get_cell(under).is_some())
+                    .collect::<Vec<CellHandle>>();
+                assert!(!crossings.is_empty());
+
+                grid.cull(CullLimit::Remaining(fraction), &mut rng);
+
+                // the passages over and under each crossing are left in place
+                for &under in &crossings {
+                    let over = CellHandle::new(under.row, under.col);
+                    for handle in [over, under] {
+                        let links = grid.get_cell(handle).map_or(0, |cell| cell.links().len());
+                        assert_eq!(links, 2, "culled {:?} (seed={})", handle, seed);
+                    }
+                }
+
+                let solver = solve(grid, DistanceOrigin::Center);
+                let center = solver.grid.center();
+                let distances = solver.distances_from_origin.borrow();
+                let distances = distances.as_ref().unwrap();
+                assert_eq!(distances.get_distance(&center), Some(0));
+                assert_eq!(
+                    distances.cells_by_distance().len(),
+                    solver.grid.enabled_count() + crossings.len(),
+                    "culling split the maze (seed={})",
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn solves_caves() {
+        for seed in 1..=10 {
+            let grid =
+                Caves::default().generate(20, 20, GridType::Orthogonal, &mut seeded_rng(seed));
+            let enabled = grid.enabled_count();
+
+            let solver = solve(grid, DistanceOrigin::Center);
+            let distances = solver.distances_from_origin.borrow();
+            assert_eq!(
+                distances.as_ref().unwrap().cells_by_distance().len(),
+                enabled,
+                "not every cave floor was reached (seed={})",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn disabled_origins_fall_back_to_the_start() {
+        let mut rng = seeded_rng(1);
+        let mut grid = RecursiveBacktracker.generate(12, 12, GridType::Orthogonal, &mut rng);
+        grid.cull(CullLimit::Remaining(0.1), &mut rng);
+
+        let culled = grid
+            .rows_iter()
+            .enumerate()
+            .find_map(|(row, cells)| cells.iter().position(Option::is_none).map(|col| (row, col)))
+            .unwrap();
+        let origins = [
+            DistanceOrigin::Cell(culled.0, culled.1),
+            DistanceOrigin::Cell(100, 100),
+        ];
+
+        for origin in origins {
+            let solver = solve(grid.clone(), origin);
+            let distances = solver.distances_from_origin.borrow();
+            assert_eq!(
+                distances.as_ref().unwrap().get_distance(&solver.root),
+                Some(0),
+                "{} wasn't measured from the start",
+                origin
+            );
+        }
     }
 }
@@ -1,10 +1,8 @@
 pub mod djikstra;
 
-use std::io;
-use std::path::Path;
-
+use crate::render::{RenderOptions, Renderer};
 use crate::util::Color;
-use crate::{Grid, Renderable};
+use crate::{CellHandle, Grid, Renderable};
 
 pub use djikstra::*;
 
@@ -26,6 +24,12 @@ pub trait Solver: Renderable {
         Color::WHITE
     }
 
+    /// Returns the cells of the solved route from the start to the goal,
+    /// or nothing if the maze hasn't been solved
+    fn path(&self) -> Vec<CellHandle> {
+        Vec::new()
+    }
+
     /// Solves the maze
     fn solve(&self, goal_row: usize, goal_col: usize);
 }
@@ -60,11 +64,11 @@ impl Renderable for NoneSolver {
         self.grid.render_ascii()
     }
 
-    fn render(&self, cell_size: usize, color: bool) -> ((usize, usize), Vec<u8>) {
-        self.grid.render(cell_size, color)
+    fn image_size(&self, options: &RenderOptions) -> (usize, usize) {
+        self.grid.image_size(options)
     }
 
-    fn save_png(&self, path: &Path, cell_size: usize) -> io::Result<()> {
-        self.grid.save_png(path, cell_size)
+    fn draw(&self, renderer: &mut dyn Renderer, options: &RenderOptions, color: bool) {
+        self.grid.draw(renderer, options, color)
     }
 }
@@ -0,0 +1,244 @@
+//! Row-by-row output for orthogonal mazes too big to keep in memory
+//! The output matches rendering the same maze from an orthogonal grid
+
+use std::collections::VecDeque;
+use std::io::{self, Write};
+
+use crate::grid::{draw_square_cell, empty_cell_contents, fill_square_cell, Side};
+use crate::render::{ImageRenderer, RenderOptions, Renderer};
+
+/// A single row of an orthogonal maze
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MazeRow {
+    /// Whether each cell is linked to its east neighbor
+    pub east: Vec<bool>,
+
+    /// Whether each cell is linked to its south neighbor
+    pub south: Vec<bool>,
+}
+
+/// Writes the maze rows as ASCII
+pub fn write_ascii(
+    rows: usize,
+    cols: usize,
+    maze: impl Iterator<Item = MazeRow>,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let (digits, empty) = empty_cell_contents(rows * cols);
+    let wall = format!("-{}-", str::repeat("-", digits));
+    let open = format!(" {} ", str::repeat(" ", digits));
+
+    writeln!(writer, "+{}", format!("{}+", wall).repeat(cols))?;
+
+    for row in maze {
+        let mut top = String::from("|");
+        let mut bottom = String::from("+");
+
+        for col in 0..cols {
+            top.push_str(&format!(" {} ", empty));
+            top.push_str(if row.east[col] { " " } else { "|" });
+
+            bottom.push_str(if row.south[col] { &open } else { &wall });
+            bottom.push('+');
+        }
+
+        writeln!(writer, "{}", top)?;
+        writeln!(writer, "{}", bottom)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the north, south, west and east sides of each cell in a row
+/// given the south links of the row before it
+fn row_sides(row: &MazeRow, north: Option<&[bool]>) -> Vec<[Side; 4]> {
+    let side = |linked: bool, neighbor_draws: bool| {
+        if linked {
+            Side::Open
+        } else if neighbor_draws {
+            Side::Shared
+        } else {
+            Side::Wall
+        }
+    };
+
+    (0..row.east.len())
+        .map(|col| {
+            [
+                match north {
+                    Some(north) => side(north[col], true),
+                    None => Side::Wall,
+                },
+                side(row.south[col], false),
+                match col {
+                    0 => Side::Wall,
+                    _ => side(row.east[col - 1], true),
+                },
+                side(row.east[col], false),
+            ]
+        })
+        .collect()
+}
+
+/// Draws the image rows of a maze row the same way as an orthogonal grid
+/// Walls and passages can spill over into the rows next to them,
+/// so those are drawn along with it (all of the backgrounds before any of the walls)
+fn draw_strip(
+    image_size: (usize, usize),
+    (top, bottom): (usize, usize),
+    rows: &VecDeque<(usize, Vec<[Side; 4]>)>,
+    options: &RenderOptions,
+) -> Vec<u8> {
+    let mut strip = ImageRenderer::new((image_size.0, bottom - top));
+
+    if options.background_color.a > 0 {
+        strip.fill_rect(
+            (0.0, 0.0),
+            (image_size.0 as f64, (bottom - top) as f64),
+            options.background_color,
+        );
+    }
+
+    let size = options.cell_size as f64;
+    let padding = options.padding();
+    let inset = options.cell_inset(size);
+    let corners = |row: usize, col: usize| {
+        let x = padding + col as f64 * size;
+        let y = padding + row as f64 * size - top as f64;
+        ((x, y), (x + size, y + size))
+    };
+
+    for (row, sides) in rows {
+        for (col, sides) in sides.iter().enumerate() {
+            let (from, to) = corners(*row, col);
+            fill_square_cell(&mut strip, from, to, *sides, inset, options.path_color);
+        }
+    }
+
+    for (row, sides) in rows {
+        for (col, sides) in sides.iter().enumerate() {
+            let (from, to) = corners(*row, col);
+            draw_square_cell(&mut strip, from, to, *sides, inset, options);
+        }
+    }
+
+    strip.finish().1
+}
+
+/// Writes the maze rows as a PNG drawn with the given options
+pub fn write_png(
+    rows: usize,
+    cols: usize,
+    options: &RenderOptions,
+    maze: impl Iterator<Item = MazeRow>,
+    writer: impl Write,
+) -> io::Result<()> {
+    let image_size = options.padded_size((
+        (cols * options.cell_size) as f64,
+        (rows * options.cell_size) as f64,
+    ));
+
+    let mut encoder = png::Encoder::new(writer, image_size.0 as u32, image_size.1 as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut header = encoder.write_header()?;
+    let mut writer = header.stream_writer()?;
+
+    // each row's strip of the image starts at its north wall
+    // except for the first and last, which cover the margins
+    let strip = |index: usize| {
+        let top = |index: usize| match index {
+            0 => 0,
+            _ if index >= rows => image_size.1,
+            _ => (options.padding() + (index * options.cell_size) as f64).floor() as usize,
+        };
+        (top(index), top(index + 1))
+    };
+
+    // each row is drawn once the row after it is known
+    let mut window = VecDeque::with_capacity(3);
+    let mut north: Option<Vec<bool>> = None;
+    for (index, row) in maze.enumerate() {
+        window.push_back((index, row_sides(&row, north.as_deref())));
+        north = Some(row.south);
+
+        if index > 0 {
+            writer.write_all(&draw_strip(image_size, strip(index - 1), &window, options))?;
+        }
+
+        if window.len() == 3 {
+            window.pop_front();
+        }
+    }
+
+    writer.write_all(&draw_strip(
+        image_size,
+        strip(rows.saturating_sub(1)),
+        &window,
+        options,
+    ))?;
+
+    writer.finish()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{Ellers, EllersRows, Generator};
+    use crate::util::{seeded_rng, Color};
+    use crate::{GridType, Renderable};
+
+    fn decode_png(data: &[u8]) -> ((usize, usize), Vec<u8>) {
+        let mut reader = png::Decoder::new(data).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        pixels.truncate(info.buffer_size());
+
+        ((info.width as usize, info.height as usize), pixels)
+    }
+
+    #[test]
+    fn streamed_png_matches_grid_render() {
+        let options = [
+            RenderOptions::default(),
+            RenderOptions::default()
+                .with_cell_size(10)
+                .with_wall_thickness(3)
+                .with_margin(4),
+            RenderOptions::default()
+                .with_cell_size(16)
+                .with_inset(0.25)
+                .with_background_color(Color::new(40, 40, 40, 255))
+                .with_wall_color(Color::new(200, 0, 0, 255)),
+        ];
+
+        for (rows, cols) in [(5, 7), (1, 6), (9, 1), (20, 20)] {
+            for seed in [1, 2] {
+                let grid = Ellers.generate(rows, cols, GridType::Orthogonal, &mut seeded_rng(seed));
+
+                for options in &options {
+                    let mut png = Vec::new();
+                    write_png(
+                        rows,
+                        cols,
+                        options,
+                        EllersRows::new(rows, cols, &mut seeded_rng(seed)),
+                        &mut png,
+                    )
+                    .unwrap();
+
+                    assert!(
+                        decode_png(&png) == grid.render(options, false),
+                        "streamed {}x{} maze (seed={}) doesn't match with {:?}",
+                        rows,
+                        cols,
+                        seed,
+                        options
+                    );
+                }
+            }
+        }
+    }
+}
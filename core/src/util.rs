@@ -1,8 +1,11 @@
+use std::fmt;
 use std::fs;
 use std::io::{self, BufRead};
 use std::path::Path;
+use std::str::FromStr;
 
-use rand::Rng;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 /// Reads a file, removing empty lines
 pub fn read_file_lines(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
@@ -19,7 +22,7 @@ pub fn read_file_lines(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
     Ok(lines)
 }
 
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -35,6 +38,41 @@ impl Color {
     }
 }
 
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses #rrggbb or #rrggbbaa hex colors, or transparent
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("transparent") {
+            return Ok(Self::default());
+        }
+
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid color: {}", s));
+        }
+
+        let channel =
+            |index: usize| u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).unwrap();
+        Ok(Self::new(
+            channel(0),
+            channel(1),
+            channel(2),
+            if hex.len() == 8 { channel(3) } else { 255 },
+        ))
+    }
+}
+
 /// Renders a quad in the given data
 pub fn quad(
     mut data: impl AsMut<[u8]>,
@@ -52,92 +90,83 @@ pub fn quad(
     }
 }
 
-/// Renders a circle in the given data
-// https://stackoverflow.com/questions/38334081/howto-draw-circles-arcs-and-vector-graphics-in-sdl
-// https://en.wikipedia.org/wiki/Midpoint_circle_algorithm
-// https://web.archive.org/web/20120422045142/https://banu.com/blog/7/drawing-circles/
-pub fn circle(
+/// Renders a filled convex polygon in the given data
+pub fn polygon(
     mut data: impl AsMut<[u8]>,
     image_size: (usize, usize),
-    center: (usize, usize),
-    radius: usize,
+    points: &[(f64, f64)],
     color: Color,
 ) {
-    let radius = radius as isize;
-    let center = (center.0 as isize, center.1 as isize);
-
-    let d = radius * 2;
-
-    let mut x = radius - 1;
-    let mut y = 0;
-    let mut tx = 1;
-    let mut ty = 1;
-    let mut error = tx - d;
-
-    while x >= y {
-        let x1 = center.0 + x;
-        let x2 = center.0 - x;
-        let y1 = center.1 + y;
-        let y2 = center.1 - y;
-
-        // skip pixels that are out of bounds
-        if x1 < 0
-            || x1 >= image_size.0 as isize
-            || x2 < 0
-            || x2 >= image_size.0 as isize
-            || y1 < 0
-            || y1 >= image_size.1 as isize
-            || y2 < 0
-            || y2 >= image_size.1 as isize
-        {
-            continue;
-        }
-
-        let x1 = x1 as usize;
-        let x2 = x2 as usize;
-        let y1 = y1 as usize;
-        let y2 = y2 as usize;
-
-        // each of the following renders an octant of the circle
-        plot(data.as_mut(), image_size.0, x1, y1, color);
-        plot(data.as_mut(), image_size.0, x1, y2, color);
-        plot(data.as_mut(), image_size.0, x2, y1, color);
-        plot(data.as_mut(), image_size.0, x2, y2, color);
-        plot(data.as_mut(), image_size.0, y1, x1, color);
-        plot(data.as_mut(), image_size.0, y1, x2, color);
-        plot(data.as_mut(), image_size.0, y2, x1, color);
-        plot(data.as_mut(), image_size.0, y2, x2, color);
-
-        if error <= 0 {
-            y += 1;
-            error += ty;
-            ty += 2;
+    // bounding box of the polygon, clamped to the image
+    let min_x = points.iter().map(|p| p.0).fold(f64::MAX, f64::min).max(0.0) as usize;
+    let min_y = points.iter().map(|p| p.1).fold(f64::MAX, f64::min).max(0.0) as usize;
+    let max_x =
+        (points.iter().map(|p| p.0).fold(f64::MIN, f64::max) as usize).min(image_size.0 - 1);
+    let max_y =
+        (points.iter().map(|p| p.1).fold(f64::MIN, f64::max) as usize).min(image_size.1 - 1);
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+
+            // the pixel is inside if it's on the same side of every edge
+            let mut positive = false;
+            let mut negative = false;
+            for (index, a) in points.iter().enumerate() {
+                let b = points[(index + 1) % points.len()];
+
+                let cross = (b.0 - a.0) * (py - a.1) - (b.1 - a.1) * (px - a.0);
+                positive |= cross > 0.0;
+                negative |= cross < 0.0;
+            }
+
+            if !(positive && negative) {
+                plot(data.as_mut(), image_size.0, x, y, color);
+            }
         }
+    }
+}
 
-        if error > 0 {
-            x -= 1;
-            tx += 2;
-            error += tx - d;
-        }
+/// Renders an arc in the given data
+/// Angles are in radians, clockwise from the positive x axis
+pub fn arc(
+    mut data: impl AsMut<[u8]>,
+    image_size: (usize, usize),
+    center: (f64, f64),
+    radius: f64,
+    angles: (f64, f64),
+    color: Color,
+) {
+    // approximate the arc with segments roughly a pixel long
+    let segments = ((radius * (angles.1 - angles.0).abs()).ceil() as usize).max(1);
+    let step = (angles.1 - angles.0) / segments as f64;
+
+    let point = |theta: f64| {
+        (
+            (center.0 + (radius * theta.cos())).floor() as isize,
+            (center.1 + (radius * theta.sin())).floor() as isize,
+        )
+    };
+
+    let mut from = point(angles.0);
+    for segment in 1..=segments {
+        let to = point(angles.0 + step * segment as f64);
+        line(data.as_mut(), image_size, from, to, color);
+
+        from = to;
     }
 }
 
 /// Renders a line in the given data
+/// Any part of the line outside of the image is clipped
 // https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm
 pub fn line(
     mut data: impl AsMut<[u8]>,
     image_size: (usize, usize),
-    x1: usize,
-    y1: usize,
-    x2: usize,
-    y2: usize,
+    (mut x1, mut y1): (isize, isize),
+    (x2, y2): (isize, isize),
     color: Color,
 ) {
-    let mut x1 = x1 as isize;
-    let x2 = x2 as isize;
-    let mut y1 = y1 as isize;
-    let y2 = y2 as isize;
-
     let dx = (x2 - x1).abs();
     let sx = if x1 < x2 { 1 } else { -1 };
     let dy = -((y2 - y1).abs());
@@ -145,7 +174,9 @@ pub fn line(
     let mut err = dx + dy;
 
     loop {
-        plot(data.as_mut(), image_size.0, x1 as usize, y1 as usize, color);
+        if x1 >= 0 && y1 >= 0 && (x1 as usize) < image_size.0 && (y1 as usize) < image_size.1 {
+            plot(data.as_mut(), image_size.0, x1 as usize, y1 as usize, color);
+        }
 
         if x1 == x2 && y1 == y2 {
             break;
@@ -164,7 +195,8 @@ pub fn line(
     }
 }
 
-fn plot(mut data: impl AsMut<[u8]>, image_width: usize, x: usize, y: usize, color: Color) {
+/// Renders a single pixel in the given data
+pub fn plot(mut data: impl AsMut<[u8]>, image_width: usize, x: usize, y: usize, color: Color) {
     let data = data.as_mut();
 
     let index = ((y * image_width) + x) * 4;
@@ -174,17 +206,36 @@ fn plot(mut data: impl AsMut<[u8]>, image_width: usize, x: usize, y: usize, colo
     data[index + 3] = color.a;
 }
 
-/// Random coin flip
-pub fn coin() -> bool {
-    let mut rng = rand::thread_rng();
+/// Seedable RNG used by the generators
+/// ChaCha is used so that a seed produces the same maze on every platform
+pub type MazeRng = ChaCha8Rng;
+
+/// Creates a new generator RNG from the given seed
+pub fn seeded_rng(seed: u64) -> MazeRng {
+    MazeRng::seed_from_u64(seed)
+}
+
+/// Creates an independent RNG stream from the given seed
+/// Parallel generators use one stream per task so that
+/// the result doesn't depend on how the work is scheduled
+pub fn stream_rng(seed: u64, stream: u64) -> MazeRng {
+    let mut rng = seeded_rng(seed);
+    rng.set_stream(stream);
+    rng
+}
 
+/// Returns a new random seed
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+/// Random coin flip
+pub fn coin(rng: &mut dyn RngCore) -> bool {
     rng.gen_range(0..=1) == 0
 }
 
 /// Returns a random item from the given set
-pub fn sample<T>(items: &[T]) -> &T {
-    let mut rng = rand::thread_rng();
-
+pub fn sample<'a, T>(items: &'a [T], rng: &mut dyn RngCore) -> &'a T {
     let index = rng.gen_range(0..items.as_ref().len());
     &items[index]
 }
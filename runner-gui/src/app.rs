@@ -5,11 +5,12 @@ use derivative::Derivative;
 use derive_more::Display;
 use eframe::{egui, epi};
 use strum::{EnumIter, IntoEnumIterator};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use mazecore::generators::*;
+use mazecore::render::{Palette, RenderOptions};
 use mazecore::solvers::*;
-use mazecore::Grid;
+use mazecore::{random_seed, seeded_rng, Grid, Renderable};
 
 use crate::image::Image;
 use crate::texture::Texture;
@@ -48,20 +49,247 @@ pub enum GeneratorType {
 
     #[display(fmt = "Recursive Backtracker")]
     RecursiveBacktracker,
+
+    #[display(fmt = "Simplified Prim's")]
+    SimplifiedPrims,
+
+    #[display(fmt = "True Prim's")]
+    TruePrims,
+
+    #[display(fmt = "Growing Tree")]
+    GrowingTree,
+
+    #[display(fmt = "Kruskal's")]
+    Kruskals,
+
+    #[display(fmt = "Eller's")]
+    Ellers,
+
+    #[display(fmt = "Recursive Division")]
+    RecursiveDivision,
+
+    #[display(fmt = "Houston")]
+    Houston,
+
+    #[display(fmt = "Caves")]
+    Caves,
 }
 
 impl GeneratorType {
-    fn generator(&self) -> Box<dyn Generator> {
+    fn generator(&self, settings: &GeneratorSettings) -> Box<dyn Generator> {
         match self {
-            GeneratorType::None => Box::new(NoneGenerator::default()),
-            GeneratorType::BinaryTree => Box::new(BinaryTree::default()),
-            GeneratorType::BinaryTreeParallel => Box::new(BinaryTreeParallel::default()),
-            GeneratorType::Sidewinder => Box::new(Sidewinder::default()),
-            GeneratorType::SidewinderParallel => Box::new(SidewinderParallel::default()),
-            GeneratorType::AldousBroder => Box::new(AldousBroder::default()),
-            GeneratorType::Wilsons => Box::new(Wilsons::default()),
-            GeneratorType::HuntAndKill => Box::new(HuntAndKill::default()),
-            GeneratorType::RecursiveBacktracker => Box::new(RecursiveBacktracker::default()),
+            GeneratorType::None => Box::new(NoneGenerator),
+            GeneratorType::BinaryTree => {
+                Box::new(BinaryTree::default().with_diagonal(settings.diagonal_type.diagonal()))
+            }
+            GeneratorType::BinaryTreeParallel => Box::new(
+                BinaryTreeParallel::default().with_diagonal(settings.diagonal_type.diagonal()),
+            ),
+            GeneratorType::Sidewinder => Box::new(
+                Sidewinder::default()
+                    .with_edge(settings.edge_type.edge())
+                    .with_close_chance(settings.close_chance),
+            ),
+            GeneratorType::SidewinderParallel => Box::new(
+                SidewinderParallel::default()
+                    .with_edge(settings.edge_type.edge())
+                    .with_close_chance(settings.close_chance),
+            ),
+            GeneratorType::AldousBroder => Box::new(AldousBroder),
+            GeneratorType::Wilsons => Box::new(Wilsons),
+            GeneratorType::HuntAndKill => Box::new(HuntAndKill),
+            GeneratorType::RecursiveBacktracker => Box::new(RecursiveBacktracker),
+            GeneratorType::SimplifiedPrims => Box::new(SimplifiedPrims),
+            GeneratorType::TruePrims => Box::new(TruePrims),
+            GeneratorType::GrowingTree => Box::new(GrowingTree::new(
+                settings.strategy_type.strategy(settings.mix),
+            )),
+            GeneratorType::Kruskals => {
+                Box::new(Kruskals::default().with_random_crossings(settings.crossings))
+            }
+            GeneratorType::Ellers => Box::new(Ellers),
+            GeneratorType::RecursiveDivision => Box::new(
+                RecursiveDivision::default()
+                    .with_min_size(settings.min_size)
+                    .with_rooms(settings.room_size, settings.room_chance),
+            ),
+            GeneratorType::Houston => Box::new(Houston::new(settings.fraction)),
+            GeneratorType::Caves => Box::new(
+                Caves::default()
+                    .with_wall_chance(settings.wall_chance)
+                    .with_steps(settings.steps),
+            ),
+        }
+    }
+}
+
+/// Settings for the generators that have them
+#[derive(Derivative)]
+#[derivative(Default)]
+pub struct GeneratorSettings {
+    diagonal_type: DiagonalType,
+
+    edge_type: EdgeType,
+    #[derivative(Default(value = "0.5"))]
+    close_chance: f64,
+
+    strategy_type: StrategyType,
+    #[derivative(Default(value = "0.5"))]
+    mix: f64,
+
+    crossings: usize,
+
+    #[derivative(Default(value = "1"))]
+    min_size: usize,
+    room_size: usize,
+    #[derivative(Default(value = "0.25"))]
+    room_chance: f64,
+
+    #[derivative(Default(value = "0.5"))]
+    fraction: f64,
+
+    #[derivative(Default(value = "0.45"))]
+    wall_chance: f64,
+    #[derivative(Default(value = "4"))]
+    steps: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, EnumIter, Derivative, Display)]
+#[derivative(Default)]
+pub enum DiagonalType {
+    #[derivative(Default)]
+    #[display(fmt = "North East")]
+    NorthEast,
+
+    #[display(fmt = "North West")]
+    NorthWest,
+
+    #[display(fmt = "South East")]
+    SouthEast,
+
+    #[display(fmt = "South West")]
+    SouthWest,
+}
+
+impl DiagonalType {
+    fn diagonal(&self) -> Diagonal {
+        match self {
+            DiagonalType::NorthEast => Diagonal::NorthEast,
+            DiagonalType::NorthWest => Diagonal::NorthWest,
+            DiagonalType::SouthEast => Diagonal::SouthEast,
+            DiagonalType::SouthWest => Diagonal::SouthWest,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, EnumIter, Derivative, Display)]
+#[derivative(Default)]
+pub enum EdgeType {
+    #[derivative(Default)]
+    #[display(fmt = "North")]
+    North,
+
+    #[display(fmt = "East")]
+    East,
+
+    #[display(fmt = "South")]
+    South,
+
+    #[display(fmt = "West")]
+    West,
+}
+
+impl EdgeType {
+    fn edge(&self) -> SidewinderEdge {
+        match self {
+            EdgeType::North => SidewinderEdge::North,
+            EdgeType::East => SidewinderEdge::East,
+            EdgeType::South => SidewinderEdge::South,
+            EdgeType::West => SidewinderEdge::West,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, EnumIter, Derivative, Display)]
+#[derivative(Default)]
+pub enum StrategyType {
+    #[derivative(Default)]
+    #[display(fmt = "Newest")]
+    Newest,
+
+    #[display(fmt = "Random")]
+    Random,
+
+    #[display(fmt = "Oldest")]
+    Oldest,
+
+    #[display(fmt = "Mixed")]
+    Mixed,
+}
+
+impl StrategyType {
+    fn strategy(&self, mix: f64) -> GrowingTreeStrategy {
+        match self {
+            StrategyType::Newest => GrowingTreeStrategy::Newest,
+            StrategyType::Random => GrowingTreeStrategy::Random,
+            StrategyType::Oldest => GrowingTreeStrategy::Oldest,
+            StrategyType::Mixed => GrowingTreeStrategy::Mixed(mix),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, EnumIter, Derivative, Display)]
+#[derivative(Default)]
+pub enum GridType {
+    #[derivative(Default)]
+    #[display(fmt = "Orthogonal")]
+    Orthogonal,
+
+    #[display(fmt = "Polar")]
+    Polar,
+
+    #[display(fmt = "Hex")]
+    Hex,
+
+    #[display(fmt = "Triangle")]
+    Triangle,
+
+    #[display(fmt = "Weave")]
+    Weave,
+
+    #[display(fmt = "Cylinder")]
+    Cylinder,
+
+    #[display(fmt = "Torus")]
+    Torus,
+
+    #[display(fmt = "Möbius")]
+    Mobius,
+
+    #[display(fmt = "Layered")]
+    Layered,
+
+    #[display(fmt = "Cube")]
+    Cube,
+
+    #[display(fmt = "Sphere")]
+    Sphere,
+}
+
+impl GridType {
+    fn grid_type(&self, levels: usize) -> mazecore::GridType {
+        match self {
+            GridType::Orthogonal => mazecore::GridType::Orthogonal,
+            GridType::Polar => mazecore::GridType::Polar,
+            GridType::Hex => mazecore::GridType::Hex,
+            GridType::Triangle => mazecore::GridType::Triangle,
+            GridType::Weave => mazecore::GridType::Weave,
+            GridType::Cylinder => mazecore::GridType::Cylinder,
+            GridType::Torus => mazecore::GridType::Torus,
+            GridType::Mobius => mazecore::GridType::Mobius,
+            GridType::Layered => mazecore::GridType::Layered(levels),
+            GridType::Cube => mazecore::GridType::Cube,
+            GridType::Sphere => mazecore::GridType::Sphere,
         }
     }
 }
@@ -78,10 +306,103 @@ pub enum SolverType {
 }
 
 impl SolverType {
-    fn solver(&self, grid: Grid, root_row: usize, root_col: usize) -> Box<dyn Solver> {
+    fn solver(
+        &self,
+        grid: Grid,
+        root_row: usize,
+        root_col: usize,
+        settings: &SolverSettings,
+    ) -> Box<dyn Solver> {
         match self {
             SolverType::None => Box::new(NoneSolver::new(grid)),
-            SolverType::Djikstra => Box::new(Djikstra::new(grid, root_row, root_col)),
+            SolverType::Djikstra => Box::new(
+                Djikstra::new(grid, root_row, root_col)
+                    .with_palette(
+                        settings
+                            .palette_type
+                            .palette(settings.bands, settings.period),
+                    )
+                    .with_origin(
+                        settings
+                            .origin_type
+                            .origin(settings.origin_row, settings.origin_col),
+                    ),
+            ),
+        }
+    }
+}
+
+/// Settings for the solvers that have them
+#[derive(Derivative)]
+#[derivative(Default)]
+pub struct SolverSettings {
+    palette_type: PaletteType,
+    #[derivative(Default(value = "8"))]
+    bands: usize,
+    #[derivative(Default(value = "10"))]
+    period: usize,
+
+    origin_type: OriginType,
+    origin_row: usize,
+    origin_col: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, EnumIter, Derivative, Display)]
+#[derivative(Default)]
+pub enum PaletteType {
+    #[derivative(Default)]
+    #[display(fmt = "Green")]
+    Green,
+
+    #[display(fmt = "Viridis")]
+    Viridis,
+
+    #[display(fmt = "Magma")]
+    Magma,
+
+    #[display(fmt = "Banded")]
+    Banded,
+
+    #[display(fmt = "Cyclic")]
+    Cyclic,
+}
+
+impl PaletteType {
+    fn palette(&self, bands: usize, period: usize) -> Palette {
+        match self {
+            PaletteType::Green => Palette::default(),
+            PaletteType::Viridis => Palette::viridis(),
+            PaletteType::Magma => Palette::magma(),
+            PaletteType::Banded => Palette::Banded(Box::new(Palette::viridis()), bands),
+            PaletteType::Cyclic => Palette::Cyclic(Box::new(Palette::rainbow()), period),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, EnumIter, Derivative, Display)]
+#[derivative(Default)]
+pub enum OriginType {
+    #[derivative(Default)]
+    #[display(fmt = "Center")]
+    Center,
+
+    #[display(fmt = "Start")]
+    Start,
+
+    #[display(fmt = "Goal")]
+    Goal,
+
+    #[display(fmt = "Cell")]
+    Cell,
+}
+
+impl OriginType {
+    fn origin(&self, row: usize, col: usize) -> DistanceOrigin {
+        match self {
+            OriginType::Center => DistanceOrigin::Center,
+            OriginType::Start => DistanceOrigin::Start,
+            OriginType::Goal => DistanceOrigin::Goal,
+            OriginType::Cell => DistanceOrigin::Cell(row, col),
         }
     }
 }
@@ -95,12 +416,27 @@ pub struct RunnerApp {
     #[derivative(Default(value = "20"))]
     height: usize,
 
-    #[derivative(Default(value = "false"))]
-    polar: bool,
+    #[derivative(Default(value = "3"))]
+    levels: usize,
 
+    #[derivative(Default(value = "true"))]
+    random_seed: bool,
+    seed: String,
+
+    grid_type: GridType,
     generator_type: GeneratorType,
     solver_type: SolverType,
 
+    settings: GeneratorSettings,
+    solver_settings: SolverSettings,
+    show_solution: bool,
+    braid: f64,
+
+    animate: bool,
+    #[derivative(Default(value = "10"))]
+    steps_per_frame: usize,
+    animation: Option<Steps>,
+
     maze_renderable: Option<Box<dyn Solver>>,
     dead_ends: usize,
     generate_time: f64,
@@ -128,6 +464,88 @@ impl RunnerApp {
             });
     }
 
+    fn add_grids_select(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("Grid Type")
+            .selected_text(format!("{}", self.grid_type))
+            .width(150.0)
+            .show_ui(ui, |ui| {
+                for grid_type in GridType::iter() {
+                    RunnerApp::add_selection(grid_type, ui, &mut self.grid_type);
+                }
+            });
+    }
+
+    fn add_generator_settings(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.settings;
+        match self.generator_type {
+            GeneratorType::BinaryTree | GeneratorType::BinaryTreeParallel => {
+                egui::ComboBox::from_label("Diagonal")
+                    .selected_text(format!("{}", settings.diagonal_type))
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        for diagonal_type in DiagonalType::iter() {
+                            RunnerApp::add_selection(
+                                diagonal_type,
+                                ui,
+                                &mut settings.diagonal_type,
+                            );
+                        }
+                    });
+            }
+            GeneratorType::Sidewinder | GeneratorType::SidewinderParallel => {
+                egui::ComboBox::from_label("Edge")
+                    .selected_text(format!("{}", settings.edge_type))
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        for edge_type in EdgeType::iter() {
+                            RunnerApp::add_selection(edge_type, ui, &mut settings.edge_type);
+                        }
+                    });
+
+                ui.add(
+                    egui::Slider::new(&mut settings.close_chance, 0.0..=1.0).text("Close Chance"),
+                );
+            }
+            GeneratorType::GrowingTree => {
+                egui::ComboBox::from_label("Strategy")
+                    .selected_text(format!("{}", settings.strategy_type))
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        for strategy_type in StrategyType::iter() {
+                            RunnerApp::add_selection(
+                                strategy_type,
+                                ui,
+                                &mut settings.strategy_type,
+                            );
+                        }
+                    });
+
+                if settings.strategy_type == StrategyType::Mixed {
+                    ui.add(egui::Slider::new(&mut settings.mix, 0.0..=1.0).text("Mix (Newest)"));
+                }
+            }
+            GeneratorType::Kruskals if self.grid_type == GridType::Weave => {
+                ui.add(egui::Slider::new(&mut settings.crossings, 0..=1000).text("Crossings"));
+            }
+            GeneratorType::RecursiveDivision => {
+                ui.add(egui::Slider::new(&mut settings.min_size, 1..=10).text("Min Size"));
+                ui.add(egui::Slider::new(&mut settings.room_size, 0..=20).text("Room Size"));
+                ui.add(egui::Slider::new(&mut settings.room_chance, 0.0..=1.0).text("Room Chance"));
+            }
+            GeneratorType::Houston => {
+                ui.add(
+                    egui::Slider::new(&mut settings.fraction, 0.0..=1.0)
+                        .text("Aldous-Broder Fraction"),
+                );
+            }
+            GeneratorType::Caves => {
+                ui.add(egui::Slider::new(&mut settings.wall_chance, 0.0..=1.0).text("Wall Chance"));
+                ui.add(egui::Slider::new(&mut settings.steps, 0..=10).text("Steps"));
+            }
+            _ => (),
+        }
+    }
+
     fn add_solvers_select(&mut self, ui: &mut egui::Ui) {
         egui::ComboBox::from_label("Maze Solver")
             .selected_text(format!("{}", self.solver_type))
@@ -139,18 +557,108 @@ impl RunnerApp {
             });
     }
 
+    fn add_solver_settings(&mut self, ui: &mut egui::Ui) {
+        if self.solver_type != SolverType::Djikstra {
+            return;
+        }
+
+        let settings = &mut self.solver_settings;
+
+        egui::ComboBox::from_label("Palette")
+            .selected_text(format!("{}", settings.palette_type))
+            .width(150.0)
+            .show_ui(ui, |ui| {
+                for palette_type in PaletteType::iter() {
+                    RunnerApp::add_selection(palette_type, ui, &mut settings.palette_type);
+                }
+            });
+        match settings.palette_type {
+            PaletteType::Banded => {
+                ui.add(egui::Slider::new(&mut settings.bands, 1..=32).text("Bands"));
+            }
+            PaletteType::Cyclic => {
+                ui.add(egui::Slider::new(&mut settings.period, 1..=100).text("Period"));
+            }
+            _ => (),
+        }
+
+        egui::ComboBox::from_label("Distance Origin")
+            .selected_text(format!("{}", settings.origin_type))
+            .width(150.0)
+            .show_ui(ui, |ui| {
+                for origin_type in OriginType::iter() {
+                    RunnerApp::add_selection(origin_type, ui, &mut settings.origin_type);
+                }
+            });
+        if settings.origin_type == OriginType::Cell {
+            ui.add(
+                egui::Slider::new(&mut settings.origin_row, 0..=self.height.saturating_sub(1))
+                    .text("Origin Row"),
+            );
+            ui.add(
+                egui::Slider::new(&mut settings.origin_col, 0..=self.width.saturating_sub(1))
+                    .text("Origin Column"),
+            );
+        }
+    }
+
+    fn add_seed(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.random_seed, "Random Seed");
+            ui.add_enabled(
+                !self.random_seed,
+                egui::TextEdit::singleline(&mut self.seed).desired_width(150.0),
+            );
+            ui.label("Seed");
+        });
+    }
+
     fn add_generate_button(&mut self, ui: &mut egui::Ui, frame: &mut epi::Frame<'_>) {
         // TODO: make this async / threaded and disable the button while generating
 
         if ui.button("Generate Maze").clicked() {
-            info!("Generating {}x{} maze ...", self.width, self.height);
+            let seed = if self.random_seed {
+                random_seed()
+            } else {
+                match self.seed.trim().parse() {
+                    Ok(seed) => seed,
+                    Err(_) => {
+                        warn!("Invalid seed {:?}", self.seed);
+                        return;
+                    }
+                }
+            };
 
-            let generator = self.generator_type.generator();
+            // show the seed so that the maze can be reproduced
+            self.seed = seed.to_string();
+
+            let grid_type = self.grid_type.grid_type(self.levels);
+            if let Err(err) = grid_type.check_size(self.height, self.width) {
+                warn!("{}", err);
+                return;
+            }
+
+            let generator = self.generator_type.generator(&self.settings);
+            let mut grid = Grid::new(self.height, self.width, grid_type);
+            if !generator.supports(&grid) {
+                warn!("{} does not support {} grids", generator.name(), grid_type);
+                return;
+            }
+
+            info!(
+                "Generating {}x{} maze (seed={}) ...",
+                self.width, self.height, seed
+            );
+
+            let mut rng = seeded_rng(seed);
             let grid = {
                 info!("Running maze generator {} ...", generator.name());
 
                 let now = Instant::now();
-                let grid = generator.generate(self.height, self.width, self.polar);
+                generator.run(&mut grid, &mut rng);
+                if self.braid > 0.0 {
+                    grid.braid(self.braid, &mut rng);
+                }
                 self.generate_time = now.elapsed().as_secs_f64() * 1000.0;
 
                 grid
@@ -167,7 +675,9 @@ impl RunnerApp {
                 (root, goal)
             };
 
-            let solver = self.solver_type.solver(grid, root.0, root.1);
+            let solver = self
+                .solver_type
+                .solver(grid, root.0, root.1, &self.solver_settings);
             {
                 info!(
                     "Running solver {} from {:?} to {:?} ...",
@@ -184,21 +694,54 @@ impl RunnerApp {
             //println!("\n{}\n", solver.render_ascii());
 
             // render the maze texture
-            let (size, pixels) = solver.render(25, true);
+            let (size, pixels) = solver.render(&self.render_options(), true);
             let image = Image::from_pixels(size, pixels);
             self.maze_texture.load(frame, &image);
 
             self.dead_ends = solver.grid().get_dead_ends().len();
             self.maze_renderable = Some(solver);
+
+            // replay the generator carving the maze before showing the finished one
+            if self.animate {
+                let grid = Grid::new(self.height, self.width, grid_type);
+                self.animation = Some(generator.steps(grid, &mut seeded_rng(seed)));
+            }
         }
     }
 
+    fn update_animation(&mut self, ctx: &egui::CtxRef, frame: &mut epi::Frame<'_>) {
+        let options = self.render_options();
+        let steps = match &mut self.animation {
+            Some(steps) => steps,
+            None => return,
+        };
+
+        let (size, pixels) = if steps.by_ref().take(self.steps_per_frame).count() > 0 {
+            steps.grid().render(&options, true)
+        } else {
+            self.animation = None;
+            match &self.maze_renderable {
+                Some(solver) => solver.render(&options, true),
+                None => return,
+            }
+        };
+
+        let image = Image::from_pixels(size, pixels);
+        self.maze_texture.load(frame, &image);
+
+        ctx.request_repaint();
+    }
+
+    fn render_options(&self) -> RenderOptions {
+        RenderOptions::default().with_solution(self.show_solution)
+    }
+
     fn add_save_button(&self, ui: &mut egui::Ui) {
         if ui.button("Save Maze").clicked() {
             /*let filename = ...;
             info!("Saving to {:?} ...", filename.as_ref());
 
-            if let Err(err) = renderable.save_png(filename.as_ref(), 50) {
+            if let Err(err) = renderable.save_png(filename.as_ref(), &RenderOptions::default().with_cell_size(50)) {
                 error!("{}", err);
             }*/
         }
@@ -231,12 +774,31 @@ impl epi::App for RunnerApp {
 
     fn update(&mut self, ctx: &egui::CtxRef, frame: &mut epi::Frame<'_>) {
         egui::CentralPanel::default().show(ctx, |ui| {
+            self.add_grids_select(ui);
             self.add_generators_select(ui);
+            self.add_generator_settings(ui);
             self.add_solvers_select(ui);
-
-            ui.add(egui::Slider::new(&mut self.width, 1..=500).text("Width"));
-            ui.add(egui::Slider::new(&mut self.height, 1..=500).text("Height"));
-            ui.checkbox(&mut self.polar, "Polar");
+            self.add_solver_settings(ui);
+            ui.checkbox(&mut self.show_solution, "Show Solution");
+
+            // some grid types fall apart when they're too small
+            let (min_rows, min_cols) = self.grid_type.grid_type(self.levels).min_size();
+            self.width = self.width.max(min_cols);
+            self.height = self.height.max(min_rows);
+            ui.add(egui::Slider::new(&mut self.width, min_cols..=500).text("Width"));
+            ui.add(egui::Slider::new(&mut self.height, min_rows..=500).text("Height"));
+            if self.grid_type == GridType::Layered {
+                ui.add(egui::Slider::new(&mut self.levels, 1..=10).text("Levels"));
+            }
+            ui.add(egui::Slider::new(&mut self.braid, 0.0..=1.0).text("Braid"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.animate, "Animate");
+                ui.add_enabled(
+                    self.animate,
+                    egui::Slider::new(&mut self.steps_per_frame, 1..=100).text("Steps Per Frame"),
+                );
+            });
+            self.add_seed(ui);
 
             ui.horizontal(|ui| {
                 self.add_generate_button(ui, frame);
@@ -257,5 +819,7 @@ impl epi::App for RunnerApp {
 
             // TODO: fix the maze scroll area height and move the buttons down here
         });
+
+        self.update_animation(ctx, frame);
     }
 }
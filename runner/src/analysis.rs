@@ -3,17 +3,25 @@ use std::collections::HashMap;
 use tracing::info;
 
 use mazecore::generators::*;
+use mazecore::{GridType, MazeRng};
 
-pub fn run(rows: usize, cols: usize, tries: usize) {
+pub fn run(rows: usize, cols: usize, tries: usize, rng: &mut MazeRng) {
     let mut generators: Vec<Box<dyn Generator>> = vec![
         Box::new(BinaryTree::default()),
         Box::new(BinaryTreeParallel::default()),
         Box::new(Sidewinder::default()),
         Box::new(SidewinderParallel::default()),
-        Box::new(AldousBroder::default()),
-        Box::new(Wilsons::default()),
-        Box::new(HuntAndKill::default()),
-        Box::new(RecursiveBacktracker::default()),
+        Box::new(AldousBroder),
+        Box::new(Wilsons),
+        Box::new(HuntAndKill),
+        Box::new(RecursiveBacktracker),
+        Box::new(SimplifiedPrims),
+        Box::new(TruePrims),
+        Box::new(Kruskals::default()),
+        Box::new(Ellers),
+        Box::new(RecursiveDivision::default()),
+        Box::new(Houston::default()),
+        Box::new(Caves::default()),
     ];
 
     // TODO: run timing analysis
@@ -26,7 +34,7 @@ pub fn run(rows: usize, cols: usize, tries: usize) {
 
         let mut deadend_counts = Vec::new();
         for _ in 0..tries {
-            let grid = generator.generate(rows, cols, false);
+            let grid = generator.generate(rows, cols, GridType::Orthogonal, rng);
             deadend_counts.push(grid.get_dead_ends().len());
         }
 
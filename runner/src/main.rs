@@ -1,14 +1,17 @@
 mod analysis;
 mod options;
 
-use std::path::Path;
+use std::fs;
+use std::io::{self, BufWriter};
 use std::time::Instant;
 
 use tracing::{debug, info, Level};
 use tracing_subscriber::FmtSubscriber;
 
+use mazecore::animation::Animation;
+use mazecore::generators::{EllersRows, Steps};
 use mazecore::solvers::Solver;
-use mazecore::{Grid, Mask};
+use mazecore::{random_seed, seeded_rng, stream};
 
 use options::Options;
 
@@ -24,13 +27,84 @@ fn init_logging() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn render(solver: &dyn Solver, filename: &Option<impl AsRef<Path>>) -> anyhow::Result<()> {
+fn render(solver: &dyn Solver, options: &Options) -> anyhow::Result<()> {
     println!("\n{}\n", solver.render_ascii());
 
-    if let Some(filename) = filename {
-        info!("Saving to {:?} ...", filename.as_ref());
+    if let Some(filename) = &options.filename {
+        info!("Saving to {:?} ...", filename);
 
-        solver.save_png(filename.as_ref(), 25)?;
+        let svg = filename
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+        if svg {
+            solver.save_svg(filename, &options.render_options())?;
+        } else {
+            solver.save_png(filename, &options.render_options())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn animate(
+    options: &Options,
+    steps: Steps,
+    solver: &dyn Solver,
+    root: (usize, usize),
+) -> anyhow::Result<()> {
+    let filename = match &options.animation {
+        Some(filename) => filename,
+        None => return Ok(()),
+    };
+
+    let animation = Animation::default()
+        .with_render_options(options.render_options())
+        .with_frames_per_step(options.frames_per_step)
+        .with_delay(options.delay)
+        .with_loops(options.loops);
+
+    let gif = filename
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+    if gif {
+        info!("Saving animation to {:?} ...", filename);
+
+        animation.save_gif(filename, steps, solver, root)?;
+    } else {
+        info!("Saving animation frames next to {:?} ...", filename);
+
+        let frames = animation.save_png_frames(filename, steps, solver, root)?;
+        info!("Saved {} frames", frames);
+    }
+
+    Ok(())
+}
+
+fn render_stream(options: &Options, seed: u64) -> anyhow::Result<()> {
+    let (rows, cols) = (options.height, options.width);
+
+    // the maze isn't kept around, so regenerate it from the seed for each output
+    if !options.norender {
+        println!();
+        stream::write_ascii(
+            rows,
+            cols,
+            EllersRows::new(rows, cols, &mut seeded_rng(seed)),
+            BufWriter::new(io::stdout().lock()),
+        )?;
+        println!();
+    }
+
+    if let Some(filename) = &options.filename {
+        info!("Saving to {:?} ...", filename);
+
+        stream::write_png(
+            rows,
+            cols,
+            &options.render_options(),
+            EllersRows::new(rows, cols, &mut seeded_rng(seed)),
+            BufWriter::new(fs::File::create(filename)?),
+        )?;
     }
 
     Ok(())
@@ -42,35 +116,73 @@ fn main() -> anyhow::Result<()> {
     let options: Options = argh::from_env();
     options.validate()?;
 
+    // print the seed so that the maze can be reproduced
+    let seed = options.seed.unwrap_or_else(random_seed);
+    info!("Seed: {}", seed);
+    let mut rng = seeded_rng(seed);
+
     if options.generator.is_analysis() {
-        analysis::run(options.width, options.height, 100);
+        analysis::run(options.width, options.height, 100, &mut rng);
+        return Ok(());
+    }
+
+    if options.generator.is_stream() {
+        info!(
+            "Streaming {}x{} maze (generator={}) ...",
+            options.height, options.width, options.generator
+        );
+
+        let now = Instant::now();
+        render_stream(&options, seed)?;
+        info!("{:.2}ms", now.elapsed().as_secs_f64() * 1000.0);
+
         return Ok(());
     }
 
     let generator = options.generator.generator();
     let mask = options.generator.mask();
+    let mut steps = None;
     let grid = {
         info!(
-            "Generating {}x{} maze (mask={:?}) (polar={}) ...",
+            "Generating {}x{} maze (mask={:?}) (grid={}) ...",
             options.height,
             options.width,
             mask,
-            options.generator.is_polar()
+            options.generator.grid_type()
         );
 
-        let mut grid = if let Some(mask_path) = mask {
-            let mask = Mask::from_file(mask_path)?;
-            Grid::from_mask(mask, options.generator.is_polar())
-        } else {
-            Grid::new(options.height, options.width, options.generator.is_polar())
-        };
+        let mut grid = options.grid()?;
 
         info!("Running maze generator {} ...", generator.name());
 
         let now = Instant::now();
-        generator.run(&mut grid);
+        if options.animation.is_some() {
+            // keep each step to replay, this carves the same maze run would
+            let carved = generator.steps(grid.clone(), &mut rng);
+            grid = carved.clone().finish();
+            steps = Some(carved);
+        } else {
+            generator.run(&mut grid, &mut rng);
+        }
         info!("{:.2}ms", now.elapsed().as_secs_f64() * 1000.0);
 
+        if let Some(cull) = options.generator.cull() {
+            info!("Culling dead ends ({}) ...", cull);
+
+            let now = Instant::now();
+            grid.cull(cull, &mut rng);
+            info!("{:.2}ms", now.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        let braid = options.generator.braid();
+        if braid > 0.0 {
+            info!("Braiding {} of the dead ends ...", braid);
+
+            let now = Instant::now();
+            grid.braid(braid, &mut rng);
+            info!("{:.2}ms", now.elapsed().as_secs_f64() * 1000.0);
+        }
+
         grid
     };
     debug!("{:?}", grid);
@@ -102,7 +214,13 @@ fn main() -> anyhow::Result<()> {
     }
 
     if !options.norender {
-        render(&*solver, &options.filename)?;
+        render(&*solver, &options)?;
+    }
+
+    if let Some(steps) = steps {
+        let now = Instant::now();
+        animate(&options, steps, &*solver, root)?;
+        info!("{:.2}ms", now.elapsed().as_secs_f64() * 1000.0);
     }
 
     Ok(())
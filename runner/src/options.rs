@@ -1,11 +1,13 @@
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use argh::FromArgs;
 use derive_more::Display;
 
 use mazecore::generators::*;
+use mazecore::render::{Color, Palette, RenderOptions};
 use mazecore::solvers::*;
-use mazecore::Grid;
+use mazecore::{CellHandle, CullLimit, Grid, GridType, Mask};
 
 // TODO: all of this would be cleaner with macros
 
@@ -32,10 +34,110 @@ pub enum GeneratorOption {
 
     #[display(fmt = "Recursive Backtracker")]
     RecursiveBacktracker(RecursiveBacktrackerGenerator),
+
+    #[display(fmt = "Simplified Prim's")]
+    SimplifiedPrims(SimplifiedPrimsGenerator),
+
+    #[display(fmt = "True Prim's")]
+    TruePrims(TruePrimsGenerator),
+
+    #[display(fmt = "Growing Tree")]
+    GrowingTree(GrowingTreeGenerator),
+
+    #[display(fmt = "Kruskal's")]
+    Kruskals(KruskalsGenerator),
+
+    #[display(fmt = "Eller's")]
+    Ellers(EllersGenerator),
+
+    #[display(fmt = "Recursive Division")]
+    RecursiveDivision(RecursiveDivisionGenerator),
+
+    #[display(fmt = "Houston")]
+    Houston(HoustonGenerator),
+
+    #[display(fmt = "Caves")]
+    Caves(CavesGenerator),
 }
 
 impl GeneratorOption {
     pub fn validate(&self) -> anyhow::Result<()> {
+        if let GeneratorOption::Sidewinder(generator) = self {
+            if generator.grid == GridType::Polar
+                && matches!(generator.edge, SidewinderEdge::East | SidewinderEdge::West)
+            {
+                anyhow::bail!(
+                    "{} does not support the {} edge on polar grids",
+                    self,
+                    generator.edge
+                );
+            }
+
+            if !(0.0..=1.0).contains(&generator.close_chance) {
+                anyhow::bail!(
+                    "Invalid close chance {}, must be in [0, 1]",
+                    generator.close_chance
+                );
+            }
+        }
+
+        if let GeneratorOption::Ellers(generator) = self {
+            if generator.stream && generator.solver.is_some() {
+                anyhow::bail!("Streamed mazes cannot be solved");
+            }
+
+            if generator.stream && generator.braid > 0.0 {
+                anyhow::bail!("Streamed mazes cannot be braided");
+            }
+
+            if generator.stream && generator.cull.is_some() {
+                anyhow::bail!("Streamed mazes cannot be culled");
+            }
+        }
+
+        if !(0.0..=1.0).contains(&self.braid()) {
+            anyhow::bail!("Invalid braid {}, must be in [0, 1]", self.braid());
+        }
+
+        if let Some(CullLimit::Remaining(fraction)) = self.cull() {
+            if !(0.0..=1.0).contains(&fraction) {
+                anyhow::bail!(
+                    "Invalid cull percentage {}, must be in [0, 100]",
+                    fraction * 100.0
+                );
+            }
+        }
+
+        if let GeneratorOption::RecursiveDivision(generator) = self {
+            if !(0.0..=1.0).contains(&generator.room_chance) {
+                anyhow::bail!(
+                    "Invalid room chance {}, must be in [0, 1]",
+                    generator.room_chance
+                );
+            }
+        }
+
+        if let GeneratorOption::GrowingTree(generator) = self {
+            if !(0.0..=1.0).contains(&generator.mix) {
+                anyhow::bail!("Invalid mix {}, must be in [0, 1]", generator.mix);
+            }
+        }
+
+        if let GeneratorOption::Houston(generator) = self {
+            if !(0.0..=1.0).contains(&generator.fraction) {
+                anyhow::bail!("Invalid fraction {}, must be in [0, 1]", generator.fraction);
+            }
+        }
+
+        if let GeneratorOption::Caves(generator) = self {
+            if !(0.0..=1.0).contains(&generator.wall_chance) {
+                anyhow::bail!(
+                    "Invalid wall chance {}, must be in [0, 1]",
+                    generator.wall_chance
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -43,6 +145,10 @@ impl GeneratorOption {
         matches!(self, GeneratorOption::Analysis(_))
     }
 
+    pub fn is_stream(&self) -> bool {
+        matches!(self, GeneratorOption::Ellers(generator) if generator.stream)
+    }
+
     pub fn mask(&self) -> Option<PathBuf> {
         match self {
             GeneratorOption::Analysis(_) => None,
@@ -52,42 +158,135 @@ impl GeneratorOption {
             GeneratorOption::Wilsons(generator) => generator.mask.clone(),
             GeneratorOption::HuntAndKill(generator) => generator.mask.clone(),
             GeneratorOption::RecursiveBacktracker(generator) => generator.mask.clone(),
+            GeneratorOption::SimplifiedPrims(generator) => generator.mask.clone(),
+            GeneratorOption::TruePrims(generator) => generator.mask.clone(),
+            GeneratorOption::GrowingTree(generator) => generator.mask.clone(),
+            GeneratorOption::Kruskals(generator) => generator.mask.clone(),
+            GeneratorOption::Ellers(_) => None,
+            GeneratorOption::RecursiveDivision(_) => None,
+            GeneratorOption::Houston(generator) => generator.mask.clone(),
+            GeneratorOption::Caves(generator) => generator.mask.clone(),
         }
     }
 
-    pub fn is_polar(&self) -> bool {
+    pub fn grid_type(&self) -> GridType {
         match self {
-            GeneratorOption::Analysis(_) => false,
-            GeneratorOption::BinaryTree(generator) => generator.polar,
-            GeneratorOption::Sidewinder(generator) => generator.polar,
-            GeneratorOption::AldousBroder(generator) => generator.polar,
-            GeneratorOption::Wilsons(generator) => generator.polar,
-            GeneratorOption::HuntAndKill(generator) => generator.polar,
-            GeneratorOption::RecursiveBacktracker(generator) => generator.polar,
+            GeneratorOption::Analysis(_) => GridType::Orthogonal,
+            GeneratorOption::BinaryTree(generator) => generator.grid,
+            GeneratorOption::Sidewinder(generator) => generator.grid,
+            GeneratorOption::AldousBroder(generator) => generator.grid,
+            GeneratorOption::Wilsons(generator) => generator.grid,
+            GeneratorOption::HuntAndKill(generator) => generator.grid,
+            GeneratorOption::RecursiveBacktracker(generator) => generator.grid,
+            GeneratorOption::SimplifiedPrims(generator) => generator.grid,
+            GeneratorOption::TruePrims(generator) => generator.grid,
+            GeneratorOption::GrowingTree(generator) => generator.grid,
+            GeneratorOption::Kruskals(generator) => generator.grid,
+            GeneratorOption::Ellers(_) => GridType::Orthogonal,
+            GeneratorOption::RecursiveDivision(_) => GridType::Orthogonal,
+            GeneratorOption::Houston(generator) => generator.grid,
+            GeneratorOption::Caves(_) => GridType::Orthogonal,
         }
     }
 
     pub fn generator(&self) -> Box<dyn Generator> {
         match self {
-            GeneratorOption::Analysis(_) => Box::new(NoneGenerator::default()),
+            GeneratorOption::Analysis(_) => Box::new(NoneGenerator),
             GeneratorOption::BinaryTree(generator) => {
                 if generator.parallel {
-                    Box::new(BinaryTreeParallel::default())
+                    Box::new(BinaryTreeParallel::default().with_diagonal(generator.diagonal))
                 } else {
-                    Box::new(BinaryTree::default())
+                    Box::new(BinaryTree::default().with_diagonal(generator.diagonal))
                 }
             }
             GeneratorOption::Sidewinder(generator) => {
                 if generator.parallel {
-                    Box::new(SidewinderParallel::default())
+                    Box::new(
+                        SidewinderParallel::default()
+                            .with_edge(generator.edge)
+                            .with_close_chance(generator.close_chance),
+                    )
                 } else {
-                    Box::new(Sidewinder::default())
+                    Box::new(
+                        Sidewinder::default()
+                            .with_edge(generator.edge)
+                            .with_close_chance(generator.close_chance),
+                    )
                 }
             }
-            GeneratorOption::AldousBroder(_) => Box::new(AldousBroder::default()),
-            GeneratorOption::Wilsons(_) => Box::new(Wilsons::default()),
-            GeneratorOption::HuntAndKill(_) => Box::new(HuntAndKill::default()),
-            GeneratorOption::RecursiveBacktracker(_) => Box::new(RecursiveBacktracker::default()),
+            GeneratorOption::AldousBroder(_) => Box::new(AldousBroder),
+            GeneratorOption::Wilsons(_) => Box::new(Wilsons),
+            GeneratorOption::HuntAndKill(_) => Box::new(HuntAndKill),
+            GeneratorOption::RecursiveBacktracker(_) => Box::new(RecursiveBacktracker),
+            GeneratorOption::SimplifiedPrims(_) => Box::new(SimplifiedPrims),
+            GeneratorOption::TruePrims(_) => Box::new(TruePrims),
+            GeneratorOption::GrowingTree(generator) => {
+                Box::new(GrowingTree::new(generator.strategy.strategy(generator.mix)))
+            }
+            GeneratorOption::Kruskals(generator) => {
+                let mut kruskals =
+                    Kruskals::default().with_random_crossings(generator.random_crossings);
+                for link in &generator.link {
+                    kruskals = kruskals.with_link(link.0.into(), link.1.into());
+                }
+                for crossing in &generator.crossing {
+                    kruskals = kruskals.with_crossing((*crossing).into());
+                }
+                Box::new(kruskals)
+            }
+            GeneratorOption::Ellers(_) => Box::new(Ellers),
+            GeneratorOption::RecursiveDivision(generator) => Box::new(
+                RecursiveDivision::default()
+                    .with_min_size(generator.min_size)
+                    .with_rooms(generator.room_size, generator.room_chance),
+            ),
+            GeneratorOption::Houston(generator) => Box::new(Houston::new(generator.fraction)),
+            GeneratorOption::Caves(generator) => Box::new(
+                Caves::default()
+                    .with_rules(generator.rules)
+                    .with_wall_chance(generator.wall_chance)
+                    .with_steps(generator.steps),
+            ),
+        }
+    }
+
+    pub fn braid(&self) -> f64 {
+        match self {
+            GeneratorOption::Analysis(_) => 0.0,
+            GeneratorOption::BinaryTree(generator) => generator.braid,
+            GeneratorOption::Sidewinder(generator) => generator.braid,
+            GeneratorOption::AldousBroder(generator) => generator.braid,
+            GeneratorOption::Wilsons(generator) => generator.braid,
+            GeneratorOption::HuntAndKill(generator) => generator.braid,
+            GeneratorOption::RecursiveBacktracker(generator) => generator.braid,
+            GeneratorOption::SimplifiedPrims(generator) => generator.braid,
+            GeneratorOption::TruePrims(generator) => generator.braid,
+            GeneratorOption::GrowingTree(generator) => generator.braid,
+            GeneratorOption::Kruskals(generator) => generator.braid,
+            GeneratorOption::Ellers(generator) => generator.braid,
+            GeneratorOption::RecursiveDivision(generator) => generator.braid,
+            GeneratorOption::Houston(generator) => generator.braid,
+            GeneratorOption::Caves(generator) => generator.braid,
+        }
+    }
+
+    pub fn cull(&self) -> Option<CullLimit> {
+        match self {
+            GeneratorOption::Analysis(_) => None,
+            GeneratorOption::BinaryTree(generator) => generator.cull,
+            GeneratorOption::Sidewinder(generator) => generator.cull,
+            GeneratorOption::AldousBroder(generator) => generator.cull,
+            GeneratorOption::Wilsons(generator) => generator.cull,
+            GeneratorOption::HuntAndKill(generator) => generator.cull,
+            GeneratorOption::RecursiveBacktracker(generator) => generator.cull,
+            GeneratorOption::SimplifiedPrims(generator) => generator.cull,
+            GeneratorOption::TruePrims(generator) => generator.cull,
+            GeneratorOption::GrowingTree(generator) => generator.cull,
+            GeneratorOption::Kruskals(generator) => generator.cull,
+            GeneratorOption::Ellers(generator) => generator.cull,
+            GeneratorOption::RecursiveDivision(generator) => generator.cull,
+            GeneratorOption::Houston(generator) => generator.cull,
+            GeneratorOption::Caves(generator) => generator.cull,
         }
     }
 
@@ -118,6 +317,38 @@ impl GeneratorOption {
                 .solver
                 .clone()
                 .unwrap_or(SolverOption::None(NoneSolver {})),
+            GeneratorOption::SimplifiedPrims(generator) => generator
+                .solver
+                .clone()
+                .unwrap_or(SolverOption::None(NoneSolver {})),
+            GeneratorOption::TruePrims(generator) => generator
+                .solver
+                .clone()
+                .unwrap_or(SolverOption::None(NoneSolver {})),
+            GeneratorOption::GrowingTree(generator) => generator
+                .solver
+                .clone()
+                .unwrap_or(SolverOption::None(NoneSolver {})),
+            GeneratorOption::Kruskals(generator) => generator
+                .solver
+                .clone()
+                .unwrap_or(SolverOption::None(NoneSolver {})),
+            GeneratorOption::Ellers(generator) => generator
+                .solver
+                .clone()
+                .unwrap_or(SolverOption::None(NoneSolver {})),
+            GeneratorOption::RecursiveDivision(generator) => generator
+                .solver
+                .clone()
+                .unwrap_or(SolverOption::None(NoneSolver {})),
+            GeneratorOption::Houston(generator) => generator
+                .solver
+                .clone()
+                .unwrap_or(SolverOption::None(NoneSolver {})),
+            GeneratorOption::Caves(generator) => generator
+                .solver
+                .clone()
+                .unwrap_or(SolverOption::None(NoneSolver {})),
         }
     }
 }
@@ -139,9 +370,21 @@ pub struct BinaryTreeGenerator {
     #[argh(switch)]
     pub parallel: bool,
 
-    /// use a polar grid
-    #[argh(switch)]
-    pub polar: bool,
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// diagonal the maze leans toward (northeast, northwest, southeast, southwest)
+    #[argh(option, default = "Diagonal::NorthEast")]
+    pub diagonal: Diagonal,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -156,9 +399,25 @@ pub struct SidewinderGenerator {
     #[argh(switch)]
     pub parallel: bool,
 
-    /// use a polar grid
-    #[argh(switch)]
-    pub polar: bool,
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// edge the runs go along (north, east, south, west)
+    #[argh(option, default = "SidewinderEdge::North")]
+    pub edge: SidewinderEdge,
+
+    /// chance of closing out a run after each cell
+    #[argh(option, default = "0.5")]
+    pub close_chance: f64,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -173,9 +432,17 @@ pub struct AldousBroderGenerator {
     #[argh(option)]
     pub mask: Option<PathBuf>,
 
-    /// use a polar grid
-    #[argh(switch)]
-    pub polar: bool,
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -190,9 +457,17 @@ pub struct WilsonsGenerator {
     #[argh(option)]
     pub mask: Option<PathBuf>,
 
-    /// use a polar grid
-    #[argh(switch)]
-    pub polar: bool,
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -207,9 +482,17 @@ pub struct HuntAndKillGenerator {
     #[argh(option)]
     pub mask: Option<PathBuf>,
 
-    /// use a polar grid
-    #[argh(switch)]
-    pub polar: bool,
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -224,9 +507,326 @@ pub struct RecursiveBacktrackerGenerator {
     #[argh(option)]
     pub mask: Option<PathBuf>,
 
-    /// use a polar grid
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Simplified Prim's generator
+#[argh(subcommand, name = "simplifiedprims")]
+pub struct SimplifiedPrimsGenerator {
+    /// solver to run
+    #[argh(subcommand)]
+    pub solver: Option<SolverOption>,
+
+    /// mask the grid with the given file
+    #[argh(option)]
+    pub mask: Option<PathBuf>,
+
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// True Prim's generator
+#[argh(subcommand, name = "trueprims")]
+pub struct TruePrimsGenerator {
+    /// solver to run
+    #[argh(subcommand)]
+    pub solver: Option<SolverOption>,
+
+    /// mask the grid with the given file
+    #[argh(option)]
+    pub mask: Option<PathBuf>,
+
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Growing Tree generator
+#[argh(subcommand, name = "growingtree")]
+pub struct GrowingTreeGenerator {
+    /// solver to run
+    #[argh(subcommand)]
+    pub solver: Option<SolverOption>,
+
+    /// mask the grid with the given file
+    #[argh(option)]
+    pub mask: Option<PathBuf>,
+
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// active cell selection strategy (newest, random, oldest, mixed)
+    #[argh(option, default = "GrowingTreeStrategyOption::Newest")]
+    pub strategy: GrowingTreeStrategyOption,
+
+    /// probability of selecting the newest cell with the mixed strategy
+    #[argh(option, default = "0.5")]
+    pub mix: f64,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(PartialEq, Debug, Display, Copy, Clone)]
+pub enum GrowingTreeStrategyOption {
+    #[display(fmt = "newest")]
+    Newest,
+
+    #[display(fmt = "random")]
+    Random,
+
+    #[display(fmt = "oldest")]
+    Oldest,
+
+    #[display(fmt = "mixed")]
+    Mixed,
+}
+
+impl GrowingTreeStrategyOption {
+    pub fn strategy(&self, mix: f64) -> GrowingTreeStrategy {
+        match self {
+            GrowingTreeStrategyOption::Newest => GrowingTreeStrategy::Newest,
+            GrowingTreeStrategyOption::Random => GrowingTreeStrategy::Random,
+            GrowingTreeStrategyOption::Oldest => GrowingTreeStrategy::Oldest,
+            GrowingTreeStrategyOption::Mixed => GrowingTreeStrategy::Mixed(mix),
+        }
+    }
+}
+
+impl FromStr for GrowingTreeStrategyOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newest" => Ok(Self::Newest),
+            "random" => Ok(Self::Random),
+            "oldest" => Ok(Self::Oldest),
+            "mixed" => Ok(Self::Mixed),
+            _ => Err(format!("Invalid strategy: {}", s)),
+        }
+    }
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Kruskal's generator
+#[argh(subcommand, name = "kruskals")]
+pub struct KruskalsGenerator {
+    /// solver to run
+    #[argh(subcommand)]
+    pub solver: Option<SolverOption>,
+
+    /// mask the grid with the given file
+    #[argh(option)]
+    pub mask: Option<PathBuf>,
+
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// link two neighboring cells before generating (row,col:row,col)
+    #[argh(option)]
+    pub link: Vec<LinkOption>,
+
+    /// place a weave grid crossing before generating (row,col)
+    #[argh(option)]
+    pub crossing: Vec<CellOption>,
+
+    /// number of random weave grid crossings to try placing before generating
+    #[argh(option, default = "0")]
+    pub random_crossings: usize,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Eller's generator
+#[argh(subcommand, name = "ellers")]
+pub struct EllersGenerator {
+    /// solver to run
+    #[argh(subcommand)]
+    pub solver: Option<SolverOption>,
+
+    /// stream the maze out row by row without keeping it in memory
     #[argh(switch)]
-    pub polar: bool,
+    pub stream: bool,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Recursive division generator
+#[argh(subcommand, name = "recursivedivision")]
+pub struct RecursiveDivisionGenerator {
+    /// solver to run
+    #[argh(subcommand)]
+    pub solver: Option<SolverOption>,
+
+    /// regions this many cells tall or wide or smaller are not divided
+    #[argh(option, default = "1")]
+    pub min_size: usize,
+
+    /// regions smaller than this in both directions may be left as rooms
+    #[argh(option, default = "0")]
+    pub room_size: usize,
+
+    /// chance of leaving a small region as a room
+    #[argh(option, default = "0.25")]
+    pub room_chance: f64,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Houston generator
+#[argh(subcommand, name = "houston")]
+pub struct HoustonGenerator {
+    /// solver to run
+    #[argh(subcommand)]
+    pub solver: Option<SolverOption>,
+
+    /// mask the grid with the given file
+    #[argh(option)]
+    pub mask: Option<PathBuf>,
+
+    /// grid type (orthogonal, polar, hex, triangle, weave, cylinder, torus, mobius, layered[:levels], cube, sphere)
+    #[argh(option, default = "GridType::Orthogonal")]
+    pub grid: GridType,
+
+    /// fraction of the cells to visit with Aldous-Broder before switching to Wilson's
+    #[argh(option, default = "0.5")]
+    pub fraction: f64,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Cellular automaton cave generator
+#[argh(subcommand, name = "caves")]
+pub struct CavesGenerator {
+    /// solver to run
+    #[argh(subcommand)]
+    pub solver: Option<SolverOption>,
+
+    /// mask the grid with the given file
+    #[argh(option)]
+    pub mask: Option<PathBuf>,
+
+    /// automaton rules as the wall counts that create and keep walls (B678/S345678)
+    #[argh(option, default = "CaveRules::default()")]
+    pub rules: CaveRules,
+
+    /// chance of each cell starting as a wall
+    #[argh(option, default = "0.45")]
+    pub wall_chance: f64,
+
+    /// number of automaton generations to run
+    #[argh(option, default = "4")]
+    pub steps: usize,
+
+    /// fraction of dead ends to remove by adding loops
+    #[argh(option, default = "0.0")]
+    pub braid: f64,
+
+    /// cull dead ends this many times, or until a percentage of the cells remain (percent%)
+    #[argh(option)]
+    pub cull: Option<CullLimit>,
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct CellOption(usize, usize);
+
+impl From<CellOption> for CellHandle {
+    fn from(cell: CellOption) -> Self {
+        CellHandle::new(cell.0, cell.1)
+    }
+}
+
+impl FromStr for CellOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |v: Option<&str>| v.and_then(|v| v.trim().parse().ok());
+
+        let mut parts = s.split(',');
+        match (parse(parts.next()), parse(parts.next()), parts.next()) {
+            (Some(row), Some(col), None) => Ok(Self(row, col)),
+            _ => Err(format!("Invalid cell: {}", s)),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct LinkOption(CellOption, CellOption);
+
+impl FromStr for LinkOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((a, b)) => Ok(Self(a.parse()?, b.parse()?)),
+            None => Err(format!("Invalid link: {}", s)),
+        }
+    }
 }
 
 #[derive(FromArgs, PartialEq, Debug, Display, Clone)]
@@ -243,7 +843,11 @@ impl SolverOption {
     pub fn solver(&self, grid: Grid, root_row: usize, root_col: usize) -> Box<dyn Solver> {
         match self {
             SolverOption::None(_) => Box::new(mazecore::solvers::NoneSolver::new(grid)),
-            SolverOption::Djikstra(_) => Box::new(Djikstra::new(grid, root_row, root_col)),
+            SolverOption::Djikstra(solver) => Box::new(
+                Djikstra::new(grid, root_row, root_col)
+                    .with_palette(solver.palette.clone())
+                    .with_origin(solver.origin),
+            ),
         }
     }
 }
@@ -256,7 +860,15 @@ pub struct NoneSolver {}
 #[derive(FromArgs, PartialEq, Debug, Clone)]
 /// Simple Djikstra's algorithm solver
 #[argh(subcommand, name = "djikstra")]
-pub struct DjikstraSolver {}
+pub struct DjikstraSolver {
+    /// heatmap palette (green, hue:color, viridis, magma, rainbow, gradient:color,color,..., bands:count[:palette], cyclic:period[:palette])
+    #[argh(option, default = "Palette::default()")]
+    pub palette: Palette,
+
+    /// cell the heatmap distances are measured from (center, start, goal, row,col)
+    #[argh(option, default = "DistanceOrigin::Center")]
+    pub origin: DistanceOrigin,
+}
 
 /// Maze runner
 #[derive(FromArgs, Debug)]
@@ -277,15 +889,200 @@ pub struct Options {
     #[argh(switch)]
     pub norender: bool,
 
-    /// filename to render to
+    /// filename to render to (.svg for vector output, otherwise PNG)
     #[argh(option)]
     pub filename: Option<PathBuf>,
+
+    /// rendered cell size in pixels
+    #[argh(option, default = "25")]
+    pub cell_size: usize,
+
+    /// rendered wall thickness in pixels
+    #[argh(option, default = "1")]
+    pub wall_thickness: usize,
+
+    /// inset of the passages from the cell walls as a fraction of the cell size
+    /// (square cells only)
+    #[argh(option, default = "0.0")]
+    pub inset: f64,
+
+    /// margin around the outside walls in pixels
+    #[argh(option, default = "1")]
+    pub margin: usize,
+
+    /// wall color (#rrggbb or #rrggbbaa)
+    #[argh(option)]
+    pub wall_color: Option<Color>,
+
+    /// color of everything that isn't a passage (transparent if not given)
+    #[argh(option)]
+    pub background_color: Option<Color>,
+
+    /// color of passages that aren't colored by the solver
+    #[argh(option)]
+    pub path_color: Option<Color>,
+
+    /// draw the solved route over the maze
+    #[argh(switch)]
+    pub solution: bool,
+
+    /// rendered solution thickness in pixels
+    #[argh(option, default = "3")]
+    pub solution_thickness: usize,
+
+    /// solution color
+    #[argh(option)]
+    pub solution_color: Option<Color>,
+
+    /// color of the solution start marker
+    #[argh(option)]
+    pub start_color: Option<Color>,
+
+    /// color of the solution goal marker
+    #[argh(option)]
+    pub goal_color: Option<Color>,
+
+    /// seed for the maze generator (random if not given)
+    #[argh(option)]
+    pub seed: Option<u64>,
+
+    /// animate the maze being carved and solved to a GIF (.gif) or numbered PNG frames
+    #[argh(option)]
+    pub animation: Option<PathBuf>,
+
+    /// animation frames to show each step for (fractions skip steps)
+    #[argh(option, default = "1.0")]
+    pub frames_per_step: f64,
+
+    /// animation frame delay in milliseconds
+    #[argh(option, default = "50")]
+    pub delay: u32,
+
+    /// number of times to play the animation (0 loops forever)
+    #[argh(option, default = "0")]
+    pub loops: u16,
 }
 
 impl Options {
     pub fn validate(&self) -> anyhow::Result<()> {
         self.generator.validate()?;
 
+        // masks bring their own size
+        if self.generator.mask().is_none() {
+            if let Err(err) = self
+                .generator
+                .grid_type()
+                .check_size(self.height, self.width)
+            {
+                anyhow::bail!(err);
+            }
+        }
+
+        // streamed mazes are never built as a grid
+        if !self.generator.is_stream() {
+            let grid = self.grid()?;
+            if !self.generator.generator().supports(&grid) {
+                if self.generator.mask().is_some() {
+                    anyhow::bail!(
+                        "{} does not support masked {} grids",
+                        self.generator,
+                        self.generator.grid_type()
+                    );
+                }
+
+                anyhow::bail!(
+                    "{} does not support {} grids",
+                    self.generator,
+                    self.generator.grid_type()
+                );
+            }
+        }
+
+        if self.animation.is_some() && self.generator.is_stream() {
+            anyhow::bail!("Streamed mazes cannot be animated");
+        }
+
+        if !self.frames_per_step.is_finite() || self.frames_per_step <= 0.0 {
+            anyhow::bail!(
+                "Invalid frames per step {}, must be greater than 0",
+                self.frames_per_step
+            );
+        }
+
+        if self.cell_size == 0 {
+            anyhow::bail!("Invalid cell size 0, must be greater than 0");
+        }
+
+        if self.wall_thickness == 0 || self.wall_thickness >= self.cell_size {
+            anyhow::bail!(
+                "Invalid wall thickness {}, must be in [1, {})",
+                self.wall_thickness,
+                self.cell_size
+            );
+        }
+
+        if !(0.0..=0.4).contains(&self.inset) {
+            anyhow::bail!("Invalid inset {}, must be in [0, 0.4]", self.inset);
+        }
+
+        if self.solution
+            && (self.solution_thickness == 0 || self.solution_thickness >= self.cell_size)
+        {
+            anyhow::bail!(
+                "Invalid solution thickness {}, must be in [1, {})",
+                self.solution_thickness,
+                self.cell_size
+            );
+        }
+
         Ok(())
     }
+
+    /// Creates the empty grid to generate the maze on
+    pub fn grid(&self) -> anyhow::Result<Grid> {
+        let grid = if let Some(mask_path) = self.generator.mask() {
+            let mask = Mask::from_file(mask_path)?;
+            Grid::from_mask(mask, self.generator.grid_type())
+        } else {
+            Grid::new(self.height, self.width, self.generator.grid_type())
+        };
+
+        Ok(grid)
+    }
+
+    pub fn render_options(&self) -> RenderOptions {
+        let mut options = RenderOptions::default()
+            .with_cell_size(self.cell_size)
+            .with_wall_thickness(self.wall_thickness)
+            .with_inset(self.inset)
+            .with_margin(self.margin)
+            .with_solution(self.solution)
+            .with_solution_thickness(self.solution_thickness);
+
+        if let Some(wall_color) = self.wall_color {
+            options = options.with_wall_color(wall_color);
+        }
+
+        if let Some(background_color) = self.background_color {
+            options = options.with_background_color(background_color);
+        }
+
+        if let Some(path_color) = self.path_color {
+            options = options.with_path_color(path_color);
+        }
+
+        if let Some(solution_color) = self.solution_color {
+            options = options.with_solution_color(solution_color);
+        }
+
+        if let Some(start_color) = self.start_color {
+            options = options.with_start_color(start_color);
+        }
+
+        if let Some(goal_color) = self.goal_color {
+            options = options.with_goal_color(goal_color);
+        }
+
+        options
+    }
 }